@@ -0,0 +1,100 @@
+use std::fs::{read_to_string, write};
+
+use crate::solutions::registry::{Part, Task};
+
+// Confirmed answers are recorded per day in `src/solutions/dayXX/answers.txt`.
+// Each non-empty line that does not start with '#' contains the part, the base
+// name of the input file, and the expected answer, separated by whitespace,
+// e.g. "a sample 142". Answers are compared against the string representation
+// of the primary value of a solution, so text answers work the same as numbers.
+
+/* --------------------------------- Answer --------------------------------- */
+
+pub struct Answer {
+    pub part: Part,
+    pub input: String,
+    pub value: String
+}
+
+impl Answer {
+    fn parse(line: &str) -> Option<Answer> {
+        let mut fields: std::str::SplitWhitespace<'_> = line.split_whitespace();
+        let part: Part = fields.next().and_then(|field| field.chars().next()).and_then(Part::parse)?;
+        let input: String = String::from(fields.next()?);
+        let value: String = fields.collect::<Vec<&str>>().join(" ");
+
+        if value.is_empty() {
+            return None;
+        }
+
+        Some(Answer { part, input, value })
+    }
+
+    fn format(&self) -> String {
+        format!("{} {} {}", self.part, self.input, self.value)
+    }
+}
+
+/* --------------------------------- Answers -------------------------------- */
+
+pub struct Answers {
+    day: u8,
+    comments: Vec<String>,
+    pub entries: Vec<Answer>
+}
+
+impl Answers {
+    fn filename(day: u8) -> String {
+        format!("{}/src/solutions/day{:02}/answers.txt", env!("CARGO_MANIFEST_DIR"), day)
+    }
+
+    pub fn load(day: u8) -> Answers {
+        let content: String = read_to_string(Answers::filename(day)).unwrap_or_default();
+
+        let comments: Vec<String> = content.lines()
+            .filter(|line| line.starts_with('#'))
+            .map(String::from).collect();
+
+        let entries: Vec<Answer> = content.lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| match Answer::parse(line) {
+                Some(answer) => answer,
+                None => panic!("Invalid line '{line}' in {}", Answers::filename(day))
+            }).collect();
+
+        Answers { day, comments, entries }
+    }
+
+    pub fn get(&self, task: &Task, input: &str) -> Option<&str> {
+        self.entries.iter()
+            .find(|answer| answer.part == task.part && answer.input == input)
+            .map(|answer| answer.value.as_str())
+    }
+
+    // Records an answer, replacing the previous answer for the same part and
+    // input if there was one, and writes the sorted list back to the file below
+    // any existing comments. Returns the previously recorded answer, if any.
+
+    pub fn record(&mut self, task: &Task, input: &str, value: &str) -> Result<Option<String>, String> {
+        let previous: Option<String> = self.get(task, input).map(String::from);
+        self.entries.retain(|answer| answer.part != task.part || answer.input != input);
+        self.entries.push(Answer { part: task.part, input: String::from(input), value: String::from(value) });
+        self.entries.sort_by(|a, b| (a.part, &a.input).cmp(&(b.part, &b.input)));
+
+        let mut lines: Vec<String> = self.comments.clone();
+
+        if lines.is_empty() {
+            lines.push(String::from("# part input answer"));
+        }
+
+        lines.extend(self.entries.iter().map(|answer| answer.format()));
+
+        let filename: String = Answers::filename(self.day);
+
+        match write(&filename, lines.join("\n") + "\n") {
+            Ok(_) => Ok(previous),
+            Err(_) => Err(format!("Failed to write file '{filename}'"))
+        }
+    }
+}
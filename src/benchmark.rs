@@ -0,0 +1,349 @@
+use std::fs::{read_to_string, write};
+use std::time::Duration;
+
+use crate::profiler::Report;
+use crate::solutions::registry::Task;
+use crate::table::Cell;
+
+// Machine-readable output of runs and profiles, plus baselines. Runs and profiles
+// can be printed as JSON or CSV instead of text. All durations are written as
+// whole nanoseconds, in fields ending in "_ns". In CSV, profiles take one row
+// per phase ("parse" and "solve"), so that all statistics fit in flat columns.
+//
+// A baseline is simply a profile in CSV format saved to a file. The `compare`
+// command profiles every task in a baseline again, and compares the median time
+// (of both phases combined) to the baseline; tasks whose median changed by more
+// than the threshold (a percentage of the baseline) are flagged as regressed or
+// improved. The median is used because it is much less sensitive to outliers
+// than the mean, which matters when comparing runs made at different times.
+
+/* -------------------------------- Constants ------------------------------- */
+
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+const CSV_HEADER: &str = "task,input,phase,warmup_runs,batch_size,samples,min_ns,median_ns,mean_ns,p95_ns,stddev_ns,low_outliers,high_outliers";
+
+/* --------------------------------- Format --------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "csv"  => Some(Format::Csv),
+            _ => None
+        }
+    }
+}
+
+/* --------------------------------- Profile -------------------------------- */
+
+// The profiled parse and solve phases of a single solution.
+
+pub struct Profile {
+    pub parse: Report,
+    pub solve: Report
+}
+
+impl Profile {
+    pub fn mean(&self) -> Duration {
+        self.parse.mean + self.solve.mean
+    }
+
+    pub fn median(&self) -> Duration {
+        self.parse.median + self.solve.median
+    }
+
+    fn phases(&self) -> [(&'static str, &Report); 2] {
+        [("parse", &self.parse), ("solve", &self.solve)]
+    }
+}
+
+/* ---------------------------------- Entry --------------------------------- */
+
+// The result of profiling one task on one input. Tasks that could not be
+// profiled (e.g. because they are unfinished or their input is missing) keep
+// the table cell describing why; they are included in JSON with a status, but
+// left out of CSV, since CSV rows only describe actual measurements.
+
+pub struct Entry {
+    pub task: Task,
+    pub input: String,
+    pub outcome: Result<Profile, Cell>
+}
+
+impl Entry {
+    pub fn cell(&self) -> Cell {
+        match &self.outcome {
+            Ok(profile) => Cell::Time(profile.mean()),
+            Err(cell) => *cell
+        }
+    }
+
+    fn status(&self) -> &'static str {
+        match &self.outcome {
+            Ok(_) => "ok",
+            Err(cell) => cell.status()
+        }
+    }
+}
+
+/* ---------------------------------- Run ----------------------------------- */
+
+// A single (non-profiled) run of a solution. The solution is its primary
+// answer; any other labelled values of the solution are kept separately, and
+// written as separate fields (in a "values" object in JSON, and as additional
+// columns named after their labels in CSV).
+
+pub struct Run<'a> {
+    pub task: Task,
+    pub input: &'a str,
+    pub solved: bool,
+    pub solution: String,
+    pub values: Vec<(&'static str, String)>,
+    pub parse: Duration,
+    pub solve: Duration
+}
+
+pub fn format_run(run: &Run, format: Format) -> String {
+    match format {
+        Format::Text => String::new(),
+        Format::Json => {
+            let values: Vec<String> = run.values.iter()
+                .map(|(label, value)| format!("{}: {}", json_string(label), json_string(value))).collect();
+
+            format!(
+                "{{\"task\": {}, \"input\": {}, \"solved\": {}, \"solution\": {}, \"values\": {{{}}}, \"parse_ns\": {}, \"solve_ns\": {}}}",
+                json_string(&run.task.to_string()), json_string(run.input), run.solved, json_string(&run.solution),
+                values.join(", "), run.parse.as_nanos(), run.solve.as_nanos())
+        },
+        Format::Csv => {
+            let mut header: Vec<String> = ["task", "input", "solved", "solution", "parse_ns", "solve_ns"].map(String::from).to_vec();
+            let mut row: Vec<String> = vec![run.task.to_string(), csv_field(run.input), run.solved.to_string(),
+                csv_field(&run.solution), run.parse.as_nanos().to_string(), run.solve.as_nanos().to_string()];
+
+            for (label, value) in &run.values {
+                header.push(csv_field(label));
+                row.push(csv_field(value));
+            }
+
+            [header.join(","), row.join(",")].join("\n")
+        }
+    }
+}
+
+/* ------------------------------- Formatting ------------------------------- */
+
+fn json_string(value: &str) -> String {
+    let mut result: String = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"'  => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c)
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        String::from(value)
+    }
+}
+
+fn report_json(report: &Report) -> String {
+    format!(
+        "{{\"warmup_runs\": {}, \"batch_size\": {}, \"samples\": {}, \"min_ns\": {}, \"median_ns\": {}, \"mean_ns\": {}, \
+        \"p95_ns\": {}, \"stddev_ns\": {}, \"low_outliers\": {}, \"high_outliers\": {}}}",
+        report.warmup_runs, report.batch_size, report.samples, report.min.as_nanos(), report.median.as_nanos(),
+        report.mean.as_nanos(), report.p95.as_nanos(), report.stddev.as_nanos(), report.low_outliers, report.high_outliers)
+}
+
+fn report_csv(task: &Task, input: &str, phase: &str, report: &Report) -> String {
+    format!("{},{},{},{},{},{},{},{},{},{},{},{},{}",
+        task, csv_field(input), phase, report.warmup_runs, report.batch_size, report.samples,
+        report.min.as_nanos(), report.median.as_nanos(), report.mean.as_nanos(), report.p95.as_nanos(),
+        report.stddev.as_nanos(), report.low_outliers, report.high_outliers)
+}
+
+pub fn entry_json(entry: &Entry) -> String {
+    let phases: String = match &entry.outcome {
+        Ok(profile) => profile.phases().iter()
+            .map(|(phase, report)| format!(", \"{}\": {}", phase, report_json(report)))
+            .collect(),
+        Err(_) => String::new()
+    };
+
+    format!("{{\"task\": {}, \"input\": {}, \"status\": \"{}\"{}}}",
+        json_string(&entry.task.to_string()), json_string(&entry.input), entry.status(), phases)
+}
+
+pub fn format_entries(entries: &[Entry], format: Format) -> String {
+    match format {
+        Format::Text => String::new(),
+        Format::Json => {
+            let objects: Vec<String> = entries.iter().map(|entry| format!("  {}", entry_json(entry))).collect();
+            format!("[\n{}\n]", objects.join(",\n"))
+        },
+        Format::Csv => {
+            let mut lines: Vec<String> = vec![String::from(CSV_HEADER)];
+
+            for entry in entries {
+                if let Ok(profile) = &entry.outcome {
+                    lines.extend(profile.phases().iter()
+                        .map(|(phase, report)| report_csv(&entry.task, &entry.input, phase, report)));
+                }
+            }
+
+            lines.join("\n")
+        }
+    }
+}
+
+/* -------------------------------- Baseline -------------------------------- */
+
+pub struct BaselineEntry {
+    pub task: Task,
+    pub input: String,
+    pub median: Duration
+}
+
+pub fn save_baseline(path: &str, entries: &[Entry]) -> Result<(), String> {
+    match write(path, format_entries(entries, Format::Csv) + "\n") {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("Failed to write file '{path}'"))
+    }
+}
+
+// Splits a CSV line into fields, handling quoted fields as written by `csv_field()`.
+
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields: Vec<String> = vec![String::new()];
+    let mut quoted: bool = false;
+    let mut chars: std::iter::Peekable<std::str::Chars<'_>> = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => { chars.next(); fields.last_mut().unwrap().push('"'); },
+            ('"', _) => quoted = !quoted,
+            (',', false) => fields.push(String::new()),
+            (c, _) => fields.last_mut().unwrap().push(c)
+        }
+    }
+
+    fields
+}
+
+// Loads a baseline, summing the medians of the phases of every task and input
+// (in the order in which they first appear in the file).
+
+pub fn load_baseline(path: &str) -> Result<Vec<BaselineEntry>, String> {
+    let content: String = match read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return Err(format!("Failed to read file '{path}'"))
+    };
+
+    let mut lines: std::str::Lines<'_> = content.lines();
+    let header: Vec<String> = lines.next().map(split_csv).unwrap_or_default();
+    let column = |name: &str| header.iter().position(|field| field == name)
+        .ok_or_else(|| format!("Baseline '{path}' has no '{name}' column"));
+
+    let (task_column, input_column, median_column) = (column("task")?, column("input")?, column("median_ns")?);
+    let mut entries: Vec<BaselineEntry> = Vec::new();
+
+    for (index, line) in lines.enumerate().filter(|(_, line)| !line.is_empty()) {
+        let fields: Vec<String> = split_csv(line);
+        let invalid = || format!("Invalid line {} in baseline '{path}'", index + 2);
+
+        let task: Task = fields.get(task_column).and_then(|field| Task::parse(field)).ok_or_else(invalid)?;
+        let input: &String = fields.get(input_column).ok_or_else(invalid)?;
+        let median: u64 = fields.get(median_column).and_then(|field| field.parse().ok()).ok_or_else(invalid)?;
+
+        match entries.iter_mut().find(|entry| entry.task == task && entry.input == *input) {
+            Some(entry) => entry.median += Duration::from_nanos(median),
+            None => entries.push(BaselineEntry { task, input: input.clone(), median: Duration::from_nanos(median) })
+        }
+    }
+
+    Ok(entries)
+}
+
+/* ------------------------------- Comparison ------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Unchanged,
+    Improved,
+    Regressed
+}
+
+// Returns the relative change of the current median compared to the baseline,
+// as a percentage, and whether it exceeds the threshold in either direction.
+
+pub fn compare(baseline: Duration, current: Duration, threshold: f64) -> (f64, Change) {
+    let baseline_nanos: f64 = baseline.as_nanos().max(1) as f64;
+    let percentage: f64 = 100.0 * (current.as_nanos() as f64 - baseline_nanos) / baseline_nanos;
+
+    let change: Change = if percentage > threshold {
+        Change::Regressed
+    } else if percentage < -threshold {
+        Change::Improved
+    } else {
+        Change::Unchanged
+    };
+
+    (percentage, change)
+}
+
+/* ---------------------------------- Tests --------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solutions::registry::Part;
+
+    fn run(values: Vec<(&'static str, String)>) -> Run<'static> {
+        Run {
+            task: Task { day: 17, part: Part::A },
+            input: "sample",
+            solved: true,
+            solution: String::from("102"),
+            values,
+            parse: Duration::from_nanos(10),
+            solve: Duration::from_nanos(20)
+        }
+    }
+
+    #[test]
+    fn run_values_are_separate_fields() {
+        let run: Run = run(vec![("expanded", String::from("773")), ("route", String::from("> 2, v 1"))]);
+
+        assert_eq!(format_run(&run, Format::Json),
+            "{\"task\": \"17a\", \"input\": \"sample\", \"solved\": true, \"solution\": \"102\", \
+            \"values\": {\"expanded\": \"773\", \"route\": \"> 2, v 1\"}, \"parse_ns\": 10, \"solve_ns\": 20}");
+        assert_eq!(format_run(&run, Format::Csv),
+            "task,input,solved,solution,parse_ns,solve_ns,expanded,route\n17a,sample,true,102,10,20,773,\"> 2, v 1\"");
+    }
+
+    #[test]
+    fn run_without_values() {
+        let run: Run = run(Vec::new());
+        assert!(format_run(&run, Format::Json).contains("\"values\": {}"));
+        assert_eq!(format_run(&run, Format::Csv), "task,input,solved,solution,parse_ns,solve_ns\n17a,sample,true,102,10,20");
+    }
+}
@@ -0,0 +1,114 @@
+use std::env;
+use std::fs::read_to_string;
+use std::io::{read_to_string as read_stream, stdin};
+use std::path::{Path, PathBuf};
+
+// Resolves the `<input>` argument of the runner to the lines of an input file.
+// The argument can be one of the following, tried in this order:
+//
+// - A single dash, in which case the input is read from stdin.
+// - A path to a file, recognized by containing a path separator or ending in
+//   ".txt"; relative paths are resolved against the working directory.
+// - The base name of an input file of the day, e.g. "input" or "sample". By
+//   default these are read from `src/solutions/dayXX/input/` in the crate
+//   directory, so that the runner also works from other working directories.
+//   If the `ADVENT2023_INPUTS` environment variable is set, they are instead
+//   read from `$ADVENT2023_INPUTS/dayXX/`.
+// - The base name of one of the sample inputs, which are embedded in the binary
+//   and used as a fallback if the corresponding file cannot be found.
+
+/* -------------------------------- Constants ------------------------------- */
+
+pub const INPUTS_VARIABLE: &str = "ADVENT2023_INPUTS";
+
+/* --------------------------------- Samples -------------------------------- */
+
+macro_rules! samples {
+    ($( $day:literal $module:literal [ $( $name:literal ),+ ] ; )+) => {
+        fn embedded_sample(day: u8, name: &str) -> Option<&'static str> {
+            match (day, name) {
+                $( $( ($day, $name) => Some(include_str!(
+                    concat!("solutions/", $module, "/input/", $name, ".txt"))), )+ )+
+                _ => None
+            }
+        }
+    };
+}
+
+samples! {
+     1 "day01" ["sample", "sample_b"];
+     2 "day02" ["sample"];
+     3 "day03" ["sample"];
+     4 "day04" ["sample"];
+     5 "day05" ["sample"];
+     6 "day06" ["sample"];
+     7 "day07" ["sample"];
+     8 "day08" ["sample", "sample_b"];
+     9 "day09" ["sample"];
+    10 "day10" ["sample", "sample_b"];
+    11 "day11" ["sample"];
+    12 "day12" ["sample"];
+    13 "day13" ["sample"];
+    14 "day14" ["sample"];
+    15 "day15" ["sample"];
+    16 "day16" ["sample"];
+    17 "day17" ["sample", "sample_b"];
+    18 "day18" ["sample"];
+    19 "day19" ["sample"];
+    20 "day20" ["sample", "sample_b", "sample_rx"];
+    21 "day21" ["sample"];
+    22 "day22" ["sample"];
+    23 "day23" ["sample"];
+    24 "day24" ["sample"];
+    25 "day25" ["sample"];
+}
+
+/* --------------------------------- Helpers -------------------------------- */
+
+fn is_path(input: &str) -> bool {
+    input.contains(['/', '\\']) || input.ends_with(".txt")
+}
+
+// Returns true if the input argument refers to an input by its base name, i.e.
+// if it is neither a path nor stdin. Only such inputs can have recorded answers.
+
+pub fn is_named(input: &str) -> bool {
+    input != "-" && !is_path(input)
+}
+
+fn named_input_path(day: u8, name: &str) -> PathBuf {
+    match env::var_os(INPUTS_VARIABLE) {
+        Some(root) => Path::new(&root).join(format!("day{:02}", day)).join(format!("{name}.txt")),
+        None => Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join(format!("src/solutions/day{:02}/input/{name}.txt", day))
+    }
+}
+
+fn to_lines(content: &str) -> Vec<String> {
+    content.lines().map(String::from).collect()
+}
+
+/* ---------------------------------- Input --------------------------------- */
+
+pub fn read_lines(day: u8, input: &str) -> Result<Vec<String>, String> {
+    if input == "-" {
+        return match read_stream(stdin()) {
+            Ok(content) => Ok(to_lines(&content)),
+            Err(_) => Err(String::from("Failed to read input from stdin"))
+        };
+    }
+
+    let path: PathBuf = if is_path(input) { PathBuf::from(input) } else { named_input_path(day, input) };
+
+    if let Ok(content) = read_to_string(&path) {
+        return Ok(to_lines(&content));
+    }
+
+    if !is_path(input) {
+        if let Some(content) = embedded_sample(day, input) {
+            return Ok(to_lines(content));
+        }
+    }
+
+    Err(format!("Failed to read file '{}'", path.display()))
+}
@@ -0,0 +1,6 @@
+// The solutions and their shared helpers form a library, which the runner in
+// `main.rs` uses. Helpers in the shared modules (such as `grid`, `geometry` and
+// `number`) are part of its public interface, so they are available to every
+// day (and covered by their own tests) even if no day currently uses them.
+
+pub mod solutions;
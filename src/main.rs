@@ -1,94 +1,844 @@
+use std::any::Any;
 use std::env;
-use std::fs::read_to_string;
-use std::ops::Div;
+use std::fs;
+use std::hint::black_box;
 use std::process::exit;
 use std::time::Duration;
 use std::time::Instant;
 
-mod solutions;
+use advent2023::solutions::{self, Solution};
+use solutions::day16::common::{Beam, Contraption};
+use solutions::day16::visualizer::{self, Visualizer};
+use solutions::day17::{self, common::{Rules, Search}};
+use solutions::day20::common::{Network, Pulse, PulseValue};
+use solutions::day20::simulator::{self, Simulator};
+use solutions::grid::Grid;
+use solutions::parse::ParseError;
+use solutions::registry::{Part, Puzzle, Registry, Status, Task, FIRST_DAY, LAST_DAY};
+use answers::Answers;
+use benchmark::{BaselineEntry, Change, Entry, Format, Profile, Run};
+use profiler::{Report, Settings};
+use table::Cell;
+use watchdog::Outcome;
+
+mod answers;
+mod benchmark;
+mod input;
+mod profiler;
+mod table;
+mod watchdog;
 
 /* -------------------------------- Constants ------------------------------- */
 
-const PROFILE_RUNS: u32 = 20;
+const EXIT_USAGE: i32 = 1;
+const EXIT_UNSOLVED: i32 = 2;
+const EXIT_VERIFY_FAILED: i32 = 3;
+const EXIT_PARSE_ERROR: i32 = 4;
+const EXIT_REGRESSION: i32 = 5;
+const EXIT_TIMEOUT: i32 = 6;
 
 /* --------------------------------- Helpers -------------------------------- */
 
-struct Arguments {
-    task:  String,
-    input: String,
-    flag:  String
+struct Options {
+    profile: bool,
+    format: Format,
+    save: Option<String>,
+    threshold: f64,
+    timeout: Option<Duration>,
+    settings: Settings
+}
+
+enum Command {
+    Run { task: String, input: String, options: Options },
+    All { input: String, options: Options },
+    Verify { input: Option<String>, options: Options },
+    Record { task: String, input: String, options: Options },
+    Compare { baseline: String, options: Options },
+    Pulses { input: String, options: PulseOptions },
+    Beams { input: String, options: BeamOptions },
+    Route { input: String, options: RouteOptions },
+    List
+}
+
+struct PulseOptions {
+    presses: usize,
+    trace: bool,
+    state: bool,
+    dot: Option<String>
+}
+
+struct BeamOptions {
+    edge: String,
+    index: isize,
+    frames: bool
+}
+
+struct RouteOptions {
+    part: Part,
+    prune: bool
 }
 
 fn print_usage() {
-    println!("Usage: cargo run  --release <task> <input> [--profile]");
+    println!("Usage: cargo run --release <task> <input> [--profile] [--format=<format>] [--save=<file>] [--timeout=<ms>] [--warmup=<ms>] [--budget=<ms>]");
+	println!("       cargo run --release all <input> [--format=<format>] [--save=<file>] [--timeout=<ms>] [--warmup=<ms>] [--budget=<ms>]");
+	println!("       cargo run --release compare <file> [--threshold=<percent>] [--timeout=<ms>] [--warmup=<ms>] [--budget=<ms>]");
+	println!("       cargo run --release verify [<input>] [--timeout=<ms>]");
+	println!("       cargo run --release record <task> <input> [--timeout=<ms>]");
+	println!("       cargo run --release pulses <input> [--presses=<n>] [--trace] [--state] [--dot=<file>]");
+	println!("       cargo run --release beams <input> [--entry=<edge>:<n>] [--frames]");
+	println!("       cargo run --release route <input> [--part=<part>] [--no-prune]");
+	println!("       cargo run --release list");
 	println!(" <task>     Day number (two digits) plus part ('a' or 'b')");
-	println!(" <input>    Input file base name, e.g. 'input' or 'sample'");
-	println!(" --profile  Run solution multiple times and compute average duration");
-	println!("Example: cargo run  --release 01a sample");
+	println!(" <input>    Input file base name (e.g. 'input' or 'sample'), a file path, or '-' for stdin");
+	println!(" --profile  Run solution repeatedly and report runtime statistics");
+	println!(" --warmup   Time spent running the solution before measuring (default 100 ms)");
+	println!(" --budget   Time spent measuring, at least five samples are taken (default 1000 ms)");
+	println!(" --format   Output format: 'text' (default), 'json' or 'csv'");
+	println!(" --save     Save the profile as a baseline file (CSV) for 'compare'");
+	println!(" --threshold Change of the median time flagged by 'compare' (default 10 percent)");
+	println!(" --timeout  Time after which a solution is reported as timed out (default 60000 ms, 0 for none)");
+	println!(" all        Profile all solutions and print the README results table");
+	println!(" compare    Profile all solutions in a baseline file again and flag regressions");
+	println!(" verify     Check all solutions against the recorded answers");
+	println!(" record     Run a solution and record its answer as the expected answer");
+	println!(" list       List all registered solutions and their status");
+	println!(" pulses     Simulate a day 20 module network, printing a summary of every press");
+	println!(" --presses  Number of button presses to simulate (default 1000)");
+	println!(" --trace    Print every pulse sent during a press instead of a summary");
+	println!(" --state    Print the state of the network after every press, and of every module at the end");
+	println!(" --dot      Save the network as a Graphviz DOT graph");
+	println!(" beams      Trace a day 16 beam, printing the directions of the beams and the energized tiles");
+	println!(" --entry    Edge ('top', 'bottom', 'left' or 'right') and row or column of the entry point (default left:0)");
+	println!(" --frames   Print the beams after every generation of split beams");
+	println!(" route      Find a day 17 crucible route, printing it on the city map along with its segments");
+	println!(" --part     Turning rules of part 'a' (default) or 'b'");
+	println!(" --no-prune Do not prune dominated states, to compare the number of expanded states");
+	println!("Base names are read from $ADVENT2023_INPUTS/dayXX/ if set, else from src/solutions/dayXX/input/;");
+	println!("the sample inputs are embedded, and are used if the corresponding file is not found.");
+	println!("Example: cargo run --release 01a sample");
+}
+
+fn usage_error() -> ! {
+    print_usage();
+    exit(EXIT_USAGE);
+}
+
+fn parse_options(flags: &[&str]) -> Options {
+    let mut options: Options = Options {
+        profile: false,
+        format: Format::Text,
+        save: None,
+        threshold: benchmark::DEFAULT_THRESHOLD,
+        timeout: Some(watchdog::DEFAULT_TIMEOUT),
+        settings: Settings::new()
+    };
+
+    for flag in flags {
+        if *flag == "--profile" {
+            options.profile = true;
+        } else if let Some(format) = flag.strip_prefix("--format=") {
+            options.format = Format::parse(format).unwrap_or_else(|| usage_error());
+        } else if let Some(path) = flag.strip_prefix("--save=") {
+            options.save = Some(path.to_string());
+        } else if let Some(threshold) = flag.strip_prefix("--threshold=") {
+            options.threshold = threshold.parse().ok().filter(|t: &f64| *t >= 0.0).unwrap_or_else(|| usage_error());
+        } else if let Some(timeout) = flag.strip_prefix("--timeout=") {
+            let millis: u64 = timeout.parse().unwrap_or_else(|_| usage_error());
+            options.timeout = if millis == 0 { None } else { Some(Duration::from_millis(millis)) };
+        } else if !options.settings.apply(flag) {
+            usage_error();
+        }
+    }
+
+    options
+}
+
+fn parse_pulse_options(flags: &[&str]) -> PulseOptions {
+    let mut options: PulseOptions = PulseOptions { presses: 1000, trace: false, state: false, dot: None };
+
+    for flag in flags {
+        if let Some(presses) = flag.strip_prefix("--presses=") {
+            options.presses = presses.parse().unwrap_or_else(|_| usage_error());
+        } else if *flag == "--trace" {
+            options.trace = true;
+        } else if *flag == "--state" {
+            options.state = true;
+        } else if let Some(path) = flag.strip_prefix("--dot=") {
+            options.dot = Some(path.to_string());
+        } else {
+            usage_error();
+        }
+    }
+
+    options
+}
+
+fn parse_beam_options(flags: &[&str]) -> BeamOptions {
+    let mut options: BeamOptions = BeamOptions { edge: String::from("left"), index: 0, frames: false };
+
+    for flag in flags {
+        if let Some(entry) = flag.strip_prefix("--entry=") {
+            let (edge, index) = entry.split_once(':').unwrap_or_else(|| usage_error());
+            options.edge = edge.to_string();
+            options.index = index.parse().unwrap_or_else(|_| usage_error());
+        } else if *flag == "--frames" {
+            options.frames = true;
+        } else {
+            usage_error();
+        }
+    }
+
+    options
 }
 
-fn parse_arguments() -> Arguments {
+fn parse_route_options(flags: &[&str]) -> RouteOptions {
+    let mut options: RouteOptions = RouteOptions { part: Part::A, prune: true };
+
+    for flag in flags {
+        if let Some(part) = flag.strip_prefix("--part=") {
+            options.part = part.parse().ok().and_then(Part::parse).unwrap_or_else(|| usage_error());
+        } else if *flag == "--no-prune" {
+            options.prune = false;
+        } else {
+            usage_error();
+        }
+    }
+
+    options
+}
+
+fn parse_arguments() -> Command {
     let args: Vec<String> = env::args().collect();
+    let command: Vec<&str> = args.iter().skip(1).map(|arg| arg.as_str()).collect();
+
+    match command.as_slice() {
+        ["list"]                             => Command::List,
+        ["all", input, flags @ ..]           => Command::All { input: input.to_string(), options: parse_options(flags) },
+        ["compare", file, flags @ ..]        => Command::Compare { baseline: file.to_string(), options: parse_options(flags) },
+        ["verify", input, flags @ ..] if !input.starts_with("--")
+                                             => Command::Verify { input: Some(input.to_string()), options: parse_options(flags) },
+        ["verify", flags @ ..]               => Command::Verify { input: None, options: parse_options(flags) },
+        ["record", task, input, flags @ ..]  => Command::Record { task: task.to_string(), input: input.to_string(), options: parse_options(flags) },
+        ["pulses", input, flags @ ..]        => Command::Pulses { input: input.to_string(), options: parse_pulse_options(flags) },
+        ["beams", input, flags @ ..]         => Command::Beams { input: input.to_string(), options: parse_beam_options(flags) },
+        ["route", input, flags @ ..]         => Command::Route { input: input.to_string(), options: parse_route_options(flags) },
+        [task, input, flags @ ..]            => Command::Run { task: task.to_string(), input: input.to_string(), options: parse_options(flags) },
+        _                                    => usage_error()
+    }
+}
+
+fn parse_task(task: &str) -> Task {
+    match Task::parse(task) {
+        Some(task) => task,
+        None => panic!("Unexpected task format '{task}'")
+    }
+}
+
+fn try_read_lines(task: &Task, input: &str) -> Result<Vec<String>, String> {
+    input::read_lines(task.day, input)
+}
+
+fn read_lines(task: &Task, input: &str) -> Vec<String> {
+    match try_read_lines(task, input) {
+        Ok(lines) => lines,
+        Err(message) => panic!("{message}")
+    }
+}
+
+// Profiles the parse and solve phases of a solution separately. The solve phase
+// is profiled on a single parsed input, which is reused for every iteration.
+// The input is parsed once up front, so that malformed input is reported before
+// any profiling is done. Profiling happens on a worker thread (see `watchdog`);
+// it times out if it takes longer than profiling both phases could take if no
+// single run exceeds the timeout.
+
+fn profile(puzzle: &'static dyn Puzzle, lines: &Vec<String>, options: &Options) -> Outcome<Result<Profile, ParseError>> {
+    let lines: Vec<String> = lines.clone();
+    let settings: Settings = options.settings;
+    let timeout: Option<Duration> = options.timeout.map(|timeout| settings.max_duration(timeout) * 2);
+
+    watchdog::run(timeout, move || {
+        let input: Box<dyn Any> = puzzle.parse(&lines)?;
+        let parse: Report = profiler::profile(&settings, || { let _ = black_box(puzzle.parse(black_box(&lines))); });
+        let solve: Report = profiler::profile(&settings, || { black_box(puzzle.solve_input(black_box(input.as_ref()))); });
+        Ok(Profile { parse, solve })
+    })
+}
+
+// The result of a single run of both phases of a solution.
 
-    if args.len() < 3 || args.len() > 4 {
-        print_usage();
-        exit(1);
+struct Timed {
+    solution: Solution,
+    parse: Duration,
+    solve: Duration
+}
+
+// Runs a solution once on a worker thread (see `watchdog`), timing the parse
+// and solve phases. The worker needs its own copy of the input lines, since it
+// may outlive the caller if it times out.
+
+fn run_timed(puzzle: &'static dyn Puzzle, lines: &Vec<String>, timeout: Option<Duration>) -> Outcome<Result<Timed, ParseError>> {
+    let lines: Vec<String> = lines.clone();
+
+    watchdog::run(timeout, move || {
+        let start_time: Instant = Instant::now();
+        let parsed: Box<dyn Any> = puzzle.parse(&lines)?;
+        let parse: Duration = start_time.elapsed();
+        let solution: Solution = puzzle.solve_input(parsed.as_ref());
+        Ok(Timed { solution, parse, solve: start_time.elapsed() - parse })
+    })
+}
+
+// Prints a parse error along with the offending line of the input, and returns
+// the corresponding exit code of the process.
+
+fn print_parse_error(error: &ParseError, lines: &Vec<String>) -> i32 {
+    println!("{}", error.render(lines));
+    return EXIT_PARSE_ERROR;
+}
+
+// Prints a solution in a form that matches its variant. Multi-value solutions
+// print their primary answer first, followed by all labelled values. Returns
+// the exit code of the process, which is non-zero for unsolved puzzles.
+
+fn print_solution(solution: &Solution) -> i32 {
+    match solution {
+        Solution::Unsolved(reason) => {
+            println!("Unsolved: {reason}");
+            return EXIT_UNSOLVED;
+        },
+        Solution::Text(text) => println!("Solution: \"{text}\""),
+        Solution::Multiple(values) => {
+            println!("Solution: {}", solution.primary());
+
+            for (label, value) in values {
+                println!("  {label}: {value}");
+            }
+        },
+        _ => println!("Solution: {solution}")
     }
-    
-    let task:  String = args.get(1).unwrap().clone();
-    let input: String = args.get(2).unwrap().clone();
-    let flag:  String = args.get(3).unwrap_or(&String::from("")).clone();
 
-    Arguments { task, input, flag }
+    if solution.is_solved() { 0 } else { EXIT_UNSOLVED }
 }
 
-fn parse_day(task: &str) -> &str {
-    if task.len() != 3 {
-        panic!("Unexpected task format '{task}'")
+/* -------------------------------- Commands -------------------------------- */
+
+fn list(registry: &'static Registry) {
+    println!("Task  Status      Title");
+
+    for day in FIRST_DAY ..= LAST_DAY {
+        for part in Part::ALL {
+            let task: Task = Task { day, part };
+
+            match registry.get(&task) {
+                Some(puzzle) => println!("{task}   {:<10}  {}", puzzle.status().to_string(), puzzle.title()),
+                None         => println!("{task}   {:<10}  {}", "missing", registry.title(day).unwrap_or("-"))
+            }
+        }
     }
 
-    &task[0..2]
+    let missing: Vec<String> = registry.missing().iter().map(|task| task.to_string()).collect();
+    let unfinished: Vec<String> = registry.unfinished().map(|puzzle| puzzle.task().to_string()).collect();
+
+    println!("Missing:    {}", if missing.is_empty() { String::from("none") } else { missing.join(", ") });
+    println!("Unfinished: {}", if unfinished.is_empty() { String::from("none") } else { unfinished.join(", ") });
 }
 
-fn read_lines(task: &str, input: &str) -> Vec<String> {
-    let day: &str = parse_day(task);
-    let filename: String = format!("src/solutions/day{day}/input/{input}.txt");
-    let read_result: Result<String, std::io::Error> = read_to_string(&filename);
+// Profiles a single task for `all` and `compare`. If the task cannot be profiled,
+// the table cell describing the reason is returned instead. Progress is written
+// to stderr, so that stdout only contains the results and can be redirected.
+// The task is first run once under the watchdog, so that solutions that take
+// longer than the timeout are skipped rather than profiled.
 
-    let content: String = match read_result {
-        Err(_) => panic!("Failed to read file '{filename}'"),
-        Ok(s) => s
+fn profile_task(registry: &'static Registry, task: &Task, input: &str, options: &Options) -> Result<Profile, Cell> {
+    let puzzle: &dyn Puzzle = match registry.get(task) {
+        Some(puzzle) => puzzle,
+        None => return Err(Cell::Missing)
     };
 
-    return content.lines().map(String::from).collect();
+    if puzzle.status() == Status::Unfinished {
+        return Err(Cell::DidNotFinish);
+    }
+
+    let lines: Vec<String> = match try_read_lines(task, input) {
+        Ok(lines) => lines,
+        Err(message) => {
+            eprintln!("{task}: {message}");
+            return Err(Cell::NoInput);
+        }
+    };
+
+    match run_timed(puzzle, &lines, options.timeout) {
+        Outcome::Finished(Ok(timed)) if !timed.solution.is_solved() => {
+            eprintln!("{task}: {}", timed.solution);
+            return Err(Cell::DidNotFinish);
+        },
+        Outcome::Finished(Ok(_)) => (),
+        Outcome::Finished(Err(error)) => {
+            eprintln!("{task}: {error}");
+            return Err(Cell::Failed);
+        },
+        Outcome::Panicked => {
+            eprintln!("{task}: failed");
+            return Err(Cell::Failed);
+        },
+        Outcome::TimedOut => {
+            eprintln!("{task}: timed out after {:?}", options.timeout.unwrap_or_default());
+            return Err(Cell::TimedOut);
+        }
+    }
+
+    eprintln!("{task}: profiling...");
+
+    match profile(puzzle, &lines, options) {
+        Outcome::Finished(Ok(profile)) => {
+            eprintln!("{task}: parse {:?}, solve {:?} (mean)", profile.parse.mean, profile.solve.mean);
+            Ok(profile)
+        },
+        Outcome::Finished(Err(error)) => {
+            eprintln!("{task}: {error}");
+            Err(Cell::Failed)
+        },
+        Outcome::Panicked => {
+            eprintln!("{task}: failed");
+            Err(Cell::Failed)
+        },
+        Outcome::TimedOut => {
+            eprintln!("{task}: timed out while profiling");
+            Err(Cell::TimedOut)
+        }
+    }
 }
 
-/* ---------------------------------- Main ---------------------------------- */
+fn save_baseline(path: &str, entries: &[Entry]) {
+    match benchmark::save_baseline(path, entries) {
+        Ok(_) => eprintln!("Saved baseline to '{path}'"),
+        Err(message) => panic!("{message}")
+    }
+}
 
-fn main() {
-    let arguments: Arguments = parse_arguments();
-    let lines: Vec<String> = read_lines(&arguments.task, &arguments.input);
-    let solver: solutions::Solver = solutions::get_solver(&arguments.task);
+// Profiles every registered solution against the given input, and prints the
+// mean runtimes in the format of the README table, or all statistics in JSON
+// or CSV format.
 
-    if arguments.flag == "--profile" {
-        println!("Profiling task '{}' using input '{}'", arguments.task, arguments.input);
-        let start_time: Instant = Instant::now();
+fn run_all(registry: &'static Registry, input: &str, options: &Options) {
+    let entries: Vec<Entry> = (FIRST_DAY ..= LAST_DAY)
+        .flat_map(|day| Part::ALL.into_iter().map(move |part| Task { day, part }))
+        .map(|task| Entry {
+            task,
+            input: input.to_string(),
+            outcome: profile_task(registry, &task, input, options)
+        }).collect();
+
+    if options.format == Format::Text {
+        let cells: Vec<(Cell, Cell)> = entries.chunks(2).map(|pair| (pair[0].cell(), pair[1].cell())).collect();
+        println!("{}", table::format_table(&cells));
+    } else {
+        println!("{}", benchmark::format_entries(&entries, options.format));
+    }
+
+    if let Some(path) = &options.save {
+        save_baseline(path, &entries);
+    }
+}
+
+// Profiles every task in a baseline file again, and compares the median times
+// against the baseline. Tasks that can no longer be profiled count as failed.
+
+fn compare(registry: &'static Registry, path: &str, options: &Options) -> i32 {
+    let baseline: Vec<BaselineEntry> = match benchmark::load_baseline(path) {
+        Ok(baseline) => baseline,
+        Err(message) => panic!("{message}")
+    };
+
+    let mut results: Vec<String> = Vec::new();
+    let mut regressed: usize = 0;
+    let mut improved: usize = 0;
+    let mut failed: usize = 0;
+
+    for entry in &baseline {
+        let label: String = format!("{} {:<10}", entry.task, entry.input);
+
+        let profile: Profile = match profile_task(registry, &entry.task, &entry.input, options) {
+            Ok(profile) => profile,
+            Err(cell) => {
+                results.push(format!("{label} FAIL       {}", cell.status()));
+                failed += 1;
+                continue;
+            }
+        };
+
+        let (percentage, change) = benchmark::compare(entry.median, profile.median(), options.threshold);
+        let status: &str = match change {
+            Change::Unchanged => "ok",
+            Change::Improved  => { improved += 1; "IMPROVED" },
+            Change::Regressed => { regressed += 1; "REGRESSED" }
+        };
+
+        results.push(format!("{label} {:<10} {:>12} -> {:>12} ({:+.1}%)", status,
+            format!("{:?}", entry.median), format!("{:?}", profile.median()), percentage));
+    }
+
+    results.iter().for_each(|line| println!("{line}"));
+    println!("{} compared, {regressed} regressed, {improved} improved, {failed} failed (threshold {}%)",
+        baseline.len(), options.threshold);
+
+    if regressed + failed == 0 { 0 } else { EXIT_REGRESSION }
+}
+
+// Runs a single task once under the watchdog. Returns the run if it finished,
+// or the exit code of the process if it did not; panics are passed on, since
+// for a single task the panic message is the most useful output.
 
-        for _ in 0..PROFILE_RUNS {
-            solver(&lines);
+fn run_once(puzzle: &'static dyn Puzzle, lines: &Vec<String>, options: &Options) -> Result<Timed, i32> {
+    match run_timed(puzzle, lines, options.timeout) {
+        Outcome::Finished(Ok(timed)) => Ok(timed),
+        Outcome::Finished(Err(error)) => Err(print_parse_error(&error, lines)),
+        Outcome::Panicked => panic!("Solver for task '{}' panicked", puzzle.task()),
+        Outcome::TimedOut => {
+            println!("Timed out: no solution after {:?}", options.timeout.unwrap_or_default());
+            Err(EXIT_TIMEOUT)
+        }
+    }
+}
+
+fn run(registry: &'static Registry, task_name: &str, input: &str, options: &Options) -> i32 {
+    let task: Task = parse_task(task_name);
+    let puzzle: &dyn Puzzle = match registry.get(&task) {
+        Some(puzzle) => puzzle,
+        None => panic!("Invalid task '{task}'")
+    };
+
+    let lines: Vec<String> = read_lines(&task, input);
+
+    if options.profile {
+        if options.format == Format::Text {
+            println!("Profiling task '{task_name}' using input '{input}'");
+        }
+
+        let profile: Profile = match profile(puzzle, &lines, options) {
+            Outcome::Finished(Ok(profile)) => profile,
+            Outcome::Finished(Err(error)) => return print_parse_error(&error, &lines),
+            Outcome::Panicked => panic!("Solver for task '{task}' panicked"),
+            Outcome::TimedOut => {
+                println!("Timed out while profiling");
+                return EXIT_TIMEOUT;
+            }
+        };
+
+        if options.format == Format::Text {
+            println!("Parse phase:");
+            profile.parse.print();
+            println!("Solve phase:");
+            profile.solve.print();
+        }
+
+        let entries: Vec<Entry> = vec![Entry { task, input: input.to_string(), outcome: Ok(profile) }];
+
+        match options.format {
+            Format::Text => (),
+            Format::Json => println!("{}", benchmark::entry_json(&entries[0])),
+            Format::Csv  => println!("{}", benchmark::format_entries(&entries, Format::Csv))
         }
 
-        let elapsed: Duration = start_time.elapsed();
-        let average: Duration = elapsed.div(PROFILE_RUNS);
-        println!("Average duration: {:?}", average);
+        if let Some(path) = &options.save {
+            save_baseline(path, &entries);
+        }
+
+        return 0;
+    } else if options.save.is_some() {
+        println!("Only profiles (--profile) can be saved as a baseline");
+        return EXIT_USAGE;
     } else {
-        println!("Running task '{}' on input '{}'", arguments.task, arguments.input);
+        if options.format == Format::Text {
+            println!("Running task '{task_name}' on input '{input}'");
+        }
 
-        let start_time: Instant = Instant::now();
-        let solution: solutions::Solution = solver(&lines);
-        let elapsed: Duration = start_time.elapsed();
+        let timed: Timed = match run_once(puzzle, &lines, options) {
+            Ok(timed) => timed,
+            Err(code) => return code
+        };
+
+        if options.format != Format::Text {
+            let run: Run = Run {
+                task,
+                input,
+                solved: timed.solution.is_solved(),
+                solution: timed.solution.primary().to_string(),
+                values: timed.solution.details().iter().map(|(label, value)| (*label, value.to_string())).collect(),
+                parse: timed.parse,
+                solve: timed.solve
+            };
+
+            println!("{}", benchmark::format_run(&run, options.format));
+            return if timed.solution.is_solved() { 0 } else { EXIT_UNSOLVED };
+        }
+
+        println!("Elapsed time: {:?} (parse {:?}, solve {:?})", timed.parse + timed.solve, timed.parse, timed.solve);
+        return print_solution(&timed.solution);
+    }
+}
+
+// Runs every solution for which an answer has been recorded (optionally only
+// for the given input), and compares the primary value of its solution to the
+// recorded answer. Solvers that panic, report the puzzle as unsolved, reject
+// their input, or whose input file is missing are reported as failures rather
+// than mismatches; parts that are marked as unfinished in the registry are
+// skipped. Solvers that time out are counted separately, but also make the
+// verification fail.
+
+fn verify(registry: &'static Registry, only_input: Option<&str>, options: &Options) -> i32 {
+    let mut passed: usize = 0;
+    let mut mismatched: usize = 0;
+    let mut failed: usize = 0;
+    let mut timed_out: usize = 0;
+    let mut skipped: usize = 0;
+
+    for day in FIRST_DAY ..= LAST_DAY {
+        let answers: Answers = Answers::load(day);
+
+        for answer in answers.entries.iter().filter(|answer| only_input.is_none_or(|input| answer.input == input)) {
+            let task: Task = Task { day, part: answer.part };
+            let label: String = format!("{task} {:<10}", answer.input);
+
+            let puzzle: &dyn Puzzle = match registry.get(&task) {
+                Some(puzzle) => puzzle,
+                None => {
+                    println!("{label} FAIL      no solver registered");
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            if puzzle.status() == Status::Unfinished {
+                println!("{label} SKIP      unfinished");
+                skipped += 1;
+                continue;
+            }
+
+            let lines: Vec<String> = match try_read_lines(&task, &answer.input) {
+                Ok(lines) => lines,
+                Err(message) => {
+                    println!("{label} FAIL      {message}");
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            match run_timed(puzzle, &lines, options.timeout) {
+                Outcome::Finished(Ok(timed)) if !timed.solution.is_solved() => {
+                    println!("{label} FAIL      {}", timed.solution);
+                    failed += 1;
+                },
+                Outcome::Finished(Ok(timed)) if timed.solution.primary().to_string() == answer.value => {
+                    println!("{label} PASS      {}", answer.value);
+                    passed += 1;
+                },
+                Outcome::Finished(Ok(timed)) => {
+                    println!("{label} MISMATCH  expected {}, got {}", answer.value, timed.solution.primary());
+                    mismatched += 1;
+                },
+                Outcome::Finished(Err(error)) => {
+                    println!("{label} FAIL      {error}");
+                    failed += 1;
+                },
+                Outcome::Panicked => {
+                    println!("{label} FAIL      solver panicked");
+                    failed += 1;
+                },
+                Outcome::TimedOut => {
+                    println!("{label} TIMEOUT   no solution after {:?}", options.timeout.unwrap_or_default());
+                    timed_out += 1;
+                }
+            }
+        }
+    }
+
+    println!("{passed} passed, {mismatched} mismatched, {failed} failed, {timed_out} timed out, {skipped} skipped");
+
+    if mismatched + failed + timed_out == 0 { 0 } else { EXIT_VERIFY_FAILED }
+}
+
+fn record(registry: &'static Registry, task_name: &str, input: &str, options: &Options) -> i32 {
+    let task: Task = parse_task(task_name);
+    let puzzle: &dyn Puzzle = match registry.get(&task) {
+        Some(puzzle) => puzzle,
+        None => panic!("Invalid task '{task}'")
+    };
+
+    if !input::is_named(input) {
+        println!("Answers can only be recorded for inputs given by their base name");
+        return EXIT_USAGE;
+    }
+
+    let lines: Vec<String> = read_lines(&task, input);
+    let solution: Solution = match run_once(puzzle, &lines, options) {
+        Ok(timed) => timed.solution,
+        Err(code) => return code
+    };
+
+    if !solution.is_solved() {
+        println!("Not recording answer for '{task}': {solution}");
+        return EXIT_UNSOLVED;
+    }
+
+    let value: String = solution.primary().to_string();
+    let mut answers: Answers = Answers::load(task.day);
+
+    match answers.record(&task, input, &value) {
+        Ok(Some(previous)) if previous == value => println!("Answer for '{task}' on '{input}' unchanged: {value}"),
+        Ok(Some(previous)) => println!("Answer for '{task}' on '{input}' changed from {previous} to {value}"),
+        Ok(None) => println!("Recorded answer for '{task}' on '{input}': {value}"),
+        Err(message) => panic!("{message}")
+    }
+
+    return 0;
+}
+
+// Simulates the module network of day 20 (see `day20::simulator`), printing
+// either a summary or every pulse of each press, followed by the totals.
+
+fn simulate_pulses(input: &str, options: &PulseOptions) -> i32 {
+    let lines: Vec<String> = match input::read_lines(20, input) {
+        Ok(lines) => lines,
+        Err(message) => panic!("{message}")
+    };
+
+    let network: Network = match Network::parse(&lines) {
+        Ok(network) => network,
+        Err(error) => return print_parse_error(&error, &lines)
+    };
+
+    if let Some(path) = &options.dot {
+        match fs::write(path, simulator::to_dot(&network) + "\n") {
+            Ok(_) => eprintln!("Saved graph to '{path}'"),
+            Err(error) => panic!("Cannot write graph to '{path}': {error}")
+        }
+    }
+
+    let mut simulator: Simulator = Simulator::new(&network);
+
+    for _ in 0 .. options.presses {
+        let pulses: Vec<Pulse> = simulator.press();
+        let state: String = if options.state { format!(", state {}", simulator.state_bits()) } else { String::new() };
+
+        if options.trace {
+            println!("Press {}{state}:", simulator.presses);
+            pulses.iter().for_each(|pulse| println!("  {}", simulator.format_pulse(pulse)));
+        } else {
+            let high: usize = pulses.iter().filter(|pulse| pulse.value == PulseValue::High).count();
+            println!("Press {}: {} low, {high} high{state}", simulator.presses, pulses.len() - high);
+        }
+    }
+
+    println!("{} presses: {} low, {} high (product {})", simulator.presses, simulator.low_pulses,
+        simulator.high_pulses, simulator.low_pulses * simulator.high_pulses);
+
+    if options.state {
+        simulator.module_states().iter().for_each(|line| println!("{line}"));
+    }
+
+    return 0;
+}
+
+// Traces a beam through the contraption of day 16 (see `day16::visualizer`),
+// printing the beams after every generation if requested, followed by the
+// final beams and the energized tiles.
+
+fn visualize_beams(input: &str, options: &BeamOptions) -> i32 {
+    let lines: Vec<String> = match input::read_lines(16, input) {
+        Ok(lines) => lines,
+        Err(message) => panic!("{message}")
+    };
+
+    let grid: Contraption = match Contraption::parse(&lines) {
+        Ok(grid) => grid,
+        Err(error) => return print_parse_error(&error, &lines)
+    };
+
+    let entry: Beam = match visualizer::entry_beam(&grid.tiles, &options.edge, options.index) {
+        Some(entry) => entry,
+        None => {
+            println!("Invalid entry point '{}:{}'", options.edge, options.index);
+            return EXIT_USAGE;
+        }
+    };
+
+    let mut visualizer: Visualizer = Visualizer::new(&grid, entry);
+
+    while visualizer.step() {
+        if options.frames {
+            println!("Generation {}: {} energized, {} new beams", visualizer.generation,
+                visualizer.energy(), visualizer.beam_count());
+            visualizer.render_beams().iter().for_each(|line| println!("{line}"));
+            println!();
+        }
+    }
+
+    if !options.frames {
+        visualizer.render_beams().iter().for_each(|line| println!("{line}"));
+        println!();
+    }
+
+    visualizer.render_energized().iter().for_each(|line| println!("{line}"));
+    println!("{} tiles energized after {} generations", visualizer.energy(), visualizer.generation);
+
+    return 0;
+}
+
+// Finds the best route of the crucible of day 17 (see `day17::common`) with
+// the rules of either part, and prints it on the city map, followed by its
+// segments, its heat loss and the statistics of the search.
+
+fn show_route(input: &str, options: &RouteOptions) -> i32 {
+    let lines: Vec<String> = match input::read_lines(17, input) {
+        Ok(lines) => lines,
+        Err(message) => panic!("{message}")
+    };
+
+    let grid: Grid<u8> = match day17::common::parse_grid(&lines) {
+        Ok(grid) => grid,
+        Err(error) => return print_parse_error(&error, &lines)
+    };
+
+    let rules: &Rules = match options.part {
+        Part::A => &day17::solve_a::RULES,
+        Part::B => &day17::solve_b::RULES
+    };
+
+    let search: Search = day17::common::search(&grid, rules, options.prune);
+
+    let Some(route) = search.route() else {
+        println!("Unsolved: the crucible cannot reach the bottom-right block");
+        return EXIT_UNSOLVED;
+    };
+
+    println!("{}", route.render(&grid));
+    println!("Segments: {}", route.format_segments(&grid));
+    println!("Heat loss {} ({} states expanded, {} pruned)", route.loss, search.stats.expanded, search.stats.pruned);
+
+    return 0;
+}
+
+/* ---------------------------------- Main ---------------------------------- */
+
+fn main() {
+    let command: Command = parse_arguments();
+
+    // Solvers run on worker threads that may outlive the command that started
+    // them (if they time out), so the registry lives until the process exits.
+
+    let registry: &'static Registry = Box::leak(Box::new(solutions::registry()));
 
-        println!("Elapsed time: {:?}", elapsed);
-        println!("Solution: {}", solution.to_string());
+    match command {
+        Command::List                            => list(registry),
+        Command::All { input, options }          => run_all(registry, &input, &options),
+        Command::Verify { input, options }       => exit(verify(registry, input.as_deref(), &options)),
+        Command::Record { task, input, options } => exit(record(registry, &task, &input, &options)),
+        Command::Compare { baseline, options }   => exit(compare(registry, &baseline, &options)),
+        Command::Pulses { input, options }       => exit(simulate_pulses(&input, &options)),
+        Command::Beams { input, options }        => exit(visualize_beams(&input, &options)),
+        Command::Route { input, options }        => exit(show_route(&input, &options)),
+        Command::Run { task, input, options }    => exit(run(registry, &task, &input, &options))
     }
 }
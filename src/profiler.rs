@@ -0,0 +1,180 @@
+use std::time::{Duration, Instant};
+
+// Statistical profiler used by `--profile` and `all`. A solution is first run
+// repeatedly for the warmup period, which also gives a rough estimate of its
+// runtime. Based on this estimate, consecutive runs are grouped into batches
+// that take at least `MIN_BATCH_TIME`, so that very fast solutions (like days
+// 05 and 06) are not dominated by the resolution and overhead of `Instant`.
+// Batches are then timed until the time budget runs out or the maximum number
+// of samples is reached, but at least `MIN_SAMPLES` are always collected, so
+// slow solutions (like day 23) take roughly `MIN_SAMPLES` runs, not hundreds.
+//
+// Outliers are detected using Tukey's fences: samples more than 1.5 times the
+// interquartile range below the first or above the third quartile are counted
+// as outliers. They are reported, but still included in the statistics.
+
+/* -------------------------------- Constants ------------------------------- */
+
+const MIN_BATCH_TIME: Duration = Duration::from_micros(10);
+const MIN_SAMPLES: usize = 5;
+const MAX_SAMPLES: usize = 1000;
+const OUTLIER_FACTOR: f64 = 1.5;
+
+/* -------------------------------- Settings -------------------------------- */
+
+#[derive(Clone, Copy)]
+pub struct Settings {
+    pub warmup: Duration,
+    pub budget: Duration
+}
+
+impl Settings {
+    pub fn new() -> Settings {
+        Settings { warmup: Duration::from_millis(100), budget: Duration::from_secs(1) }
+    }
+
+    // Applies a command line option of the form "--warmup=<ms>" or "--budget=<ms>".
+    // Returns false if the option is not a (valid) profiler option.
+
+    pub fn apply(&mut self, option: &str) -> bool {
+        let (name, value) = match option.split_once('=') {
+            Some(pair) => pair,
+            None => return false
+        };
+
+        let millis: u64 = match value.parse() {
+            Ok(millis) => millis,
+            Err(_) => return false
+        };
+
+        match name {
+            "--warmup" => self.warmup = Duration::from_millis(millis),
+            "--budget" => self.budget = Duration::from_millis(millis),
+            _ => return false
+        }
+
+        return true;
+    }
+
+    // Returns an upper bound on the time taken by `profile()` for a function that
+    // takes at most `run_time` per run: runs may overshoot both the warmup period
+    // and the budget, and at least `MIN_SAMPLES` runs are measured, even if they
+    // do not fit in the budget.
+
+    pub fn max_duration(&self, run_time: Duration) -> Duration {
+        self.warmup + self.budget + run_time * (MIN_SAMPLES as u32 + 1)
+    }
+}
+
+/* --------------------------------- Report --------------------------------- */
+
+pub struct Report {
+    pub warmup_runs: usize,
+    pub batch_size: usize,
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+    pub low_outliers: usize,
+    pub high_outliers: usize
+}
+
+impl Report {
+    fn new(warmup_runs: usize, batch_size: usize, mut samples: Vec<f64>) -> Report {
+        samples.sort_by(|a, b| a.total_cmp(b));
+
+        let count: usize = samples.len();
+        let mean: f64 = samples.iter().sum::<f64>() / count as f64;
+        let variance: f64 = if count > 1 {
+            samples.iter().map(|sample| (sample - mean) * (sample - mean)).sum::<f64>() / (count - 1) as f64
+        } else {
+            0.0
+        };
+
+        let q1: f64 = percentile(&samples, 25.0);
+        let q3: f64 = percentile(&samples, 75.0);
+        let iqr: f64 = q3 - q1;
+        let low_fence: f64 = q1 - OUTLIER_FACTOR * iqr;
+        let high_fence: f64 = q3 + OUTLIER_FACTOR * iqr;
+
+        Report {
+            warmup_runs,
+            batch_size,
+            samples: count,
+            min: to_duration(samples[0]),
+            median: to_duration(percentile(&samples, 50.0)),
+            mean: to_duration(mean),
+            p95: to_duration(percentile(&samples, 95.0)),
+            stddev: to_duration(variance.sqrt()),
+            low_outliers: samples.iter().filter(|sample| **sample < low_fence).count(),
+            high_outliers: samples.iter().filter(|sample| **sample > high_fence).count()
+        }
+    }
+
+    pub fn print(&self) {
+        let outliers: usize = self.low_outliers + self.high_outliers;
+        let percentage: f64 = 100.0 * outliers as f64 / self.samples as f64;
+
+        println!("  Warmup runs:  {}", self.warmup_runs);
+        println!("  Samples:      {} (batch size {})", self.samples, self.batch_size);
+        println!("  Min:          {:?}", self.min);
+        println!("  Median:       {:?}", self.median);
+        println!("  Mean:         {:?}", self.mean);
+        println!("  P95:          {:?}", self.p95);
+        println!("  Std. dev.:    {:?}", self.stddev);
+        println!("  Outliers:     {} low, {} high ({:.1}%)", self.low_outliers, self.high_outliers, percentage);
+    }
+}
+
+/* --------------------------------- Helpers -------------------------------- */
+
+// Linear interpolation between the closest ranks; `sorted` must not be empty.
+
+fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    let rank: f64 = percent / 100.0 * (sorted.len() - 1) as f64;
+    let lower: usize = rank.floor() as usize;
+    let upper: usize = rank.ceil() as usize;
+    let fraction: f64 = rank - lower as f64;
+
+    sorted[lower] + (sorted[upper] - sorted[lower]) * fraction
+}
+
+fn to_duration(nanos: f64) -> Duration {
+    Duration::from_nanos(nanos.max(0.0).round() as u64)
+}
+
+/* -------------------------------- Profiler -------------------------------- */
+
+pub fn profile<F: FnMut()>(settings: &Settings, mut run: F) -> Report {
+    let warmup_start: Instant = Instant::now();
+    let mut warmup_runs: usize = 0;
+
+    while warmup_runs == 0 || warmup_start.elapsed() < settings.warmup {
+        run();
+        warmup_runs += 1;
+    }
+
+    let estimate: Duration = warmup_start.elapsed() / warmup_runs as u32;
+    let batch_size: usize = if estimate.is_zero() {
+        MIN_BATCH_TIME.as_nanos() as usize
+    } else {
+        (MIN_BATCH_TIME.as_nanos() / estimate.as_nanos()).max(1) as usize
+    };
+
+    let measure_start: Instant = Instant::now();
+    let mut samples: Vec<f64> = Vec::new();
+
+    while samples.len() < MIN_SAMPLES || (samples.len() < MAX_SAMPLES && measure_start.elapsed() < settings.budget) {
+        let batch_start: Instant = Instant::now();
+
+        for _ in 0 .. batch_size {
+            run();
+        }
+
+        samples.push(batch_start.elapsed().as_nanos() as f64 / batch_size as f64);
+    }
+
+    Report::new(warmup_runs, batch_size, samples)
+}
@@ -1,123 +1,140 @@
-pub mod day01 {     pub mod solve_a;    pub mod solve_b;                    }
-pub mod day02 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day03 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day04 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day05 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day06 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day07 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day08 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day09 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day10 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day11 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day12 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day13 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day14 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day15 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day16 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day17 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day18 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day19 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day20 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day21 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day22 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day23 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day24 {     pub mod solve_a;    pub mod solve_b;    mod common;     }
-pub mod day25 {     pub mod solve_a;                                        }
+use std::fmt;
 
-/* -------------------------------- Solution -------------------------------- */
-
-pub enum Solution {
-    Integer(i64)
-}
-
-impl Solution {
-    pub fn to_string(&self) -> String {
-        match self {
-            Solution::Integer(i) => i.to_string()
-        }
-    }
-}
-
-/* --------------------------------- Solvers -------------------------------- */
-
-pub type Solver = fn(&Vec<String>) -> Solution;
-
-pub fn get_solver(task: &str) -> Solver {
-    match task {
-        "01a" => day01::solve_a::solve,
-        "01b" => day01::solve_b::solve,
-
-        "02a" => day02::solve_a::solve,
-        "02b" => day02::solve_b::solve,
-
-        "03a" => day03::solve_a::solve,
-        "03b" => day03::solve_b::solve,
-
-        "04a" => day04::solve_a::solve,
-        "04b" => day04::solve_b::solve,
-
-        "05a" => day05::solve_a::solve,
-        "05b" => day05::solve_b::solve,
-
-        "06a" => day06::solve_a::solve,
-        "06b" => day06::solve_b::solve,
-
-        "07a" => day07::solve_a::solve,
-        "07b" => day07::solve_b::solve,
-
-        "08a" => day08::solve_a::solve,
-        "08b" => day08::solve_b::solve,
-
-        "09a" => day09::solve_a::solve,
-        "09b" => day09::solve_b::solve,
+pub mod cancel;
+pub mod parse;
+pub mod registry;
 
-        "10a" => day10::solve_a::solve,
-        "10b" => day10::solve_b::solve,
+// Helpers shared by several days.
 
-        "11a" => day11::solve_a::solve,
-        "11b" => day11::solve_b::solve,
+pub mod cycle;
+pub mod geometry;
+pub mod grid;
+pub mod linear;
+pub mod number;
+pub mod rational;
 
-        "12a" => day12::solve_a::solve,
-        "12b" => day12::solve_b::solve,
+use registry::{Entry, Part, Registry, Status};
 
-        "13a" => day13::solve_a::solve,
-        "13b" => day13::solve_b::solve,
+/* ---------------------------------- Days ---------------------------------- */
 
-        "14a" => day14::solve_a::solve,
-        "14b" => day14::solve_b::solve,
+// Each day is registered exactly once in the `days!` invocation below. Every
+// line declares the modules of the day (the solver modules listed between the
+// brackets, plus any helper modules after the `+`, which are private unless
+// marked `pub`), and adds each of its parts to the registry returned by
+// `registry()`. Each solver module must provide a `parse()` and a `solve()`
+// function (see `registry::Puzzle`). Parts that are known to not produce the
+// correct answer are marked `(Unfinished)`.
 
-        "15a" => day15::solve_a::solve,
-        "15b" => day15::solve_b::solve,
+macro_rules! status {
+    ()                => { Status::Solved };
+    ($status:ident)   => { Status::$status };
+}
 
-        "16a" => day16::solve_a::solve,
-        "16b" => day16::solve_b::solve,
+macro_rules! days {
+    ($( $module:ident $day:literal $title:literal
+        [ $( $part:ident : $solver:ident $( ($status:ident) )? ),+ ]
+        $( + $helper_vis:vis $helper:ident )* ; )+) => {
+        $( pub mod $module { $( pub mod $solver; )+ $( $helper_vis mod $helper; )* } )+
+
+        pub fn registry() -> Registry {
+            Registry::new(vec![ $( $(
+                Entry::boxed($day, Part::$part, $title, status!($( $status )?),
+                    $module::$solver::parse, $module::$solver::solve),
+            )+ )+ ])
+        }
+    };
+}
 
-        "17a" => day17::solve_a::solve,
-        "17b" => day17::solve_b::solve,
+days! {
+    day01  1 "Trebuchet?!"                      [A: solve_a, B: solve_b];
+    day02  2 "Cube Conundrum"                   [A: solve_a, B: solve_b] + common;
+    day03  3 "Gear Ratios"                      [A: solve_a, B: solve_b] + common;
+    day04  4 "Scratchcards"                     [A: solve_a, B: solve_b] + common;
+    day05  5 "If You Give A Seed A Fertilizer"  [A: solve_a, B: solve_b] + common;
+    day06  6 "Wait For It"                      [A: solve_a, B: solve_b] + common;
+    day07  7 "Camel Cards"                      [A: solve_a, B: solve_b] + common;
+    day08  8 "Haunted Wasteland"                [A: solve_a, B: solve_b] + common;
+    day09  9 "Mirage Maintenance"               [A: solve_a, B: solve_b] + common;
+    day10 10 "Pipe Maze"                        [A: solve_a, B: solve_b] + common;
+    day11 11 "Cosmic Expansion"                 [A: solve_a, B: solve_b] + common;
+    day12 12 "Hot Springs"                      [A: solve_a, B: solve_b] + common;
+    day13 13 "Point of Incidence"               [A: solve_a, B: solve_b] + common;
+    day14 14 "Parabolic Reflector Dish"         [A: solve_a, B: solve_b] + common;
+    day15 15 "Lens Library"                     [A: solve_a, B: solve_b] + common;
+    day16 16 "The Floor Will Be Lava"           [A: solve_a, B: solve_b] + pub common + pub visualizer;
+    day17 17 "Clumsy Crucible"                  [A: solve_a, B: solve_b] + pub common;
+    day18 18 "Lavaduct Lagoon"                  [A: solve_a, B: solve_b] + common;
+    day19 19 "Aplenty"                          [A: solve_a, B: solve_b] + common;
+    day20 20 "Pulse Propagation"                [A: solve_a, B: solve_b] + pub common + pub simulator;
+    day21 21 "Step Counter"                     [A: solve_a, B: solve_b] + common;
+    day22 22 "Sand Slabs"                       [A: solve_a, B: solve_b] + common;
+    day23 23 "A Long Walk"                      [A: solve_a, B: solve_b] + common;
+    day24 24 "Never Tell Me The Odds"           [A: solve_a, B: solve_b] + common;
+    day25 25 "Snowverload"                      [A: solve_a];
+}
 
-        "18a" => day18::solve_a::solve,
-        "18b" => day18::solve_b::solve,
+/* -------------------------------- Solution -------------------------------- */
 
-        "19a" => day19::solve_a::solve,
-        "19b" => day19::solve_b::solve,
+// Most puzzles have a single (signed) integer answer, but some answers are too
+// large for an `i64` (`Unsigned` if they cannot be negative, `Wide` if they can
+// be), some are text, and some solvers produce several related values (e.g. a
+// position and a velocity) in addition to the actual answer. In the `Multiple`
+// case, the first value is considered to be the primary answer. Solvers that
+// cannot produce an answer return `Unsolved` with a reason.
 
-        "20a" => day20::solve_a::solve,
-        "20b" => day20::solve_b::solve,
+pub enum Solution {
+    Integer(i64),
+    Unsigned(u64),
+    Wide(i128),
+    Text(String),
+    Multiple(Vec<(&'static str, Solution)>),
+    Unsolved(String)
+}
 
-        "21a" => day21::solve_a::solve,
-        "21b" => day21::solve_b::solve,
+impl Solution {
+    pub fn unsolved(reason: &str) -> Solution {
+        Solution::Unsolved(String::from(reason))
+    }
 
-        "22a" => day22::solve_a::solve,
-        "22b" => day22::solve_b::solve,
+    pub fn is_solved(&self) -> bool {
+        match self {
+            Solution::Unsolved(_)      => false,
+            Solution::Multiple(values) => values.iter().all(|(_, value)| value.is_solved()),
+            _                          => true
+        }
+    }
 
-        "23a" => day23::solve_a::solve,
-        "23b" => day23::solve_b::solve,
+    pub fn primary(&self) -> &Solution {
+        match self {
+            Solution::Multiple(values) => values.first().map(|(_, value)| value.primary()).unwrap_or(self),
+            _                          => self
+        }
+    }
 
-        "24a" => day24::solve_a::solve,
-        "24b" => day24::solve_b::solve,
+    // The labelled values besides the primary answer, which only `Multiple`
+    // solutions have.
 
-        "25a" => day25::solve_a::solve,
+    pub fn details(&self) -> &[(&'static str, Solution)] {
+        match self {
+            Solution::Multiple(values) if !values.is_empty() => &values[1 ..],
+            _                                                => &[]
+        }
+    }
+}
 
-        _ => panic!("Invalid task '{task}'")
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Solution::Integer(i)       => write!(f, "{i}"),
+            Solution::Unsigned(u)      => write!(f, "{u}"),
+            Solution::Wide(w)          => write!(f, "{w}"),
+            Solution::Text(t)          => write!(f, "{t}"),
+            Solution::Unsolved(reason) => write!(f, "unsolved ({reason})"),
+            Solution::Multiple(values) => {
+                let fields: Vec<String> = values.iter()
+                    .map(|(label, value)| format!("{label}={value}")).collect();
+                write!(f, "{}", fields.join(", "))
+            }
+        }
     }
 }
@@ -0,0 +1,57 @@
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Cooperative cancellation of solvers. A thread cannot be stopped from the
+// outside, so the runner's watchdog instead installs a shared flag on every
+// worker thread before running a solver, and raises it once the solver has
+// timed out. Solvers that can run for a long time (e.g. the exhaustive search
+// of day 23) check `requested()` in their main loop, and give up as soon as it
+// returns true; since the runner has stopped waiting for them by then, what
+// they return does not matter, as long as they return quickly and don't panic.
+//
+// The flag is stored per thread, so solvers don't need an extra parameter, and
+// on threads without a flag (e.g. in tests) cancellation is never requested.
+
+/* ---------------------------------- Flag ---------------------------------- */
+
+thread_local! {
+    static FLAG: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+// Installs the flag checked by `requested()` on the current thread.
+
+pub fn install(flag: Arc<AtomicBool>) {
+    FLAG.with(|current| *current.borrow_mut() = Some(flag));
+}
+
+pub fn requested() -> bool {
+    FLAG.with(|current| {
+        current.borrow().as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed))
+    })
+}
+
+/* ---------------------------------- Tests --------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn only_requested_once_raised() {
+        let flag: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+        let worker_flag: Arc<AtomicBool> = flag.clone();
+
+        let checks: (bool, bool, bool) = thread::spawn(move || {
+            let before_install: bool = requested();
+            install(worker_flag.clone());
+            let before_raise: bool = requested();
+            worker_flag.store(true, Ordering::Relaxed);
+            (before_install, before_raise, requested())
+        }).join().unwrap();
+
+        assert_eq!(checks, (false, false, true));
+        assert!(!requested());
+    }
+}
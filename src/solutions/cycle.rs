@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Cycle detection for days that repeatedly apply some step to a state, and
+// need the state after far more steps than can be simulated. Starting from an
+// initial state, every sequence of states that can only take a finite number
+// of values eventually repeats itself: after `offset` steps, it enters a loop
+// of `period` steps, after which the states repeat. The state after any number
+// of steps is then equal to the state after a number of steps smaller than
+// `offset + period`, which `Cycle::reduce()` computes.
+//
+// `detect()` stores every state it encounters in a hash map, and finds the
+// cycle as soon as a state repeats; it remembers all states, so that
+// `History::state_at()` can look up the state after any number of steps
+// without simulating any further. (An earlier version also offered Brent's
+// algorithm, which only keeps two states in memory, but it needs several times
+// as many steps, which made day 14 twice as slow, and the states of every day
+// are small enough to store.)
+//
+// It compares complete states, so a detected cycle is always a real one. The
+// step function of `detect()` is called exactly once for every state, in order,
+// so it can also be used to record information about every step on the side
+// (e.g. the output of the step), as long as it stays deterministic.
+
+/* ---------------------------------- Cycle --------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cycle {
+    pub offset: usize,
+    pub period: usize
+}
+
+impl Cycle {
+    // Returns the smallest number of steps that leads to the same state as the
+    // given number of steps.
+
+    pub fn reduce(&self, steps: usize) -> usize {
+        if steps < self.offset {
+            return steps;
+        }
+
+        self.offset + (steps - self.offset) % self.period
+    }
+}
+
+/* --------------------------------- History -------------------------------- */
+
+// All states up to the point where the sequence starts repeating, i.e. the
+// states after zero up to and including `offset + period - 1` steps.
+
+pub struct History<S> {
+    pub cycle: Cycle,
+    pub states: Vec<S>
+}
+
+impl<S> History<S> {
+    pub fn state_at(&self, steps: usize) -> &S {
+        &self.states[self.cycle.reduce(steps)]
+    }
+}
+
+/* -------------------------------- Detectors ------------------------------- */
+
+pub fn detect<S, F>(initial: S, next: F) -> History<S>
+    where S: Clone + Eq + Hash, F: FnMut(&S) -> S {
+    detect_within(initial, usize::MAX, next).unwrap()
+}
+
+// Like `detect()`, but gives up (returning `None`) if no state has repeated
+// after the given number of steps, for sequences whose cycles may be too long
+// to find in a reasonable amount of time.
+
+pub fn detect_within<S, F>(initial: S, max_steps: usize, mut next: F) -> Option<History<S>>
+    where S: Clone + Eq + Hash, F: FnMut(&S) -> S {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut states: Vec<S> = Vec::new();
+    let mut state: S = initial;
+
+    loop {
+        if let Some(offset) = seen.get(&state) {
+            let cycle: Cycle = Cycle { offset: *offset, period: states.len() - offset };
+            return Some(History { cycle, states });
+        }
+
+        if states.len() == max_steps {
+            return None;
+        }
+
+        let new_state: S = next(&state);
+        seen.insert(state.clone(), states.len());
+        states.push(state);
+        state = new_state;
+    }
+}
+
+/* ---------------------------------- Tests --------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, 2, 3, 4, 5, 3, 4, 5, 3, ...
+
+    fn next(state: &usize) -> usize {
+        if *state < 5 { state + 1 } else { 3 }
+    }
+
+    #[test]
+    fn detect_finds_offset_and_period() {
+        let history: History<usize> = detect(0, next);
+        assert_eq!(history.cycle, Cycle { offset: 3, period: 3 });
+        assert_eq!(history.states, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn detect_finds_loop_without_offset() {
+        let history: History<usize> = detect(0, |state| (state + 1) % 4);
+        assert_eq!(history.cycle, Cycle { offset: 0, period: 4 });
+    }
+
+    #[test]
+    fn detect_within_gives_up() {
+        assert!(detect_within(0, 5, next).is_none());
+        assert!(detect_within(0, 6, next).is_some());
+    }
+
+    #[test]
+    fn state_at_reduces_steps() {
+        let history: History<usize> = detect(0, next);
+        let expected: Vec<usize> = vec![0, 1, 2, 3, 4, 5, 3, 4, 5, 3, 4];
+
+        for (steps, state) in expected.iter().enumerate() {
+            assert_eq!(history.state_at(steps), state);
+        }
+
+        assert_eq!(*history.state_at(1_000_000_000), 3 + (1_000_000_000 - 3) % 3);
+    }
+}
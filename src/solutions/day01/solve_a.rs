@@ -1,4 +1,5 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::{self, ParseResult};
 
 // Very straightforward, simply iterate over the characters in both directions
 // and find the first one that can be converted to a digit. The `find_map()`
@@ -11,6 +12,16 @@ fn to_value(line: &String) -> i64 {
     return 10 * first_digit + last_digit;
 }
 
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<String>> {
+    for line in parse::lines(lines) {
+        if !line.text.chars().any(|c| c.is_ascii_digit()) {
+            return Err(line.error(line.text, "a line containing a digit"));
+        }
+    }
+
+    Ok(lines.clone())
+}
+
 pub fn solve(lines: &Vec<String>) -> Solution {
     let result: i64 = lines.iter().map(to_value).sum();
     return Solution::Integer(result)
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use crate::solutions::Solution;
+use crate::solutions::parse::{self, ParseResult};
 
 // I initially figured I'd simply do a find-and-replace for textual numeric values
 // ("one", "two", etc) to digits, and then repeat the A part. However, this doesn't
@@ -32,6 +33,22 @@ fn to_value(line: &String, word_to_value: &HashMap<&str, i64>) -> i64 {
     return 10 * first_digit + last_digit;
 }
 
+const WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<String>> {
+    for line in parse::lines(lines) {
+        if !line.text.is_ascii() {
+            return Err(line.error(line.text, "an ASCII line"));
+        }
+
+        if !line.text.chars().any(|c| c.is_ascii_digit()) && !WORDS.iter().any(|word| line.text.contains(word)) {
+            return Err(line.error(line.text, "a line containing a digit"));
+        }
+    }
+
+    Ok(lines.clone())
+}
+
 pub fn solve(lines: &Vec<String>) -> Solution {
     let word_to_value: HashMap<&str, i64> = HashMap::from([
         ("one",   1),
@@ -1,3 +1,5 @@
+use crate::solutions::parse::{self, Line, ParseResult};
+
 pub struct Round {
     pub red:   usize,
     pub green: usize,
@@ -9,32 +11,35 @@ pub struct Game {
     pub rounds: Vec<Round>
 }
 
-pub fn parse_round(field: &str) -> Round {
+pub fn parse_round(line: &Line, field: &str) -> ParseResult<Round> {
     let subfields: Vec<&str> = field.trim().split(", ").collect();
     let mut round: Round = Round { red: 0, green: 0, blue: 0 };
 
     for subfield in subfields {
-        let mut amount_and_color: std::str::Split<'_, char> = subfield.split(' ');
-        let amount: usize = amount_and_color.next().unwrap().parse::<usize>().unwrap();
-        let color: &str = amount_and_color.next().unwrap();
+        let (amount, color) = line.split_once(subfield, " ")?;
+        let amount: usize = line.number(amount)?;
 
         match color {
             "red"   => round.red   = amount,
             "green" => round.green = amount,
             "blue"  => round.blue  = amount,
-            _ => panic!("Unknown color '{color}'")
+            _ => return Err(line.error(color, "'red', 'green' or 'blue'"))
         }
     }
 
-    return round;
+    return Ok(round);
 }
 
-pub fn parse_game(line: &String) -> Game {
-    let mut top_level_fields: std::str::SplitN<'_, char> = line.splitn(2, ':');
-    let game_id: i64 = top_level_fields.next().unwrap()[5..].parse::<i64>().unwrap();
-    let rounds: Vec<Round> = top_level_fields.next().unwrap()
-        .split(';').map(|field| parse_round(field))
-        .collect();
+pub fn parse_game(line: &Line) -> ParseResult<Game> {
+    let (header, rounds) = line.split_once(line.text, ":")?;
+    let game_id: i64 = line.number(line.strip_prefix(header, "Game ")?)?;
+    let rounds: Vec<Round> = rounds
+        .split(';').map(|field| parse_round(line, field))
+        .collect::<ParseResult<Vec<Round>>>()?;
+
+    Ok(Game { id: game_id, rounds })
+}
 
-    Game { id: game_id, rounds: rounds }
+pub fn parse_games(lines: &Vec<String>) -> ParseResult<Vec<Game>> {
+    parse::lines(lines).map(|line| parse_game(&line)).collect()
 }
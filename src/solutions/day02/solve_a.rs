@@ -1,4 +1,5 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day02::common::*;
 
 // This one mostly just comes down to parsing the input strings. I took a couple
@@ -15,9 +16,12 @@ fn is_valid(game: &Game) -> bool {
     return game.rounds.iter().all(|round| round.red <= 12 && round.green <= 13 && round.blue <= 14);
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let result: i64 = lines.iter()
-        .map(|line| parse_game(line))
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Game>> {
+    parse_games(lines)
+}
+
+pub fn solve(games: &Vec<Game>) -> Solution {
+    let result: i64 = games.iter()
         .filter(|game| is_valid(game))
         .map(|game| game.id)
         .sum();
@@ -1,4 +1,5 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day02::common::*;
 
 // Since we already have the logic for parsing a line into a `Game` object,
@@ -15,10 +16,13 @@ fn compute_power(game: &Game) -> i64 {
     return max_red * max_green * max_blue;
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let result: i64 = lines.iter()
-        .map(|line| parse_game(line))
-        .map(|game| compute_power(&game))
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Game>> {
+    parse_games(lines)
+}
+
+pub fn solve(games: &Vec<Game>) -> Solution {
+    let result: i64 = games.iter()
+        .map(compute_power)
         .sum();
 
     return Solution::Integer(result)
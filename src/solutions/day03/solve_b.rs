@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 
 use crate::solutions::Solution;
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day03::common::*;
 
 // Using the grid created in the A part, as well as the helper functions for
@@ -13,29 +15,32 @@ use crate::solutions::day03::common::*;
 // numbers separate by a period directly above or below the star symbol), and
 // explicitly handling this edge case would've been more efficient.
 
-fn compute_gear_ratio(grid: &Grid, star: &(isize, isize)) -> i64 {
-    let digit_neighbors: Vec<(isize, isize)> = grid.get_neighbors(star).into_iter()
-        .filter(|c| grid.get(c).is_digit(10)).collect();
+fn compute_gear_ratio(grid: &Grid<char>, star: &Pos) -> i64 {
+    let digit_neighbors: Vec<Pos> = grid.neighbors8(*star)
+        .filter(|c| grid[*c].is_ascii_digit()).collect();
 
-    let number_starts: HashSet<(isize, isize)> = HashSet::from_iter(digit_neighbors.iter()
-        .map(|cn| find_number_start(grid, cn)));
+    let number_starts: HashSet<Pos> = HashSet::from_iter(digit_neighbors.iter()
+        .map(|cn| find_number_start(grid, *cn)));
 
     if number_starts.len() != 2 {
         return 0
     }
 
-    number_starts.iter().map(|start| parse_number(grid, start)).product()
+    number_starts.iter().map(|start| parse_number(grid, *start)).product()
 }
 
-fn find_stars(grid: &Grid) -> Vec<(isize, isize)> {
-    grid.content.iter().enumerate()
+fn find_stars(grid: &Grid<char>) -> Vec<Pos> {
+    grid.iter()
         .filter(|(_, &ch)| ch == '*')
-        .map(|(i, _)| grid.to_coordinates(i as isize)).collect()
+        .map(|(pos, _)| pos).collect()
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let grid: Grid = Grid::from_lines(lines);
-    let stars: Vec<(isize, isize)> = find_stars(&grid);
-    let result: i64 = stars.iter().map(|star| compute_gear_ratio(&grid, star)).sum();
+pub fn parse(lines: &Vec<String>) -> ParseResult<Grid<char>> {
+    parse_grid(lines)
+}
+
+pub fn solve(grid: &Grid<char>) -> Solution {
+    let stars: Vec<Pos> = find_stars(grid);
+    let result: i64 = stars.iter().map(|star| compute_gear_ratio(grid, star)).sum();
     return Solution::Integer(result)
 }
@@ -1,26 +1,36 @@
 use std::cmp::Ordering;
 
-fn parse_numbers(substring: &str) -> Vec<i64> {
-    substring.split(' ')
-        .filter(|field| field.len() > 0)
-        .map(|field| field.trim().parse().unwrap())
-        .collect()
-}
+use crate::solutions::parse::{self, Line, ParseResult};
+
+// Both lists of numbers are sorted while parsing, which is required for the
+// double iterator in `count_matching_numbers()`.
 
-fn parse_line(line: &String) -> (Vec<i64>, Vec<i64>) {
-    let without_prefix: &str = line.split_once(':').unwrap().1;
-    let (winning_numbers, my_numbers) = without_prefix.split_once('|').unwrap();
-    (parse_numbers(winning_numbers), parse_numbers(my_numbers))
+pub struct Card {
+    winning_numbers: Vec<i64>,
+    my_numbers: Vec<i64>
 }
 
-pub fn count_matching_numbers(line: &String) -> u32 {
-    let (mut winning_numbers, mut my_numbers) = parse_line(line);
+impl Card {
+    pub fn parse(line: &Line) -> ParseResult<Card> {
+        let without_prefix: &str = line.split_once(line.text, ":")?.1;
+        let (winning_numbers, my_numbers) = line.split_once(without_prefix, "|")?;
+        let mut winning_numbers: Vec<i64> = line.numbers(winning_numbers, ' ')?;
+        let mut my_numbers: Vec<i64> = line.numbers(my_numbers, ' ')?;
 
-    winning_numbers.sort();
-    my_numbers.sort();
+        winning_numbers.sort();
+        my_numbers.sort();
+
+        Ok(Card { winning_numbers, my_numbers })
+    }
+}
+
+pub fn parse_cards(lines: &Vec<String>) -> ParseResult<Vec<Card>> {
+    parse::lines(lines).map(|line| Card::parse(&line)).collect()
+}
 
-    let mut winning_numbers_iter = winning_numbers.iter().peekable();
-    let mut my_numbers_iter = my_numbers.iter().peekable();
+pub fn count_matching_numbers(card: &Card) -> u32 {
+    let mut winning_numbers_iter = card.winning_numbers.iter().peekable();
+    let mut my_numbers_iter = card.my_numbers.iter().peekable();
     let mut matches: u32 = 0;
 
     while let (Some(&winning_number), Some(my_number)) = (winning_numbers_iter.peek(), my_numbers_iter.peek()) {
@@ -30,7 +40,7 @@ pub fn count_matching_numbers(line: &String) -> u32 {
             Ordering::Equal   => {
                 winning_numbers_iter.next();
                 my_numbers_iter.next();
-                matches = matches + 1;
+                matches += 1;
             }
         }
     }
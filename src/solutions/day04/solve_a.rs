@@ -1,4 +1,5 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day04::common::*;
 
 // The obvious solution is to dump both lists of numbers into their own set
@@ -9,12 +10,16 @@ use crate::solutions::day04::common::*;
 // `while let`. I did end up writing a quick set-based solution as well,
 // but it was actually slightly slower than the current solution.
 
-fn process_line(line: &String) -> i64 {
-    let matches: u32 = count_matching_numbers(line);
+fn process_card(card: &Card) -> i64 {
+    let matches: u32 = count_matching_numbers(card);
     if matches == 0 { 0 } else { 2_i64.pow(matches - 1) }
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let result: i64 = lines.iter().map(|line| process_line(line)).sum();
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Card>> {
+    parse_cards(lines)
+}
+
+pub fn solve(cards: &Vec<Card>) -> Solution {
+    let result: i64 = cards.iter().map(process_card).sum();
     return Solution::Integer(result)
 }
@@ -1,4 +1,5 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day04::common::*;
 
 // _Obviously_ we aren't going to repeat the calculation to count the matching
@@ -8,17 +9,21 @@ use crate::solutions::day04::common::*;
 // number of matches for the current game, and Y is the number of cards for the
 // current game. In the end, all we have to do is sum up this count array.
 
-fn process_line(line: &String, line_no: usize, counts: &mut Vec<usize>) {
-    let matches: usize = count_matching_numbers(line) as usize;
+fn process_card(card: &Card, card_no: usize, counts: &mut Vec<usize>) {
+    let matches: usize = count_matching_numbers(card) as usize;
 
     for offset in 1 ..= matches {
-        counts[line_no + offset] += counts[line_no];
+        counts[card_no + offset] += counts[card_no];
     }
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let mut counts: Vec<usize> = vec![1; lines.len()];
-    lines.iter().enumerate()
-        .for_each(|(line_no, line)| process_line(line, line_no, &mut counts));
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Card>> {
+    parse_cards(lines)
+}
+
+pub fn solve(cards: &Vec<Card>) -> Solution {
+    let mut counts: Vec<usize> = vec![1; cards.len()];
+    cards.iter().enumerate()
+        .for_each(|(card_no, card)| process_card(card, card_no, &mut counts));
     return Solution::Integer(counts.iter().sum::<usize>() as i64)
 }
@@ -1,33 +1,54 @@
 use std::ops::Range;
 
+use crate::solutions::parse::{self, Line, ParseResult};
+
 pub struct RangeFunction {
     pub source_range: Range<i64>,
     pub desintation_offset: i64,
 }
 
 impl RangeFunction {
-    pub fn parse(line: &String) -> RangeFunction {
-        let values: Vec<i64> = line.split(' ').map(|field| field.parse::<i64>().unwrap()).collect();
+    pub fn parse(line: &Line) -> ParseResult<RangeFunction> {
+        let values: Vec<i64> = line.fixed_numbers(line.text, " ", 3)?;
 
         let destination_start: i64 = values[0];
         let source_start: i64      = values[1];
         let length: i64            = values[2];
 
-        RangeFunction {
+        Ok(RangeFunction {
             desintation_offset: destination_start - source_start,
             source_range: source_start..(source_start + length)
-        }
+        })
     }
 }
 
 pub type RangeMap = Vec<RangeFunction>;
 
-fn parse_map(lines: &[String]) -> RangeMap {
-    lines.iter().map(|line| RangeFunction::parse(line)).collect()
+// The maps follow the line of seeds; every map starts with a header line like
+// "seed-to-soil map:", which is otherwise ignored.
+
+pub fn parse_maps(lines: &Vec<String>) -> ParseResult<Vec<RangeMap>> {
+    let mut range_maps: Vec<RangeMap> = Vec::new();
+
+    for line in parse::lines(lines).skip(1) {
+        if line.text.is_empty() {
+            continue;
+        } else if line.text.ends_with(" map:") {
+            range_maps.push(Vec::new());
+        } else {
+            match range_maps.last_mut() {
+                Some(range_map) => range_map.push(RangeFunction::parse(&line)?),
+                None => return Err(line.error(line.text, "a map header"))
+            }
+        }
+    }
+
+    Ok(range_maps)
 }
 
-pub fn parse_maps(lines: &Vec<String>) -> Vec<RangeMap> {
-    lines.split(|line| line.is_empty()).skip(1)
-        .map(|block| parse_map(&block[1..])).collect()
+pub fn parse_seed_values(lines: &Vec<String>) -> ParseResult<Vec<i64>> {
+    let line: Line = parse::line(lines, 0, "a list of seeds")?;
+    let seeds: &str = line.strip_prefix(line.text, "seeds: ")?;
+    line.numbers(seeds, ' ')
 }
 
@@ -1,4 +1,5 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day05::common::*;
 
 // First parse each map to a list of range functions, each consisting of
@@ -12,9 +13,9 @@ use crate::solutions::day05::common::*;
 // value; if no ranges match, the current value is not modified. After
 // doing this for all seed values, we only need to take the minimum.
 
-fn parse_seeds(line: &String) -> Vec<i64> {
-    let seeds = line.split_once(": ").unwrap().1;
-    seeds.split(' ').map(|seed| seed.parse::<i64>().unwrap()).collect()
+pub struct Almanac {
+    seeds: Vec<i64>,
+    range_maps: Vec<RangeMap>
 }
 
 fn resolve_seed(seed: i64, range_maps: &Vec<RangeMap>) -> i64 {
@@ -32,12 +33,15 @@ fn resolve_seed(seed: i64, range_maps: &Vec<RangeMap>) -> i64 {
     value
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let range_maps: Vec<RangeMap> = parse_maps(lines);
-    let seeds: Vec<i64> = parse_seeds(&lines[0]);
+pub fn parse(lines: &Vec<String>) -> ParseResult<Almanac> {
+    let seeds: Vec<i64> = parse_seed_values(lines)?;
+    let range_maps: Vec<RangeMap> = parse_maps(lines)?;
+    Ok(Almanac { seeds, range_maps })
+}
 
-    let result: i64 = seeds.into_iter()
-        .map(|seed| resolve_seed(seed, &range_maps))
+pub fn solve(almanac: &Almanac) -> Solution {
+    let result: i64 = almanac.seeds.iter()
+        .map(|seed| resolve_seed(*seed, &almanac.range_maps))
         .min().unwrap();
 
     return Solution::Integer(result)
@@ -1,6 +1,7 @@
 use std::ops::Range;
 
 use crate::solutions::Solution;
+use crate::solutions::parse::{self, ParseResult};
 use crate::solutions::day05::common::*;
 
 // We could of course run all possible seed values through the solution of the
@@ -28,6 +29,11 @@ use crate::solutions::day05::common::*;
 // fortunately doesn't happen: even after the seventh map, the output range list
 // only contains 119 entries, and the full solution runs in less than 50µs.
 
+pub struct Almanac {
+    seed_ranges: Vec<Range<i64>>,
+    range_maps: Vec<RangeMap>
+}
+
 fn resolve_range_map(in_ranges: &Vec<Range<i64>>, range_map: &RangeMap) -> Vec<Range<i64>> {
     let mut current_ranges: Vec<Range<i64>> = in_ranges.clone();
     let mut out_ranges: Vec<Range<i64>> = Vec::new();
@@ -106,18 +112,24 @@ fn resolve_seed_ranges(seed_ranges: &Vec<Range<i64>>, range_maps: &Vec<RangeMap>
     ranges.into_iter().map(|r| r.start).min().unwrap()
 }
 
-fn parse_seed_ranges(line: &String) -> Vec<Range<i64>> {
-    let seeds: Vec<i64> = line
-        .split_once(": ").unwrap().1
-        .split(' ').map(|seed| seed.parse::<i64>().unwrap())
-        .collect();
+fn parse_seed_ranges(lines: &Vec<String>) -> ParseResult<Vec<Range<i64>>> {
+    let seeds: Vec<i64> = parse_seed_values(lines)?;
+
+    if !seeds.len().is_multiple_of(2) {
+        let line: parse::Line = parse::line(lines, 0, "a list of seeds")?;
+        return Err(line.error(line.text, "pairs of seed values"));
+    }
+
+    Ok(seeds.chunks(2).map(|chunk| chunk[0]..(chunk[0] + chunk[1])).collect())
+}
 
-    seeds.chunks(2).map(|chunk| chunk[0]..(chunk[0] + chunk[1])).collect()
+pub fn parse(lines: &Vec<String>) -> ParseResult<Almanac> {
+    let seed_ranges: Vec<Range<i64>> = parse_seed_ranges(lines)?;
+    let range_maps: Vec<RangeMap> = parse_maps(lines)?;
+    Ok(Almanac { seed_ranges, range_maps })
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let range_maps: Vec<RangeMap> = parse_maps(lines);
-    let seed_ranges: Vec<Range<i64>> = parse_seed_ranges(&lines[0]);
-    let result: i64 = resolve_seed_ranges(&seed_ranges, &range_maps);
+pub fn solve(almanac: &Almanac) -> Solution {
+    let result: i64 = resolve_seed_ranges(&almanac.seed_ranges, &almanac.range_maps);
     return Solution::Integer(result)
 }
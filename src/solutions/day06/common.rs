@@ -1,14 +1,38 @@
+use crate::solutions::number::isqrt;
+
 pub struct Match {
     pub time: i64,
     pub distance: i64,
 }
 
-fn solve_equation(t: f64, d: f64, sign: f64) -> f64 {
-    (t + sign * (t.powi(2) - 4.0 * (d + 0.5)).sqrt()) / 2.0
+fn travels(t: i128, charge: i128) -> i128 {
+    charge * (t - charge)
+}
+
+// Finds the smallest charge time that beats distance `d`. The integer square
+// root gives us an estimate that is at most one off, which we then correct by
+// checking the actual distance traveled at and around the estimate.
+
+fn find_min_charge(t: i128, d: i128) -> Option<i128> {
+    let root: i128 = isqrt(t * t - 4 * d)?;
+    let mut charge: i128 = ((t - root) / 2).max(0);
+
+    while charge > 0 && travels(t, charge - 1) > d {
+        charge -= 1;
+    }
+
+    while charge <= t / 2 && travels(t, charge) <= d {
+        charge += 1;
+    }
+
+    (charge <= t / 2).then_some(charge)
 }
 
-pub fn count_winning_values(m: Match) -> i64 {
-    let min: f64 = solve_equation(m.time as f64, m.distance as f64, -1.0);
-    let max: f64 = solve_equation(m.time as f64, m.distance as f64,  1.0);
-    max.ceil() as i64 - min.ceil() as i64
+pub fn count_winning_values(m: &Match) -> i64 {
+    let t: i128 = m.time as i128;
+
+    match find_min_charge(t, m.distance as i128) {
+        Some(min) => (t - 2 * min + 1) as i64,
+        None => 0
+    }
 }
@@ -1,4 +1,5 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::{self, Line, ParseResult};
 use crate::solutions::day06::common::*;
 
 // I don't usually go with mathematical solutions to solve these questions - my
@@ -6,36 +7,41 @@ use crate::solutions::day06::common::*;
 // quadratic equation when I see one. Given the time T and our charging time C,
 // the distance traveled D is computed as `D = C * (T - C)`, or `D = TC - C^2`.
 // In order to find the range of `C` for which the distance is higher than the
-// given threshold (plus 0.5 to deal with edge-cases), we just need to solve:
+// given threshold, we just need to solve:
 //
-// -1 * C^2 + T * C - (D + 0.5) = 0
+// -1 * C^2 + T * C - D = 0
 //
 // With the standard quadratic equation solver, this resolves to:
 //
-// C = (T ± sqrt(T^2 - 4 * (D + 0.5))) / 2
+// C = (T ± sqrt(T^2 - 4 * D)) / 2
 //
-// After computing both values - i.e., the two charge times that cap off the
-// winning range - we simply round them up and compute the difference to get
-// the number of winning integer values.
+// The two solutions - i.e., the two charge times that cap off the winning
+// range - lie symmetrically around `T / 2`, so we only need the lower one.
+// Originally, I computed it with floating point numbers, adding 0.5 to the
+// distance to deal with edge cases; it now uses the integer square root of
+// `T^2 - 4 * D` instead, which is at most one off from the lowest winning
+// integer charge time. We correct this estimate by checking the distances
+// traveled around it, which makes the answer exact for any input. Given this
+// lowest winning charge time `L`, the number of winning values is `T - 2L + 1`.
 
-fn parse_values(line: &String) -> Vec<i64> {
-    line.split_once(':')
-        .unwrap().1.split(' ')
-        .filter(|field| !field.is_empty())
-        .map(|field| field.parse::<i64>().unwrap())
-        .collect()
+fn parse_values(line: &Line, prefix: &str) -> ParseResult<Vec<i64>> {
+    line.numbers(line.strip_prefix(line.text, prefix)?, ' ')
 }
 
-fn parse_input(lines: &Vec<String>) -> Vec<Match> {
-    let time_values: Vec<i64> = parse_values(&lines[0]);
-    let distance_values: Vec<i64> = parse_values(&lines[1]);
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Match>> {
+    let distance_line: Line = parse::line(lines, 1, "a line of distances")?;
+    let time_values: Vec<i64> = parse_values(&parse::line(lines, 0, "a line of times")?, "Time:")?;
+    let distance_values: Vec<i64> = parse_values(&distance_line, "Distance:")?;
 
-    time_values.into_iter().zip(distance_values)
-        .map(|(time, distance)| Match { time: time, distance: distance }).collect()
+    if distance_values.len() != time_values.len() {
+        return Err(distance_line.error(distance_line.text, &format!("{} distances", time_values.len())));
+    }
+
+    Ok(time_values.into_iter().zip(distance_values)
+        .map(|(time, distance)| Match { time, distance }).collect())
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let matches: Vec<Match> = parse_input(lines);
-    let result: i64 = matches.into_iter().map(|m| count_winning_values(m)).product();
+pub fn solve(matches: &Vec<Match>) -> Solution {
+    let result: i64 = matches.iter().map(count_winning_values).product();
     return Solution::Integer(result)
 }
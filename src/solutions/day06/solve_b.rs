@@ -1,24 +1,29 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::{self, Line, ParseResult};
 use crate::solutions::day06::common::*;
 
 // Exactly as difficult as the first part, unless you somehow didn't use the
 // quadratic equation solver. The only thing I had to fix was change the type
 // used in the calculation from `f32` to `f64`, since the high numbers in the
-// B part caused rounding errors when using `f32`.
+// B part caused rounding errors when using `f32`. Since the calculation now
+// uses integers only, larger numbers can no longer cause rounding errors.
 
-fn parse_value(line: &String) -> i64 {
-    line.split_once(':').unwrap().1
-        .replace(' ', "").parse().unwrap()
+fn parse_value(line: &Line, prefix: &str) -> ParseResult<i64> {
+    let digits: &str = line.strip_prefix(line.text, prefix)?;
+
+    match digits.replace(' ', "").parse() {
+        Ok(value) => Ok(value),
+        Err(_) => Err(line.error(digits, "a number"))
     }
+}
 
-fn parse_input(lines: &Vec<String>) -> Match {
-    let time: i64 = parse_value(&lines[0]);
-    let distance: i64 = parse_value(&lines[1]);
-    Match { time, distance }
+pub fn parse(lines: &Vec<String>) -> ParseResult<Match> {
+    let time: i64 = parse_value(&parse::line(lines, 0, "a line of times")?, "Time:")?;
+    let distance: i64 = parse_value(&parse::line(lines, 1, "a line of distances")?, "Distance:")?;
+    Ok(Match { time, distance })
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let m: Match = parse_input(lines);
+pub fn solve(m: &Match) -> Solution {
     let result: i64 = count_winning_values(m);
     return Solution::Integer(result)
 }
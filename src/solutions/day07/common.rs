@@ -2,6 +2,8 @@
 
 use std::cmp::Ordering;
 
+use crate::solutions::parse::{Line, ParseResult};
+
 enum HandType {
     FiveOfAKind,
     FourOfAKind,
@@ -29,7 +31,7 @@ impl HandType {
 /* ----------------------------- HandProperties ----------------------------- */
 
 pub struct HandProperties {
-    pub char_to_value: fn(char) -> usize,
+    pub char_to_value: fn(char) -> Option<usize>,
     pub count_cards: fn(&[usize]) -> Vec<usize>
 }
 
@@ -41,10 +43,17 @@ struct Hand {
 }
 
 impl Hand {
-    fn parse(input: &str, properties: &HandProperties) -> Hand {
-        let cards: Vec<usize> = input.chars().map(|c| (properties.char_to_value)(c)).collect();
+    fn parse(line: &Line, input: &str, properties: &HandProperties) -> ParseResult<Hand> {
+        if input.chars().count() != 5 {
+            return Err(line.error(input, "a hand of five cards"));
+        }
+
+        let cards: Vec<usize> = input.char_indices()
+            .map(|(i, c)| (properties.char_to_value)(c).ok_or_else(|| line.error(&input[i .. i + c.len_utf8()], "a card")))
+            .collect::<ParseResult<Vec<usize>>>()?;
+
         let hand_type: HandType = Self::determine_type(&cards, properties.count_cards);
-        Hand { cards: cards, hand_type: hand_type }
+        Ok(Hand { cards, hand_type })
     }
 
     pub fn compare(a: &Hand, b: &Hand) -> Ordering {
@@ -94,13 +103,13 @@ pub struct Row {
 }
 
 impl Row {
-    pub fn parse(input: &str, properties: &HandProperties) -> Row {
-        let (hand_str, bid_str) = input.split_once(' ').unwrap();
+    pub fn parse(line: &Line, properties: &HandProperties) -> ParseResult<Row> {
+        let (hand_str, bid_str) = line.split_once(line.text, " ")?;
 
-        let hand: Hand = Hand::parse(hand_str, properties);
-        let bid: i64 = bid_str.parse().unwrap();
-        
-        Row { hand, bid }
+        let hand: Hand = Hand::parse(line, hand_str, properties)?;
+        let bid: i64 = line.number(bid_str)?;
+
+        Ok(Row { hand, bid })
     }
 
     pub fn compare(a: &Row, b: &Row) -> Ordering {
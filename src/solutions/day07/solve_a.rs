@@ -1,4 +1,5 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::{self, ParseResult};
 use crate::solutions::day07::common::*;
 
 // This mostly comes down to parsing the input and determining what kind of
@@ -20,23 +21,23 @@ use crate::solutions::day07::common::*;
 // algorithm takes less than half a millisecond to sort all 1000 hands in
 // this way, so I wasn't tempted to try and improve on this.
 
-fn char_to_value(c: char) -> usize {
+fn char_to_value(c: char) -> Option<usize> {
     match c {
-        '2' => 0,
-        '3' => 1,
-        '4' => 2,
-        '5' => 3,
-        '6' => 4,
-        '7' => 5,
-        '8' => 6,
-        '9' => 7,
-        'T' => 8,
-        'J' => 9,
-        'Q' => 10,
-        'K' => 11,
-        'A' => 12,
-
-        _ => panic!("Invalid character '{c}'")
+        '2' => Some(0),
+        '3' => Some(1),
+        '4' => Some(2),
+        '5' => Some(3),
+        '6' => Some(4),
+        '7' => Some(5),
+        '8' => Some(6),
+        '9' => Some(7),
+        'T' => Some(8),
+        'J' => Some(9),
+        'Q' => Some(10),
+        'K' => Some(11),
+        'A' => Some(12),
+
+        _ => None
     }
 }
 
@@ -47,9 +48,13 @@ fn count_cards(cards: &[usize]) -> Vec<usize> {
     counts.to_vec()
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Row>> {
     let properties: HandProperties = HandProperties { char_to_value, count_cards };
-    let mut rows: Vec<Row> = lines.iter().map(|line| Row::parse(line, &properties)).collect();
+    parse::lines(lines).map(|line| Row::parse(&line, &properties)).collect()
+}
+
+pub fn solve(rows: &Vec<Row>) -> Solution {
+    let mut rows: Vec<&Row> = rows.iter().collect();
     rows.sort_by(|a, b| Row::compare(a, b));
 
     let result: i64 = rows.iter().enumerate()
@@ -1,4 +1,5 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::{self, ParseResult};
 use crate::solutions::day07::common::*;
 
 // Compared to the A part, there are only two things we really need to change
@@ -12,23 +13,23 @@ use crate::solutions::day07::common::*;
 // conveniently stored at the very start, since 'J' maps to index 0), and
 // then add this joker count to the highest value in this sorted array.
 
-fn char_to_value(c: char) -> usize {
+fn char_to_value(c: char) -> Option<usize> {
     match c {
-        'J' => 0,
-        '2' => 1,
-        '3' => 2,
-        '4' => 3,
-        '5' => 4,
-        '6' => 5,
-        '7' => 6,
-        '8' => 7,
-        '9' => 8,
-        'T' => 9,
-        'Q' => 10,
-        'K' => 11,
-        'A' => 12,
-
-        _ => panic!("Invalid character '{c}'")
+        'J' => Some(0),
+        '2' => Some(1),
+        '3' => Some(2),
+        '4' => Some(3),
+        '5' => Some(4),
+        '6' => Some(5),
+        '7' => Some(6),
+        '8' => Some(7),
+        '9' => Some(8),
+        'T' => Some(9),
+        'Q' => Some(10),
+        'K' => Some(11),
+        'A' => Some(12),
+
+        _ => None
     }
 }
 
@@ -43,9 +44,13 @@ fn count_cards(cards: &[usize]) -> Vec<usize> {
     remaining_counts
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Row>> {
     let properties: HandProperties = HandProperties { char_to_value, count_cards };
-    let mut rows: Vec<Row> = lines.iter().map(|line| Row::parse(line, &properties)).collect();
+    parse::lines(lines).map(|line| Row::parse(&line, &properties)).collect()
+}
+
+pub fn solve(rows: &Vec<Row>) -> Solution {
+    let mut rows: Vec<&Row> = rows.iter().collect();
     rows.sort_by(|a, b| Row::compare(a, b));
 
     let result: i64 = rows.iter().enumerate()
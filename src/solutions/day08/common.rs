@@ -1,29 +1,74 @@
 use std::collections::HashMap;
 
-pub enum Direction {
-    Left,
-    Right
-}
+use crate::solutions::geometry::Turn;
+use crate::solutions::parse::{self, Line, ParseResult};
+
+pub fn parse_directions(line: &Line) -> ParseResult<Vec<Turn>> {
+    if line.text.is_empty() {
+        return Err(line.error(line.text, "a list of directions"));
+    }
 
-pub fn parse_directions(line: &str) -> Vec<Direction> {
-    line.chars().map(|c| {
-        match c {
-            'L' => Direction::Left,
-            'R' => Direction::Right,
-            _ => panic!()
+    line.text.chars().enumerate().map(|(index, c)| {
+        match Turn::parse(c) {
+            Some(turn) => Ok(turn),
+            None => Err(line.error(line.char_at(index), "'L' or 'R'"))
         }
     }).collect()
 }
 
-pub fn parse_node_names(lines: &[String]) -> HashMap<String, usize> {
-    lines.iter().enumerate()
-        .map(|(index, line)| (String::from(&line[0..3]), index))
-        .collect()
+// Splits a line of the form "AAA = (BBB, CCC)" into the names of the node and
+// its left and right neighbors.
+
+fn split_node<'a>(line: &Line<'a>) -> ParseResult<(&'a str, &'a str, &'a str)> {
+    let (name, links) = line.split_once(line.text, " = ")?;
+    let links: &str = line.strip_suffix(line.strip_prefix(links, "(")?, ")")?;
+    let (left, right) = line.split_once(links, ", ")?;
+    Ok((name, left, right))
+}
+
+// Maps the name of every node to its index; every node may only be defined
+// once, since a later definition would otherwise silently replace it.
+
+pub fn parse_node_names(lines: &[Line]) -> ParseResult<HashMap<String, usize>> {
+    let mut name_to_index: HashMap<String, usize> = HashMap::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let name: &str = split_node(line)?.0;
+
+        if name_to_index.insert(String::from(name), index).is_some() {
+            return Err(line.error(name, "the name of a node that is not defined yet"));
+        }
+    }
+
+    Ok(name_to_index)
 }
 
-pub fn parse_node_links(lines: &[String], name_to_index: &HashMap<String, usize>) -> Vec<(usize, usize)> {
+pub fn parse_node_links(lines: &[Line], name_to_index: &HashMap<String, usize>) -> ParseResult<Vec<(usize, usize)>> {
+    let find_node = |line: &Line, name: &str| match name_to_index.get(name) {
+        Some(index) => Ok(*index),
+        None => Err(line.error(name, "the name of a node"))
+    };
+
     lines.iter()
-        .map(|line| (&line[7..10], &line[12..15]))
-        .map(|(l, r)| (name_to_index[l], name_to_index[r]))
+        .map(|line| {
+            let (_, left, right) = split_node(line)?;
+            Ok((find_node(line, left)?, find_node(line, right)?))
+        })
         .collect()
 }
+
+pub struct Network {
+    pub directions: Vec<Turn>,
+    pub name_to_index: HashMap<String, usize>,
+    pub node_links: Vec<(usize, usize)>
+}
+
+impl Network {
+    pub fn parse(lines: &Vec<String>) -> ParseResult<Network> {
+        let directions: Vec<Turn> = parse_directions(&parse::line(lines, 0, "a list of directions")?)?;
+        let node_lines: Vec<Line> = parse::lines(lines).skip(2).collect();
+        let name_to_index: HashMap<String, usize> = parse_node_names(&node_lines)?;
+        let node_links: Vec<(usize, usize)> = parse_node_links(&node_lines, &name_to_index)?;
+        Ok(Network { directions, name_to_index, node_links })
+    }
+}
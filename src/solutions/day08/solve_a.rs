@@ -1,6 +1,7 @@
-use std::collections::HashMap;
-
 use crate::solutions::Solution;
+use crate::solutions::cancel;
+use crate::solutions::geometry::Turn;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day08::common::*;
 
 // First create a map of node names to indices, where the index is simply
@@ -9,38 +10,48 @@ use crate::solutions::day08::common::*;
 // indicating the next node index when moving left or right, respectively.
 // Once we've got this list of links, and we've found the start and end
 // nodes, we simply follow the directions from the start node until
-// we've reached the end node, and return the number of steps taken.
+// we've reached the end node, and return the number of steps taken. (If the
+// end node can't be reached, this never finishes, so after every pass through
+// the directions we check whether the runner has cancelled the solver.)
 
 fn count_steps(
     node_links: &Vec<(usize, usize)>,
-    directions: &Vec<Direction>,
+    directions: &Vec<Turn>,
     start_index: usize,
-    end_index: usize) -> i64 {
+    end_index: usize) -> Option<i64> {
     let mut current_index: usize = start_index;
     let mut step_count: usize = 0;
 
     loop {
         current_index = match directions[step_count % directions.len()] {
-            Direction::Left  => node_links[current_index].0,
-            Direction::Right => node_links[current_index].1
+            Turn::Left  => node_links[current_index].0,
+            Turn::Right => node_links[current_index].1
         };
 
         step_count += 1;
 
         if current_index == end_index {
-            return step_count as i64
+            return Some(step_count as i64)
+        }
+
+        if step_count.is_multiple_of(directions.len()) && cancel::requested() {
+            return None
         }
     }
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let directions: Vec<Direction> = parse_directions(&lines[0]);
-    let name_to_index: HashMap<String, usize> = parse_node_names(&lines[2..]);
-    let node_links: Vec<(usize, usize)> = parse_node_links(&lines[2..], &name_to_index);
-    let start_index: usize = name_to_index["AAA"];
-    let end_index: usize = name_to_index["ZZZ"];
-    
-    let result: i64 = count_steps(&node_links, &directions, start_index, end_index);
+pub fn parse(lines: &Vec<String>) -> ParseResult<Network> {
+    Network::parse(lines)
+}
+
+pub fn solve(network: &Network) -> Solution {
+    let start_index: usize = network.name_to_index["AAA"];
+    let end_index: usize = network.name_to_index["ZZZ"];
     
-    return Solution::Integer(result)
+    let result: Option<i64> = count_steps(&network.node_links, &network.directions, start_index, end_index);
+
+    match result {
+        Some(steps) => Solution::Integer(steps),
+        None        => Solution::unsolved("cancelled before reaching the end node")
+    }
 }
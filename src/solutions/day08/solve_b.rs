@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 
 use crate::solutions::Solution;
+use crate::solutions::cycle::{self, Cycle, History};
+use crate::solutions::geometry::Turn;
+use crate::solutions::number::{self, Congruence, CrtError};
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day08::common::*;
 
 // This one was tough. I tried the naive approach first - simply trace all paths
@@ -10,64 +14,62 @@ use crate::solutions::day08::common::*;
 //
 // Instead, we look for loops. If we trace a path from any start node, it will
 // inevitable start to loop; more specifically, it will enter a loop after `O`
-// steps, and will loop back after every addition `P` steps; both `O` and `P`
-// will be multiples of the number of directions `D`. We search for these loops
-// in the `find_loops()` function by checking every `D` steps if the current node
-// was already visited at an earlier multiple of `D` steps. In addition, we track
-// at which step counts along the way we encountered end nodes (every loop should
-// contain at least one end node). Once we've found the loop, we record the offset
-// of each encountered end node, as well as the period of the full loop.
+// steps, and will loop back after every addition `P` steps, where `P` will be
+// a multiple of the number of directions `D`. We search for these loops in the
+// `find_loops()` function using the shared cycle detector, with the current node
+// and the current position in the list of directions as the state. The detector
+// keeps all states up to the end of the first loop, in which we then look up the
+// step counts at which we encountered end nodes, both on the way to the loop
+// and inside it; we record all of these, along with the offset and period of
+// the loop.
 //
-// (In the actual input, each loop contains exactly one end node, meaning we
-// could theoretically change the `offsets` field of `Loop` to a single value.
-// However, this just happens to be the case for this particular input; the
-// sample of the B part, for example, encounters the same end node twice in
-// one loop, resulting in two offset values.)
+// (In the actual input, each loop contains exactly one end node, and there are
+// no end nodes before the loop. However, this just happens to be the case for
+// this particular input; the sample of the B part, for example, encounters
+// end nodes twice in one loop, resulting in two end offsets.)
 //
 // Once we've found the loop for each of the start nodes, we need to find the
-// lowest value common to all these loops. There is probably a smart mathemical
-// way to do this, but I couldn't figure it out for the life of me. Instead, I
-// start at one offset, and increase the step count by the period of this loop
-// until I find a step count for which one of the other loops is also at an end
-// node. At this point, I increase the step size to the LCM of both loop periods;
-// this allows me to make larger steps, while ensuring that both loops will be
-// at an end node after every step. I then repeat this for the other loops
-// (growing the step size every time) until I've incorperated all of them,
-// at which point I return the current step count.
+// lowest step count at which every path is at an end node. My first attempt at
+// this stepped through the end nodes of one loop, growing the step size to the
+// LCM of the periods of the loops it had already lined up with, but that never
+// finished if two loops never line up at all (as in the sample, where one loop
+// has its end node at offset 2 with period 2, and the other at offsets 3 and 6
+// with period 6, and it depended on the order of the loops whether it got
+// stuck on offset 3).
 //
-// Note that this doesn't quite work for the sample; since the loop with offset
-// 2 and period 2 never intersects with the one with offset 3 and period 6, we
-// end up in an infinite loop. It does work for the actual input though (and
-// pretty fast too; against all odds I managed to keep it under one milli-
-// second), and I've already spent way too much time on this, so whatever.
+// The proper way to do this is with the Chinese Remainder Theorem. Once every
+// path has entered its loop, a path is at an end node after `S` steps exactly
+// when `S` is congruent to one of the end offsets of its loop, modulo the loop
+// period. Picking one such congruence for every loop and combining them gives
+// us all step counts that line up for that particular choice (or none, if the
+// congruences contradict each other, which the CRT tells us right away), so we
+// try every combination of end offsets and take the lowest step count. Before
+// that, we check the steps before all paths have entered their loops one by
+// one, since end nodes that are passed on the way to a loop are only reached
+// once. If no combination has a solution either, the paths never line up, and
+// we report the puzzle as unsolved for that reason instead of searching
+// forever. Since the number of combinations is the product of the number of
+// end offsets of every loop, we also give up if there are too many of them.
+
+// The end nodes of a path, given as the numbers of steps after which the path
+// is at an end node, up to the end of the first time through its loop.
 
-#[derive(Clone)]
 struct Loop {
-    offsets: Vec<usize>,
-    period: usize
+    cycle: Cycle,
+    ends: Vec<usize>
 }
 
 impl Loop {
     fn is_end(&self, steps: usize) -> bool {
-        self.offsets.iter().any(|o| steps >= *o && (steps - o) % self.period == 0)
+        self.ends.contains(&self.cycle.reduce(steps))
     }
-}
-
-fn gcd(a: usize, b: usize) -> usize {
-    let mut aa = a;
-    let mut bb = b;
 
-    while bb != 0 {
-        let t: usize = bb;
-        bb = aa % bb;
-        aa = t;
+    fn congruences(&self) -> Vec<Congruence> {
+        self.ends.iter()
+            .filter(|end| **end >= self.cycle.offset)
+            .map(|end| Congruence::new(*end as i128, self.cycle.period as i128))
+            .collect()
     }
-
-    return aa
-}
-
-fn lcm(a: usize, b: usize) -> usize {
-    a * (b / gcd(a, b))
 }
 
 pub fn find_nodes(name_to_index: &HashMap<String, usize>, last_char: char) -> Vec<usize> {
@@ -78,82 +80,67 @@ pub fn find_nodes(name_to_index: &HashMap<String, usize>, last_char: char) -> Ve
 }
 
 fn find_loops(
-    node_links: Vec<(usize, usize)>,
-    directions: Vec<Direction>,
+    node_links: &Vec<(usize, usize)>,
+    directions: &Vec<Turn>,
     start_indices: Vec<usize>,
     end_indices: Vec<usize>) -> Vec<Loop> {
     start_indices.into_iter().map(|start_index| {
-        let mut loop_start_indices: Vec<Option<usize>> = vec![None; node_links.len()];
-        let mut end_nodes_steps: Vec<usize> = Vec::new();
-        let mut current_index: usize = start_index;
-        let mut direction_index: usize = 0;
-        let mut step_count: usize = 0;
-
-        loop {
-            if direction_index == 0 {
-                if let Some(previous_step_count) = loop_start_indices[current_index] {
-                    let offsets: Vec<usize> = end_nodes_steps.into_iter()
-                        .filter(|steps| *steps >= previous_step_count).collect();
-                    return Loop { offsets: offsets, period: step_count - previous_step_count }
-                }
-
-                loop_start_indices[current_index] = Some(step_count);
-            }
-
-            if end_indices.contains(&current_index) {
-                end_nodes_steps.push(step_count);
-            }
-
-            current_index = match directions[direction_index] {
-                Direction::Left  => node_links[current_index].0,
-                Direction::Right => node_links[current_index].1
+        let history: History<(usize, usize)> = cycle::detect((start_index, 0), |(node_index, direction_index)| {
+            let next_index: usize = match directions[*direction_index] {
+                Turn::Left  => node_links[*node_index].0,
+                Turn::Right => node_links[*node_index].1
             };
 
-            direction_index += 1;
-            step_count += 1;
+            (next_index, (direction_index + 1) % directions.len())
+        });
+
+        let ends: Vec<usize> = (0 .. history.states.len())
+            .filter(|steps| end_indices.contains(&history.states[*steps].0))
+            .collect();
 
-            if direction_index == directions.len() {
-                direction_index = 0;
-            }
-        }
+        Loop { cycle: history.cycle, ends }
     }).collect()
 }
 
-fn find_min_steps(loops: Vec<Loop>) -> i64 {
-    let first_loop: &Loop = loops.first().unwrap();
-    
-    first_loop.offsets.iter().map(|offset| {
-        let mut other_loops: Vec<Loop> = loops[1..].to_vec();
-        let mut period: usize = first_loop.period;
-        let mut steps: usize = *offset;
-
-        loop {
-            if let Some((index, other_loop)) = other_loops.iter().enumerate()
-                .find(|(_, other_loop)| other_loop.is_end(steps)) {
-                period = lcm(period, other_loop.period);
-                other_loops.remove(index);
-                continue;
-            }
-
-            if other_loops.is_empty() {
-                return steps;
-            }
-
-            steps += period;
-        }
-    }).min().unwrap() as i64
+// Returns the lowest number of steps after which every path is at an end node,
+// or `None` if that never happens.
+
+fn find_min_steps(loops: &Vec<Loop>) -> Result<Option<i128>, CrtError> {
+    let start: usize = loops.iter().map(|l| l.cycle.offset).max().unwrap_or(0);
+
+    if let Some(steps) = (0 .. start).find(|steps| loops.iter().all(|l| l.is_end(*steps))) {
+        return Ok(Some(steps as i128));
+    }
+
+    let groups: Vec<Vec<Congruence>> = loops.iter().map(|l| l.congruences()).collect();
+    let combined: Vec<Congruence> = number::combine_all(&groups)?;
+
+    // The lowest step count of each combination that is not below `start`.
+
+    Ok(combined.iter().map(|congruence| {
+        let missing: i128 = (start as i128 - congruence.residue).max(0);
+        congruence.residue + (missing + congruence.modulus - 1) / congruence.modulus * congruence.modulus
+    }).min())
+}
+
+pub fn parse(lines: &Vec<String>) -> ParseResult<Network> {
+    Network::parse(lines)
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let directions: Vec<Direction> = parse_directions(&lines[0]);
-    let name_to_index: HashMap<String, usize> = parse_node_names(&lines[2..]);
-    let node_links: Vec<(usize, usize)> = parse_node_links(&lines[2..], &name_to_index);
+pub fn solve(network: &Network) -> Solution {
+    let start_indices: Vec<usize> = find_nodes(&network.name_to_index, 'A');
+    let end_indices: Vec<usize> = find_nodes(&network.name_to_index, 'Z');
 
-    let start_indices: Vec<usize> = find_nodes(&name_to_index, 'A');
-    let end_indices: Vec<usize> = find_nodes(&name_to_index, 'Z');
+    let loops: Vec<Loop> = find_loops(&network.node_links, &network.directions, start_indices, end_indices);
 
-    let loops: Vec<Loop> = find_loops(node_links, directions, start_indices, end_indices);
-    let result: i64 = find_min_steps(loops);
+    let result: i64 = match find_min_steps(&loops) {
+        Ok(Some(steps)) => match i64::try_from(steps) {
+            Ok(steps) => steps,
+            Err(_) => return Solution::unsolved("step count does not fit in 64 bits")
+        },
+        Ok(None) => return Solution::unsolved("the paths never line up"),
+        Err(error) => return Solution::unsolved(&error.to_string())
+    };
 
     return Solution::Integer(result)
 }
@@ -1,3 +1,5 @@
-pub fn parse_values(line: &str) -> Vec<i64> {
-    line.split(' ').map(|field| field.parse().unwrap()).collect()
+use crate::solutions::parse::{self, ParseResult};
+
+pub fn parse_histories(lines: &Vec<String>) -> ParseResult<Vec<Vec<i64>>> {
+    parse::lines(lines).map(|line| line.numbers(line.text, ' ')).collect()
 }
@@ -1,4 +1,5 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day09::common::*;
 
 // Same as the A part, the only thing we need to change is the very last line
@@ -19,12 +20,11 @@ fn recurse(values: &Vec<i64>) -> i64 {
     values.first().unwrap() - recurse(&next_values)
 }
 
-fn solve_line(line: &str) -> i64 {
-    let values: Vec<i64> = parse_values(line);
-    recurse(&values)
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Vec<i64>>> {
+    parse_histories(lines)
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let result: i64 = lines.iter().map(|line| solve_line(line)).sum();
+pub fn solve(histories: &Vec<Vec<i64>>) -> Solution {
+    let result: i64 = histories.iter().map(recurse).sum();
     return Solution::Integer(result)
 }
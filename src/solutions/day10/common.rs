@@ -1,81 +1,84 @@
-/* -------------------------------- Direction ------------------------------- */
-
-pub enum Direction {
-    North,
-    East,
-    South,
-    West,
-    Done
-}
+use crate::solutions::geometry::Direction;
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::parse::{ParseError, ParseResult};
 
 /* ---------------------------------- Step ---------------------------------- */
 
 pub struct Step {
-    pub coordinates: (isize, isize),
+    pub coordinates: Pos,
     pub from: Direction
 }
 
-/* ---------------------------------- Grid ---------------------------------- */
+impl Step {
+    // The steps from the start tile into each of its four neighbors.
 
-pub struct Grid {
-    pub content: Vec<char>,
-    pub rows: isize,
-    pub cols: isize
+    pub fn initial(start: Pos) -> Vec<Step> {
+        Direction::ALL.into_iter()
+            .map(|dir| Step { coordinates: dir.step(start), from: dir.reverse() })
+            .collect()
+    }
 }
 
-impl Grid {
-    pub fn from_lines(lines: &Vec<String>) -> Grid {
-        Grid {
-            content: lines.concat().chars().collect(),
-            rows: lines.len() as isize,
-            cols: lines.first().unwrap().len() as isize
-        }
-    }
+// The result of following the pipe for one step: either the next step, or the
+// conclusion that we have arrived back at the start tile.
 
-    pub fn find_start(&self) -> (isize, isize) {
-        let index: usize = self.content.iter().enumerate().find(|(_, &c)| c == 'S').unwrap().0;
-        self.to_coordinates(index as isize)
-    }
+pub enum Resolved {
+    Step(Step),
+    Start
+}
 
-    pub fn contains(&self, c: &(isize, isize)) -> bool {
-        c.0 >= 0 && c.0 < self.rows && c.1 >= 0 && c.1 < self.cols
-    }
+/* ---------------------------------- Grid ---------------------------------- */
 
-    pub fn to_index(&self, c: &(isize, isize)) -> usize {
-        (c.0 * self.cols + c.1) as usize
-    }
+pub fn parse_grid(lines: &Vec<String>) -> ParseResult<Grid<char>> {
+    let grid: Grid<char> = Grid::parse(lines, 0, "a pipe, '.' or 'S'", |c| "|-LJ7F.S".contains(c).then_some(c))?;
 
-    pub fn to_coordinates(&self, i: isize) -> (isize, isize) {
-        (i / self.cols, i % self.cols)
+    if grid.find(|&c| c == 'S').is_none() {
+        return Err(ParseError::input(lines, "a start tile 'S'", ""));
     }
+
+    Ok(grid)
+}
+
+pub fn find_start(grid: &Grid<char>) -> Pos {
+    grid.find(|&c| c == 'S').unwrap()
 }
 
 /* ------------------------------- Main logic ------------------------------- */
 
-pub fn resolve_step(grid: &Grid, step: &Step) -> Option<Step> {
-    let c: &(isize, isize) = &step.coordinates;
-    let from: &Direction = &step.from;
+// The two directions in which a pipe tile connects to its neighbors.
 
-    if !grid.contains(c) {
-        return None
+fn connections(ch: char) -> Option<(Direction, Direction)> {
+    match ch {
+        '|' => Some((Direction::North, Direction::South)),
+        '-' => Some((Direction::East,  Direction::West)),
+        'L' => Some((Direction::North, Direction::East)),
+        'J' => Some((Direction::North, Direction::West)),
+        '7' => Some((Direction::South, Direction::West)),
+        'F' => Some((Direction::South, Direction::East)),
+        _ => None
     }
+}
+
+// Enters a pipe tile from the direction we came from, and leaves it through
+// its other connection, which makes us enter the next tile from the opposite
+// side. Returns `None` if the tile does not connect to the direction we came
+// from (or is not a pipe at all, or lies outside of the grid).
 
-    let ch = grid.content[(c.0 * grid.cols + c.1) as usize];
-
-    match (ch, from) {
-        ('|', Direction::North) => Some(Step { coordinates: (c.0 + 1, c.1), from: Direction::North }),
-        ('|', Direction::South) => Some(Step { coordinates: (c.0 - 1, c.1), from: Direction::South }),
-        ('-', Direction::West ) => Some(Step { coordinates: (c.0, c.1 + 1), from: Direction::West  }),
-        ('-', Direction::East ) => Some(Step { coordinates: (c.0, c.1 - 1), from: Direction::East  }),
-        ('L', Direction::North) => Some(Step { coordinates: (c.0, c.1 + 1), from: Direction::West  }),
-        ('L', Direction::East ) => Some(Step { coordinates: (c.0 - 1, c.1), from: Direction::South }),
-        ('J', Direction::North) => Some(Step { coordinates: (c.0, c.1 - 1), from: Direction::East  }),
-        ('J', Direction::West ) => Some(Step { coordinates: (c.0 - 1, c.1), from: Direction::South }),
-        ('7', Direction::South) => Some(Step { coordinates: (c.0, c.1 - 1), from: Direction::East  }),
-        ('7', Direction::West ) => Some(Step { coordinates: (c.0 + 1, c.1), from: Direction::North }),
-        ('F', Direction::South) => Some(Step { coordinates: (c.0, c.1 + 1), from: Direction::West  }),
-        ('F', Direction::East ) => Some(Step { coordinates: (c.0 + 1, c.1), from: Direction::North }),
-        ('S', _) => Some(Step { coordinates: (c.0, c.1), from: Direction::Done }),
-        (  _, _) => None
+pub fn resolve_step(grid: &Grid<char>, step: &Step) -> Option<Resolved> {
+    let ch: char = *grid.get(step.coordinates)?;
+
+    if ch == 'S' {
+        return Some(Resolved::Start);
     }
+
+    let (a, b) = connections(ch)?;
+    let exit: Direction = if step.from == a {
+        b
+    } else if step.from == b {
+        a
+    } else {
+        return None
+    };
+
+    Some(Resolved::Step(Step { coordinates: exit.step(step.coordinates), from: exit.reverse() }))
 }
@@ -1,4 +1,6 @@
 use crate::solutions::Solution;
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day10::common::*;
 
 // From the starting position, try following the pipe in each of the four
@@ -8,44 +10,40 @@ use crate::solutions::day10::common::*;
 // fields not being connected properly), we return the total step count
 // divided by two. In each step, we keep track of the field coordinates
 // and the cardinal direction we came from, allowing us to determine the
-// next field (if any) using the big `match` statement in `resolve_step()`.
+// next field (if any) from the connections of the pipe in `resolve_step()`.
 
-fn resolve(grid: &Grid, initial_step: Step) -> Option<i64> {
+fn resolve(grid: &Grid<char>, initial_step: Step) -> Option<i64> {
     let mut step: Step = initial_step;
     let mut step_count: i64 = 0;
 
     loop {
-        let next_step_option: Option<Step> = resolve_step(grid, &step);
+        let next_step_option: Option<Resolved> = resolve_step(grid, &step);
 
         match next_step_option {
             None => return None,
-            Some(next_step) => match next_step.from {
-                Direction::Done => {
-                    step_count += 1;
-                    return Some(step_count / 2);
-                },
-                _ => {
-                    step = next_step;
-                    step_count += 1;
-                }
+            Some(Resolved::Start) => {
+                step_count += 1;
+                return Some(step_count / 2);
+            },
+            Some(Resolved::Step(next_step)) => {
+                step = next_step;
+                step_count += 1;
             }
         }
     }
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let grid: Grid = Grid::from_lines(lines);
-    let start_pos: (isize, isize) = grid.find_start();
+pub fn parse(lines: &Vec<String>) -> ParseResult<Grid<char>> {
+    parse_grid(lines)
+}
+
+pub fn solve(grid: &Grid<char>) -> Solution {
+    let start_pos: Pos = find_start(grid);
 
-    let initial_steps: Vec<Step> = vec!(
-        Step { coordinates: (start_pos.0 + 1, start_pos.1), from: Direction::North },
-        Step { coordinates: (start_pos.0 - 1, start_pos.1), from: Direction::South },
-        Step { coordinates: (start_pos.0, start_pos.1 + 1), from: Direction::West  },
-        Step { coordinates: (start_pos.0, start_pos.1 - 1), from: Direction::East  },
-    );
+    let initial_steps: Vec<Step> = Step::initial(start_pos);
 
     let result: i64 = initial_steps.into_iter()
-        .find_map(|step| resolve(&grid, step)).unwrap();
+        .find_map(|step| resolve(grid, step)).unwrap();
 
     return Solution::Integer(result)
 }
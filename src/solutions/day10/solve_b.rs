@@ -1,4 +1,7 @@
 use crate::solutions::Solution;
+use crate::solutions::geometry::Direction;
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day10::common::*;
 
 // While tracing the loop like we did in the A part, we mark fields to
@@ -31,75 +34,65 @@ use crate::solutions::day10::common::*;
 // real input. Introducing the `offset` loop fixed the issue.
 
 struct Tracker {
-    pipes: Vec<bool>,
-    marked: Vec<bool>
+    pipes: Grid<bool>,
+    marked: Grid<bool>
 }
 
 impl Tracker {
-    fn new(grid: &Grid) -> Tracker {
-        let size: usize = (grid.rows * grid.cols) as usize;
-        let pipes: Vec<bool> = vec![false; size];
-        let marked: Vec<bool> = vec![false; size];
-        Tracker { pipes: pipes, marked: marked }
+    fn new(grid: &Grid<char>) -> Tracker {
+        let pipes: Grid<bool> = Grid::filled(grid.rows, grid.cols, false);
+        let marked: Grid<bool> = Grid::filled(grid.rows, grid.cols, false);
+        Tracker { pipes, marked }
     }
 
-    fn mark(&mut self, grid: &Grid, step: &Step) -> bool {
-        let pipe_index: usize = grid.to_index(&step.coordinates);
-        self.marked[pipe_index] = false;
-        self.pipes[pipe_index] = true;
+    fn mark(&mut self, grid: &Grid<char>, step: &Step) -> bool {
+        self.marked[step.coordinates] = false;
+        self.pipes[step.coordinates] = true;
 
         for offset in 0 ..= 1 {
-            let mark_coordinates: (isize, isize) = match step.from {
-                Direction::North => (step.coordinates.0 - offset, step.coordinates.1 + 1),
-                Direction::East  => (step.coordinates.0 + 1, step.coordinates.1 + offset),
-                Direction::South => (step.coordinates.0 + offset, step.coordinates.1 - 1),
-                Direction::West  => (step.coordinates.0 - 1, step.coordinates.1 - offset),
-                Direction::Done  => { return true; }
+            let c: Pos = step.coordinates;
+            let mark_coordinates: Pos = match step.from {
+                Direction::North => Pos::new(c.row - offset, c.col + 1),
+                Direction::East  => Pos::new(c.row + 1, c.col + offset),
+                Direction::South => Pos::new(c.row + offset, c.col - 1),
+                Direction::West  => Pos::new(c.row - 1, c.col - offset)
             };
 
-            if !grid.contains(&mark_coordinates) {
+            if !grid.contains(mark_coordinates) {
                 return false;
             }
 
-            let mark_index: usize = grid.to_index(&mark_coordinates);
-
-            if !self.pipes[mark_index] {
-                self.marked[mark_index] = true;
+            if !self.pipes[mark_coordinates] {
+                self.marked[mark_coordinates] = true;
             }
         }
 
         true
     }
 
-    fn find_enclosed_size(&self, grid: &Grid) -> Option<i64> {
-        let size: usize = (grid.rows * grid.cols) as usize;
-        let mut processed: Vec<bool> = vec![false; size];
+    fn find_enclosed_size(&self, grid: &Grid<char>) -> Option<i64> {
+        let mut processed: Grid<bool> = Grid::filled(grid.rows, grid.cols, false);
 
         let mut process_index: usize = 0;
         let mut enclosed_count: i64 = 0;
-        let mut to_process: Vec<(isize, isize)> = self.marked.iter().enumerate()
-            .filter(|(_, &m)| m).map(|(i, _)| grid.to_coordinates(i as isize)).collect();
+        let mut to_process: Vec<Pos> = self.marked.iter()
+            .filter(|(_, &m)| m).map(|(pos, _)| pos).collect();
 
         while process_index < to_process.len() {
-            let current_coordinates: (isize, isize) = to_process[process_index];
+            let current_coordinates: Pos = to_process[process_index];
 
-            if !grid.contains(&current_coordinates) {
+            if !grid.contains(current_coordinates) {
                 return None
             }
 
-            let current_index: usize = grid.to_index(&current_coordinates);
-
-            if processed[current_index] || self.pipes[current_index] {
+            if processed[current_coordinates] || self.pipes[current_coordinates] {
                 process_index += 1;
                 continue;
             }
 
-            to_process.push((current_coordinates.0 - 1, current_coordinates.1));
-            to_process.push((current_coordinates.0 + 1, current_coordinates.1));
-            to_process.push((current_coordinates.0, current_coordinates.1 - 1));
-            to_process.push((current_coordinates.0, current_coordinates.1 + 1));
+            to_process.extend(current_coordinates.neighbors());
 
-            processed[current_index] = true;
+            processed[current_coordinates] = true;
             enclosed_count += 1;
             process_index += 1;
         }
@@ -108,48 +101,44 @@ impl Tracker {
     }
 }
 
-fn resolve(grid: &Grid, initial_step: Step) -> Option<Tracker> {
+fn resolve(grid: &Grid<char>, initial_step: Step) -> Option<Tracker> {
     let mut tracker: Tracker = Tracker::new(grid);
     let mut step: Step = initial_step;
 
     loop {
-        let next_step_option: Option<Step> = resolve_step(grid, &step);
+        let next_step_option: Option<Resolved> = resolve_step(grid, &step);
 
         match next_step_option {
             None => return None,
-            Some(next_step) => match next_step.from {
-                Direction::Done => {
-                    tracker.mark(grid, &step);
-                    return Some(tracker);
-                },
-                _ => {
-                    let valid: bool = tracker.mark(grid, &step);
-
-                    if !valid {
-                        return None
-                    }
-
-                    step = next_step;
+            Some(Resolved::Start) => {
+                tracker.mark(grid, &step);
+                return Some(tracker);
+            },
+            Some(Resolved::Step(next_step)) => {
+                let valid: bool = tracker.mark(grid, &step);
+
+                if !valid {
+                    return None
                 }
+
+                step = next_step;
             }
         }
     }
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let grid: Grid = Grid::from_lines(lines);
-    let start_pos: (isize, isize) = grid.find_start();
+pub fn parse(lines: &Vec<String>) -> ParseResult<Grid<char>> {
+    parse_grid(lines)
+}
+
+pub fn solve(grid: &Grid<char>) -> Solution {
+    let start_pos: Pos = find_start(grid);
 
-    let initial_steps: Vec<Step> = vec!(
-        Step { coordinates: (start_pos.0 + 1, start_pos.1), from: Direction::North },
-        Step { coordinates: (start_pos.0 - 1, start_pos.1), from: Direction::South },
-        Step { coordinates: (start_pos.0, start_pos.1 + 1), from: Direction::West  },
-        Step { coordinates: (start_pos.0, start_pos.1 - 1), from: Direction::East  },
-    );
+    let initial_steps: Vec<Step> = Step::initial(start_pos);
 
     let result: i64 = initial_steps.into_iter()
-        .filter_map(|step| resolve(&grid, step))
-        .find_map(|tracker| tracker.find_enclosed_size(&grid))
+        .filter_map(|step| resolve(grid, step))
+        .find_map(|tracker| tracker.find_enclosed_size(grid))
         .unwrap();
     
     return Solution::Integer(result)
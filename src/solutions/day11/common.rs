@@ -1,24 +1,44 @@
-pub fn find_stars(lines: &Vec<String>) -> Vec<(usize, usize)> {
-    lines.iter().enumerate().map(move |(row, line)| {
+use crate::solutions::grid::Pos;
+use crate::solutions::parse::{self, ParseResult};
+
+pub fn find_stars(lines: &Vec<String>) -> Vec<Pos> {
+    lines.iter().enumerate().flat_map(move |(row, line)| {
         line.chars().enumerate()
             .filter(|(_, ch)| *ch == '#')
-            .map(move |(col, _)| (row, col))
-    }).flatten().collect()
+            .map(move |(col, _)| Pos::new(row as isize, col as isize))
+    }).collect()
 }
 
-pub fn find_empty_rows(lines: &Vec<String>) -> Vec<usize> {
+pub fn find_empty_rows(lines: &Vec<String>) -> Vec<isize> {
     lines.iter().enumerate()
         .filter(|(_, line)| !line.contains('#'))
-        .map(|(row, _)| row)
+        .map(|(row, _)| row as isize)
         .collect()
 }
 
-pub fn find_empty_cols(lines: &Vec<String>, stars: &Vec<(usize, usize)>) -> Vec<usize> {
+pub fn find_empty_cols(lines: &Vec<String>, stars: &Vec<Pos>) -> Vec<isize> {
     let mut is_empty: Vec<bool> = vec![true; lines[0].len()];
-    stars.iter().for_each(|(_, col)| is_empty[*col] = false);
+    stars.iter().for_each(|star| is_empty[star.col as usize] = false);
 
     is_empty.into_iter().enumerate()
         .filter(|(_, empty)| *empty)
-        .map(|(col, _)| col)
+        .map(|(col, _)| col as isize)
         .collect()
 }
+
+pub struct Image {
+    pub stars: Vec<Pos>,
+    pub empty_rows: Vec<isize>,
+    pub empty_cols: Vec<isize>
+}
+
+impl Image {
+    pub fn parse(lines: &Vec<String>) -> ParseResult<Image> {
+        parse::grid(lines, 0, "'#' or '.'", |c| (c == '#' || c == '.').then_some(c))?;
+
+        let stars: Vec<Pos> = find_stars(lines);
+        let empty_rows: Vec<isize> = find_empty_rows(lines);
+        let empty_cols: Vec<isize> = find_empty_cols(lines, &stars);
+        Ok(Image { stars, empty_rows, empty_cols })
+    }
+}
@@ -1,11 +1,21 @@
+use crate::solutions::parse::{Line, ParseResult};
+
 /* --------------------------------- Parsing -------------------------------- */
 
-pub fn parse_springs(input: &str) -> Vec<char> {
-    input.chars().collect()
+pub fn parse_springs(line: &Line, input: &str) -> ParseResult<Vec<char>> {
+    input.char_indices().map(|(i, c)| match c {
+        '.' | '#' | '?' => Ok(c),
+        _ => Err(line.error(&input[i .. i + c.len_utf8()], "'.', '#' or '?'"))
+    }).collect()
+}
+
+pub fn parse_groups(line: &Line, input: &str) -> ParseResult<Vec<usize>> {
+    input.split(',').map(|v| line.number(v)).collect()
 }
 
-pub fn parse_groups(input: &str) -> Vec<usize> {
-    input.split(',').map(|v| v.parse().unwrap()).collect()
+pub struct Record {
+    pub springs: Vec<char>,
+    pub groups: Vec<usize>
 }
 
 /* ------------------------------- Memoization ------------------------------ */
@@ -17,7 +27,7 @@ pub struct Memo {
 
 impl Memo {
     pub fn new(nr_springs: usize, nr_groups: usize) -> Memo {
-        Memo { results: vec![-1; (nr_springs + 1) * (nr_groups + 1)], nr_groups: nr_groups }
+        Memo { results: vec![-1; (nr_springs + 1) * (nr_groups + 1)], nr_groups }
     }
 
     fn get(&self, springs_len: usize, groups_len: usize) -> Option<i64> {
@@ -32,6 +42,12 @@ impl Memo {
 
 /* --------------------------------- Helpers -------------------------------- */
 
+pub fn count_arrangements(record: &Record) -> i64 {
+    let remaining: usize = record.groups.iter().sum::<usize>();
+    let mut memo: Memo = Memo::new(record.springs.len(), record.groups.len());
+    recurse(&record.springs, &record.groups, remaining, &mut memo)
+}
+
 fn check_group_fits(springs: &[char], size: usize) -> bool {
     let fits: bool = springs[0 .. size].iter().all(|c| *c != '.');
     fits && (springs.len() == size || springs[size] != '#')
@@ -1,4 +1,5 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::{self, Line, ParseResult};
 use crate::solutions::day12::common::*;
 
 // A recursive approach. In each step, we find all possible positions in the
@@ -22,17 +23,18 @@ use crate::solutions::day12::common::*;
 // case we check whether the end state is valid, i.e. whether the list
 // of remaining springs does not contain any known broken springs.
 
-fn solve_line(line: &str) -> i64 {
-    let fields: (&str, &str) = line.split_once(' ').unwrap();
-    let springs: Vec<char> = parse_springs(fields.0);
-    let groups: Vec<usize> = parse_groups(fields.1);
+fn parse_line(line: &Line) -> ParseResult<Record> {
+    let fields: (&str, &str) = line.split_once(line.text, " ")?;
+    let springs: Vec<char> = parse_springs(line, fields.0)?;
+    let groups: Vec<usize> = parse_groups(line, fields.1)?;
+    Ok(Record { springs, groups })
+}
 
-    let remaining: usize = groups.iter().sum::<usize>();
-    let mut memo: Memo = Memo::new(springs.len(), groups.len());
-    recurse(&springs, &groups, remaining, &mut memo)
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Record>> {
+    parse::lines(lines).map(|line| parse_line(&line)).collect()
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let result: i64 = lines.iter().map(|line| solve_line(line)).sum();
+pub fn solve(records: &Vec<Record>) -> Solution {
+    let result: i64 = records.iter().map(count_arrangements).sum();
     return Solution::Integer(result)
 }
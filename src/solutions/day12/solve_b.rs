@@ -1,4 +1,5 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::{self, Line, ParseResult};
 use crate::solutions::day12::common::*;
 
 // The big optimization - applied retroactively to the A part as well - is to
@@ -15,22 +16,22 @@ use crate::solutions::day12::common::*;
 // to e.g. a hashmap) in order to slightly speed up access at the cost of
 // increased memory usage.
 
-fn extend_string(input: &str, separator: &str) -> String {
-    format!("{input}{separator}{input}{separator}{input}{separator}{input}{separator}{input}")
-}
+fn parse_line(line: &Line) -> ParseResult<Record> {
+    let fields: (&str, &str) = line.split_once(line.text, " ")?;
+    let springs: Vec<char> = parse_springs(line, fields.0)?;
+    let groups: Vec<usize> = parse_groups(line, fields.1)?;
 
-fn solve_line(line: &str) -> i64 {
-    let fields: (&str, &str) = line.split_once(' ').unwrap();
-    let springs: Vec<char> = parse_springs(&extend_string(fields.0, "?"));
-    let groups: Vec<usize> = parse_groups(&extend_string(fields.1, ","));
+    Ok(Record {
+        springs: [springs.as_slice(); 5].join(&'?'),
+        groups: groups.repeat(5)
+    })
+}
 
-    let remaining: usize = groups.iter().sum::<usize>();
-    let mut memo: Memo = Memo::new(springs.len(), groups.len());
-    let result = recurse(&springs, &groups, remaining, &mut memo);
-    result
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Record>> {
+    parse::lines(lines).map(|line| parse_line(&line)).collect()
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let result: i64 = lines.iter().map(|line| solve_line(line)).sum();
+pub fn solve(records: &Vec<Record>) -> Solution {
+    let result: i64 = records.iter().map(count_arrangements).sum();
     return Solution::Integer(result)
 }
@@ -1,48 +1,25 @@
-/* -------------------------------- Direction ------------------------------- */
-
-pub enum Direction {
-    Horizontal,
-    Vertical
-}
-
-impl Direction {
-    pub fn other(&self) -> Direction {
-        match self {
-            Direction::Horizontal => Direction::Vertical,
-            Direction::Vertical   => Direction::Horizontal,
-        }
-    }
-}
-
-/* ---------------------------------- Grid ---------------------------------- */
-
-pub struct Grid {
-    pub content: Vec<bool>,
-    pub rows: isize,
-    pub cols: isize
+use crate::solutions::grid::Grid;
+use crate::solutions::parse::ParseResult;
+
+pub fn parse_grid(lines: &[String], offset: usize) -> ParseResult<Grid<bool>> {
+    Grid::parse(lines, offset, "'#' or '.'", |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+         _  => None
+    })
 }
 
-impl Grid {
-    pub fn from_lines(lines: &[String]) -> Grid {
-        Grid {
-            content: lines.concat().chars().map(|c| c == '#').collect(),
-            cols: lines.first().unwrap().len() as isize,
-            rows: lines.len() as isize
-        }
-    }
+// The grids are separated by empty lines; `offset` keeps track of the index of
+// the first line of each grid, so that errors point at the correct line.
 
-    pub fn get(&self, c: &(isize, isize)) -> bool {
-        self.content[(c.0 * self.cols + c.1) as usize]
-    }
+pub fn parse_grids(lines: &Vec<String>) -> ParseResult<Vec<Grid<bool>>> {
+    let mut grids: Vec<Grid<bool>> = Vec::new();
+    let mut offset: usize = 0;
 
-    pub fn contains(&self, c: &(isize, isize)) -> bool {
-        c.0 >= 0 && c.0 < self.rows && c.1 >= 0 && c.1 < self.cols
+    for block in lines.split(|line| line.is_empty()) {
+        grids.push(parse_grid(block, offset)?);
+        offset += block.len() + 1;
     }
 
-    pub fn index_range(&self, direction: &Direction) -> std::ops::Range<isize> {
-        match direction {
-            Direction::Horizontal => 0 .. self.rows,
-            Direction::Vertical   => 0 .. self.cols,
-        }
-    }
+    Ok(grids)
 }
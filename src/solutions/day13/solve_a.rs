@@ -1,4 +1,6 @@
 use crate::solutions::Solution;
+use crate::solutions::grid::{Grid, Pos, View};
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day13::common::*;
 
 // For each of the two directions - horizontal and vertical - we start by creating
@@ -18,28 +20,19 @@ use crate::solutions::day13::common::*;
 // less and less work to do per row. In most cases, the list of candidates is
 // reduced to just one after two or three rows. If we've still got exactly one
 // valid candidate after checking all rows, we've found our answer; otherwise,
-// we check the vertical direction by iterating over all columns.
+// we check the vertical direction by iterating over all columns, which we do
+// by running the exact same logic on a transposed view of the grid.
 
-fn is_symmetric(grid: &Grid, pivot: (isize, isize), direction: &Direction) -> bool {
-    for offset in 0 .. grid.cols {
-        let coordinates_a: (isize, isize) = match direction {
-            Direction::Horizontal => (pivot.0, pivot.1 - offset),
-            Direction::Vertical   => (pivot.0 - offset, pivot.1),
-        };
-
-        let coordinates_b: (isize, isize) =match direction {
-            Direction::Horizontal => (pivot.0, pivot.1 + offset + 1),
-            Direction::Vertical   => (pivot.0 + offset + 1, pivot.1),
-        };
+fn is_symmetric(view: &View<bool>, pivot: Pos) -> bool {
+    for offset in 0 .. view.cols() {
+        let coordinates_a: Pos = Pos::new(pivot.row, pivot.col - offset);
+        let coordinates_b: Pos = Pos::new(pivot.row, pivot.col + offset + 1);
     
-        if !grid.contains(&coordinates_b) || !grid.contains(&coordinates_a) {
+        if !view.contains(coordinates_b) || !view.contains(coordinates_a) {
             return true;
         }
 
-        let value_a = grid.get(&coordinates_a);
-        let value_b = grid.get(&coordinates_b);
-
-        if value_a != value_b {
+        if view[coordinates_a] != view[coordinates_b] {
             return false;
         }
     }
@@ -47,24 +40,17 @@ fn is_symmetric(grid: &Grid, pivot: (isize, isize), direction: &Direction) -> bo
     return true;
 }
 
-fn filter_candidates(grid: &Grid, index: isize, candidates: &Vec<isize>, direction: &Direction) -> Vec<isize> {
-    candidates.into_iter().filter(|&candidate| {
-        let pivot: (isize, isize) = match direction {
-            Direction::Horizontal => (index, *candidate),
-            Direction::Vertical   => (*candidate, index),
-        };
-
-        return is_symmetric(grid, pivot, direction);
-    }).map(|c| *c).collect()
+fn filter_candidates(view: &View<bool>, row: isize, candidates: &Vec<isize>) -> Vec<isize> {
+    candidates.iter()
+        .filter(|&candidate| is_symmetric(view, Pos::new(row, *candidate)))
+        .copied().collect()
 }
 
-fn find_mirror_line(grid: &Grid, direction: Direction) -> Option<isize> {
-    let index_range: std::ops::Range<isize> = grid.index_range(&direction);
-    let other_range: std::ops::Range<isize> = grid.index_range(&direction.other());
-    let mut candidates: Vec<isize> = (other_range.start .. other_range.end - 1).collect();
+fn find_mirror_line(view: View<bool>) -> Option<isize> {
+    let mut candidates: Vec<isize> = (0 .. view.cols() - 1).collect();
 
-    for index in index_range {
-        candidates = filter_candidates(grid, index, &candidates, &direction);
+    for row in 0 .. view.rows() {
+        candidates = filter_candidates(&view, row, &candidates);
         
         if candidates.is_empty() {
             return None;
@@ -78,20 +64,21 @@ fn find_mirror_line(grid: &Grid, direction: Direction) -> Option<isize> {
     }
 }
 
-fn solve_block(lines: &[String]) -> i64 {
-    let grid: Grid = Grid::from_lines(lines);
-    
-    if let Some(col) = find_mirror_line(&grid, Direction::Horizontal) {
+fn solve_block(grid: &Grid<bool>) -> i64 {
+    if let Some(col) = find_mirror_line(grid.view()) {
         return (col + 1) as i64;
-    } else if let Some(row) = find_mirror_line(&grid, Direction::Vertical) {
+    } else if let Some(row) = find_mirror_line(grid.transposed()) {
         return (row + 1) as i64 * 100;
     }
     
     panic!("No symmetry found");
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let blocks: Vec<&[String]> = lines.split(|line| line.is_empty()).collect();
-    let result: i64 = blocks.into_iter().map(|block| solve_block(block)).sum();
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Grid<bool>>> {
+    parse_grids(lines)
+}
+
+pub fn solve(grids: &Vec<Grid<bool>>) -> Solution {
+    let result: i64 = grids.iter().map(solve_block).sum();
     return Solution::Integer(result)
 }
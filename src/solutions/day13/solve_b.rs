@@ -1,4 +1,6 @@
 use crate::solutions::Solution;
+use crate::solutions::grid::{Grid, Pos, View};
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day13::common::*;
 
 // Similar to the A part. Instead of the candidates consisting only of an
@@ -19,32 +21,22 @@ struct Candidate {
 
 impl Candidate {
     fn new(index: isize) -> Candidate {
-        Candidate { index: index, errors: 0 }
+        Candidate { index, errors: 0 }
     }
 }
 
-fn count_errors(grid: &Grid, pivot: (isize, isize), direction: &Direction) -> usize {
+fn count_errors(view: &View<bool>, pivot: Pos) -> usize {
     let mut errors: usize = 0;
 
-    for offset in 0 .. grid.cols {
-        let coordinates_a: (isize, isize) = match direction {
-            Direction::Horizontal => (pivot.0, pivot.1 - offset),
-            Direction::Vertical   => (pivot.0 - offset, pivot.1),
-        };
-
-        let coordinates_b: (isize, isize) =match direction {
-            Direction::Horizontal => (pivot.0, pivot.1 + offset + 1),
-            Direction::Vertical   => (pivot.0 + offset + 1, pivot.1),
-        };
+    for offset in 0 .. view.cols() {
+        let coordinates_a: Pos = Pos::new(pivot.row, pivot.col - offset);
+        let coordinates_b: Pos = Pos::new(pivot.row, pivot.col + offset + 1);
     
-        if !grid.contains(&coordinates_b) || !grid.contains(&coordinates_a) {
+        if !view.contains(coordinates_b) || !view.contains(coordinates_a) {
             return errors;
         }
 
-        let value_a = grid.get(&coordinates_a);
-        let value_b = grid.get(&coordinates_b);
-
-        if value_a != value_b {
+        if view[coordinates_a] != view[coordinates_b] {
             errors += 1;
 
             if errors >= 2 {
@@ -56,37 +48,27 @@ fn count_errors(grid: &Grid, pivot: (isize, isize), direction: &Direction) -> us
     return errors;
 }
 
-fn update_candidates(candidates: &mut Vec<Candidate>, grid: &Grid, index: isize, direction: &Direction) {
+fn update_candidates(candidates: &mut Vec<Candidate>, view: &View<bool>, row: isize) {
     for candidate in candidates {
-        let pivot: (isize, isize) = match direction {
-            Direction::Horizontal => (index, candidate.index),
-            Direction::Vertical   => (candidate.index, index),
-        };
-
-        candidate.errors += count_errors(grid, pivot, direction);
+        candidate.errors += count_errors(view, Pos::new(row, candidate.index));
     }
 }
 
-fn find_secondary_line(grid: &Grid, direction: Direction) -> Option<isize> {
-    let index_range: std::ops::Range<isize> = grid.index_range(&direction);
-    let other_range: std::ops::Range<isize> = grid.index_range(&direction.other());
-
-    let mut candidates: Vec<Candidate> = (other_range.start .. other_range.end - 1)
-        .map(|index| Candidate::new(index)).collect();
+fn find_secondary_line(view: View<bool>) -> Option<isize> {
+    let mut candidates: Vec<Candidate> = (0 .. view.cols() - 1)
+        .map(Candidate::new).collect();
 
-    for index in index_range {
-        update_candidates(&mut candidates, grid, index, &direction);
+    for row in 0 .. view.rows() {
+        update_candidates(&mut candidates, &view, row);
 
-        candidates = candidates.into_iter()
-            .filter(|c| c.errors <= 1).collect();
+        candidates.retain(|c| c.errors <= 1);
         
         if candidates.is_empty() {
             return None;
         }
     }
 
-    candidates = candidates.into_iter()
-        .filter(|c| c.errors == 1).collect();
+    candidates.retain(|c| c.errors == 1);
 
     if candidates.len() == 1 {
         return Some(candidates[0].index)
@@ -95,20 +77,21 @@ fn find_secondary_line(grid: &Grid, direction: Direction) -> Option<isize> {
     }
 }
 
-fn solve_block(lines: &[String]) -> i64 {
-    let grid: Grid = Grid::from_lines(lines);
-    
-    if let Some(col) = find_secondary_line(&grid, Direction::Horizontal) {
+fn solve_block(grid: &Grid<bool>) -> i64 {
+    if let Some(col) = find_secondary_line(grid.view()) {
         return (col + 1) as i64;
-    } else if let Some(row) = find_secondary_line(&grid, Direction::Vertical) {
+    } else if let Some(row) = find_secondary_line(grid.transposed()) {
         return (row + 1) as i64 * 100;
     }
     
     panic!("No symmetry found");
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let blocks: Vec<&[String]> = lines.split(|line| line.is_empty()).collect();
-    let result: i64 = blocks.into_iter().map(|block| solve_block(block)).sum();
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Grid<bool>>> {
+    parse_grids(lines)
+}
+
+pub fn solve(grids: &Vec<Grid<bool>>) -> Solution {
+    let result: i64 = grids.iter().map(solve_block).sum();
     return Solution::Integer(result)
 }
@@ -1,4 +1,6 @@
 use crate::solutions::Solution;
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day14::common::*;
 
 // For the A part, we simply simulate the round stones rolling north
@@ -11,9 +13,9 @@ use crate::solutions::day14::common::*;
 // which is simply its row index subtracted from the total number
 // of rows. Summing this load over all stones gives us the answer.
 
-fn roll_stone_north(grid: &mut Grid, row: isize, col: isize) -> i64 {
-    let mut c: (isize, isize) = (row, col);
-    let field: &Field = grid.get(&c);
+fn roll_stone_north(grid: &mut Grid<Field>, row: isize, col: isize) -> i64 {
+    let mut c: Pos = Pos::new(row, col);
+    let field: &Field = &grid[c];
 
     match field {
         Field::Empty      => { return 0; }
@@ -21,41 +23,45 @@ fn roll_stone_north(grid: &mut Grid, row: isize, col: isize) -> i64 {
         Field::RoundRock  => { }
     }
 
-    let c_start: (isize, isize) = (c.0, c.1);
+    let c_start: Pos = c;
 
     loop {
-        if c.0 == 0 {
+        if c.row == 0 {
             break;
         }
 
-        let nc: (isize, isize) = (c.0 - 1, c.1);
-        let nfield: &Field = grid.get(&nc);
+        let nc: Pos = Pos::new(c.row - 1, c.col);
+        let nfield: &Field = &grid[nc];
 
         match nfield {
             Field::RoundRock  => { break; }
             Field::SquareRock => { break; }
-            Field::Empty      => { c.0 -= 1; }
+            Field::Empty      => { c.row -= 1; }
         }
     }
 
     if c != c_start {
-        grid.set(&c_start, Field::Empty);
-        grid.set(&c, Field::RoundRock);
+        grid[c_start] = Field::Empty;
+        grid[c] = Field::RoundRock;
     }
 
-    return (grid.rows - c.0) as i64;
+    return (grid.rows - c.row) as i64;
 }
 
-fn roll_row_north(grid: &mut Grid, row: isize) -> i64 {
+fn roll_row_north(grid: &mut Grid<Field>, row: isize) -> i64 {
     (0 .. grid.cols).map(|col| roll_stone_north(grid, row, col)).sum()
 }
 
-fn roll_all_north(grid: &mut Grid) -> i64 {
+fn roll_all_north(grid: &mut Grid<Field>) -> i64 {
     (0 .. grid.rows).map(|row| roll_row_north(grid, row)).sum()
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let mut grid: Grid = Grid::from_lines(lines);
+pub fn parse(lines: &Vec<String>) -> ParseResult<Grid<Field>> {
+    parse_grid(lines)
+}
+
+pub fn solve(grid: &Grid<Field>) -> Solution {
+    let mut grid: Grid<Field> = grid.clone();
     let result: i64 = roll_all_north(&mut grid);
     return Solution::Integer(result);
 }
@@ -1,3 +1,10 @@
+use crate::solutions::parse::{self, Line, ParseResult};
+
+pub fn parse_steps(lines: &Vec<String>) -> ParseResult<(Line<'_>, Vec<&str>)> {
+    let line: Line = parse::line(lines, 0, "a list of steps")?;
+    Ok((line, line.text.split(',').collect()))
+}
+
 pub fn compute_hash(input: &str) -> usize {
     let mut result: usize = 0;
 
@@ -5,7 +12,7 @@ pub fn compute_hash(input: &str) -> usize {
         let ascii_value: usize = c as usize;
         result += ascii_value;
         result *= 17;
-        result = result % 256;
+        result %= 256;
     }
 
     result
@@ -1,13 +1,19 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day15::common::*;
 
 // A surprisingly easy A part, considering we're past the halfway
 // point. Nothing to say about this, just followed the instructions.
 
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let result: usize = lines[0].split(',')
-        .map(|field| compute_hash(field)).sum();
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<String>> {
+    let (_, steps) = parse_steps(lines)?;
+    Ok(steps.into_iter().map(String::from).collect())
+}
+
+pub fn solve(steps: &Vec<String>) -> Solution {
+    let result: usize = steps.iter()
+        .map(|step| compute_hash(step)).sum();
 
     return Solution::Integer(result as i64)
 }
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use crate::solutions::Solution;
+use crate::solutions::parse::{Line, ParseResult};
 use crate::solutions::day15::common::*;
 
 // When modeling the box, we use two data structures, a map from label to index,
@@ -19,15 +20,16 @@ use crate::solutions::day15::common::*;
 
 /* ---------------------------------- Lens ---------------------------------- */
 
-struct Lens {
+#[derive(Clone)]
+pub struct Lens {
     label: String,
     length: usize
 }
 
 impl Lens {
-    fn parse(field: &str) -> Lens {
-        let (label, length_str) = field.split_once('=').unwrap();
-        Lens { label: String::from(label), length: length_str.parse().unwrap() }
+    fn parse(line: &Line, field: &str) -> ParseResult<Lens> {
+        let (label, length_str) = line.split_once(field, "=")?;
+        Ok(Lens { label: String::from(label), length: line.number(length_str)? })
     }
 
     fn compute_power(&self, base: usize, pos: usize) -> usize {
@@ -35,6 +37,23 @@ impl Lens {
     }
 }
 
+/* ---------------------------------- Step ---------------------------------- */
+
+pub enum Step {
+    Remove(String),
+    Add(Lens)
+}
+
+impl Step {
+    fn parse(line: &Line, field: &str) -> ParseResult<Step> {
+        if let Some(label) = field.strip_suffix('-') {
+            Ok(Step::Remove(String::from(label)))
+        } else {
+            Ok(Step::Add(Lens::parse(line, field)?))
+        }
+    }
+}
+
 /* ----------------------------------- Box ---------------------------------- */
 
 struct Box {
@@ -65,10 +84,7 @@ impl Box {
     }
 
     fn get_lens_index(&self, label: &String) -> Option<usize> {
-        match self.lens_indices.get(label) {
-            Some(i) => Some(*i),
-            None => None
-        }
+        self.lens_indices.get(label).copied()
     }
 
     fn compute_power(&self, box_index: usize) -> usize {
@@ -92,32 +108,23 @@ impl Box {
 
 /* ------------------------------- Main logic ------------------------------- */
 
-fn get_label(instruction: &str) -> &str {
-    let end_index = instruction.chars().enumerate()
-        .find(|(_, c)| !c.is_alphabetic())
-        .map(|(i, _)| i)
-        .unwrap_or(instruction.len());
-
-    &instruction[..end_index]
+fn apply_step(step: &Step, boxes: &mut Vec<Box>) {
+    match step {
+        Step::Remove(label) => boxes[compute_hash(label)].remove_lens(label.clone()),
+        Step::Add(lens)     => boxes[compute_hash(&lens.label)].add_lens(lens.clone())
+    }
 }
 
-fn apply_step(instruction: &str, boxes: &mut Vec<Box>) {
-    let label: &str = get_label(instruction);
-    let box_index: usize = compute_hash(label);
-    let target_box: &mut Box = &mut boxes[box_index];
-
-    if instruction.ends_with('-') {
-        target_box.remove_lens(String::from(label));
-    } else {
-        target_box.add_lens(Lens::parse(instruction));
-    }
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Step>> {
+    let (line, steps) = parse_steps(lines)?;
+    steps.into_iter().map(|field| Step::parse(&line, field)).collect()
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
+pub fn solve(steps: &Vec<Step>) -> Solution {
     let mut boxes: Vec<Box> = Vec::new();
     (0 .. 256).for_each(|_| boxes.push(Box::new()));
 
-    lines[0].split(',').for_each(|field| apply_step(field, &mut boxes));
+    steps.iter().for_each(|step| apply_step(step, &mut boxes));
 
     let result: usize = boxes.iter().enumerate()
         .map(|(i, b)| b.compute_power(i)).sum();
@@ -1,15 +1,10 @@
-/* -------------------------------- Direction ------------------------------- */
-
-#[derive(Clone, Copy)]
-pub enum Direction {
-    North,
-    East,
-    South,
-    West
-}
+use crate::solutions::geometry::Direction;
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::parse::ParseResult;
 
 /* ---------------------------------- Tile ---------------------------------- */
 
+#[derive(Clone)]
 pub enum TileType {
     Empty,
     MirrorF,
@@ -18,6 +13,7 @@ pub enum TileType {
     SplitterV
 }
 
+#[derive(Clone)]
 pub struct Tile {
     tile_type: TileType,
     visited: Vec<bool>,
@@ -25,22 +21,17 @@ pub struct Tile {
 }
 
 impl Tile {
-    fn parse(c: char) -> Tile {
+    fn parse(c: char) -> Option<Tile> {
         let tile_type: TileType = match c {
             '.'  => TileType::Empty,
             '/'  => TileType::MirrorF,
             '\\' => TileType::MirrorB,
             '-'  => TileType::SplitterH,
             '|'  => TileType::SplitterV,
-            _    => { panic!(); }
+            _    => { return None; }
         };
 
-        Tile { tile_type: tile_type, visited: vec![false; 4], energized: 0 }
-    }
-
-    fn reset(&mut self) {
-        self.visited = vec![false; 4];
-        self.energized = 0;
+        Some(Tile { tile_type, visited: vec![false; 4], energized: 0 })
     }
 
     fn change_direction(&self, dir: Direction) -> Direction {
@@ -65,13 +56,44 @@ impl Tile {
         }
     }
 
+    // The directions in which a beam entering this tile in the given direction
+    // leaves it.
+
+    pub fn outgoing(&self, dir: Direction) -> Vec<Direction> {
+        match (&self.tile_type, dir) {
+            (TileType::SplitterH, Direction::North | Direction::South) => vec![Direction::East, Direction::West],
+            (TileType::SplitterV, Direction::East  | Direction::West)  => vec![Direction::North, Direction::South],
+            _ => vec![self.change_direction(dir)]
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match self.tile_type {
+            TileType::Empty     => '.',
+            TileType::MirrorF   => '/',
+            TileType::MirrorB   => '\\',
+            TileType::SplitterH => '-',
+            TileType::SplitterV => '|'
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self.tile_type, TileType::Empty)
+    }
+
+    pub fn is_energized(&self) -> bool {
+        self.energized > 0
+    }
+
+    // The directions in which beams have passed through this tile so far (after
+    // any change of direction by a mirror).
+
+    pub fn visited_directions(&self) -> Vec<Direction> {
+        Direction::ALL.into_iter().filter(|dir| self.visited[dir.index()]).collect()
+    }
+
     fn visit(&mut self, dir: &Direction) -> bool {
-        let index: usize = match dir {
-            Direction::North => 0,
-            Direction::East  => 1,
-            Direction::South => 2,
-            Direction::West  => 3
-        };
+        let index: usize = dir.index();
 
         let already_visited: bool = self.visited[index];
 
@@ -85,36 +107,24 @@ impl Tile {
     }
 }
 
-/* ---------------------------------- Grid ---------------------------------- */
-
-pub struct Grid {
-    tiles: Vec<Tile>,
-    pub energy: usize,
-    pub rows: isize,
-    pub cols: isize
-}
+/* ------------------------------- Contraption ------------------------------ */
 
-impl Grid {
-    pub fn parse(lines: &[String]) -> Grid {
-        let tiles: Vec<Tile> = lines.concat().chars()
-            .map(|c| Tile::parse(c)).collect();
+// The grid of tiles, plus the number of tiles energized so far.
 
-        Grid {
-            tiles: tiles,
-            energy: 0,
-            rows: lines.len() as isize,
-            cols: lines[0].len() as isize
-        }
-    }
+#[derive(Clone)]
+pub struct Contraption {
+    pub tiles: Grid<Tile>,
+    pub energy: usize
+}
 
-    pub fn reset(&mut self) {
-        self.tiles.iter_mut().for_each(|t| t.reset());
-        self.energy = 0;
+impl Contraption {
+    pub fn parse(lines: &[String]) -> ParseResult<Contraption> {
+        let tiles: Grid<Tile> = Grid::parse(lines, 0, "a mirror, a splitter or '.'", Tile::parse)?;
+        Ok(Contraption { tiles, energy: 0 })
     }
 
     fn visit(&mut self, beam: &Beam) -> bool {
-        let index: usize = self.to_index(&beam.pos);
-        let tile: &mut Tile = &mut self.tiles[index];
+        let tile: &mut Tile = &mut self.tiles[beam.pos];
         let is_new: bool = tile.visit(&beam.dir);
 
         if is_new && tile.energized == 1 {
@@ -123,29 +133,17 @@ impl Grid {
 
         is_new
     }
-
-    fn contains(&self, pos: &(isize, isize)) -> bool {
-        pos.0 >= 0 && pos.0 < self.rows && pos.1 >= 0 && pos.1 < self.cols
-    }
-
-    fn get(&self, pos: &(isize, isize)) -> &Tile {
-        &self.tiles[self.to_index(pos)]
-    }
-
-    fn to_index(&self, pos: &(isize, isize)) -> usize {
-        (pos.0 * self.cols + pos.1) as usize
-    }
 }
 
 /* ---------------------------------- Beam ---------------------------------- */
 
 pub struct Beam {
-    pos: (isize, isize),
+    pos: Pos,
     dir: Direction
 }
 
 impl Beam {
-    pub fn new(pos: (isize, isize), dir: Direction) -> Beam {
+    pub fn new(pos: Pos, dir: Direction) -> Beam {
         Beam { pos, dir }
     }
 
@@ -153,19 +151,13 @@ impl Beam {
         Beam { pos: self.pos, dir }
     }
 
-    fn step(&mut self, grid: &Grid) -> bool {
-        let next_pos: (isize, isize) = match self.dir {
-            Direction::North => (self.pos.0 - 1, self.pos.1),
-            Direction::East  => (self.pos.0, self.pos.1 + 1),
-            Direction::South => (self.pos.0 + 1, self.pos.1),
-            Direction::West  => (self.pos.0, self.pos.1 - 1),
-        };
-
-        if !grid.contains(&next_pos) {
-            return false;
-        }
+    fn step(&mut self, grid: &Contraption) -> bool {
+        let next_pos: Pos = self.dir.step(self.pos);
 
-        let next_tile: &Tile = grid.get(&next_pos);
+        let next_tile: &Tile = match grid.tiles.get(next_pos) {
+            Some(tile) => tile,
+            None => return false
+        };
         let next_dir: Direction = next_tile.change_direction(self.dir);
 
         self.pos = next_pos;
@@ -173,8 +165,8 @@ impl Beam {
         true
     }
 
-    fn split(&self, grid: &Grid) -> Option<Vec<Beam>> {
-        let tile: &Tile = grid.get(&self.pos);
+    fn split(&self, grid: &Contraption) -> Option<Vec<Beam>> {
+        let tile: &Tile = &grid.tiles[self.pos];
 
         match tile.tile_type {
             TileType::SplitterH => {
@@ -206,17 +198,17 @@ impl Beam {
 
 /* ------------------------------- Main logic ------------------------------- */
 
-pub fn trace(grid: &mut Grid, beams: &mut Vec<Beam>, index: usize) -> Option<Vec<Beam>> {
+pub fn trace(grid: &mut Contraption, beams: &mut Vec<Beam>, index: usize) -> Option<Vec<Beam>> {
     let beam: &mut Beam = &mut beams[index];
 
     loop {
-        let should_continue: bool = beam.step(&grid);
+        let should_continue: bool = beam.step(grid);
 
         if !should_continue {
             return None;
         }
 
-        let is_new: bool = grid.visit(&beam);
+        let is_new: bool = grid.visit(beam);
 
         if !is_new {
             return None;
@@ -1,4 +1,7 @@
 use crate::solutions::Solution;
+use crate::solutions::geometry::Direction;
+use crate::solutions::grid::Pos;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day16::common::*;
 
 // We trace the initial beam until we either leave the grid, or hit a splitter
@@ -11,10 +14,14 @@ use crate::solutions::day16::common::*;
 // a tile (going in any direction) for the first time. We're done once we've
 // finished tracing all beams, and no new beams have spawned from splitters.
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let init_beam: Beam = Beam::new((0, -1), Direction::East);
+pub fn parse(lines: &Vec<String>) -> ParseResult<Contraption> {
+    Contraption::parse(lines)
+}
+
+pub fn solve(grid: &Contraption) -> Solution {
+    let init_beam: Beam = Beam::new(Pos::new(0, -1), Direction::East);
     let mut beams: Vec<Beam> = vec!(init_beam);
-    let mut grid: Grid = Grid::parse(lines);
+    let mut grid: Contraption = grid.clone();
     let mut index: usize = 0;
 
     while index < beams.len() {
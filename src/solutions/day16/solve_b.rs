@@ -1,48 +1,255 @@
 use crate::solutions::Solution;
+use crate::solutions::geometry::Direction;
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day16::common::*;
 
-// I know there is a nicer way to do this - one which involves memoization,
-// allowing us to quickly return the set of tiles that the current beam will
-// visit based on its current tile and direction - but I wasn't able to get
-// the implementation right. Despite it returning the correct answer for the
-// sample, this solution returned a wrong answer for the real input. Having
-// already spent a lot of time on this, and not feeling like spending even
-// more time chasing down the bug, I opted for a naive solution instead.
+// My first version of this part simply repeated the A part for every possible
+// start position. I knew there was a nicer way to do this, one that reuses the
+// work done for earlier start positions, but my attempt at memoizing the set of
+// tiles visited from each tile and direction returned a wrong answer for the
+// real input. In hindsight, the problem is obvious: beams can loop, so the set
+// of tiles visited from one tile and direction can depend on itself, and a
+// memo filled in halfway through such a loop is incomplete.
 //
-// In this naive solution, we basically just repeat the A part for every
-// possible start position, and then return the maximum of all these runs.
-// Since the solution of the A part mutates the grid, we need to reset it
-// after every run. Despite the lack of memoization - which would allow us
-// to skip a lot of repeated calculations - this solution is still fairly
-// fast (less than 100 milliseconds), so I guess I can live with it.
-
-pub fn solve_one(grid: &mut Grid, init_beam: Beam) -> i64 {
-    let mut beams: Vec<Beam> = vec!(init_beam);
-    let mut index: usize = 0;
-
-    grid.reset();
-
-    while index < beams.len() {
-        if let Some(new_beams) = trace(grid, &mut beams, index) {
-            new_beams.into_iter().for_each(|b| beams.push(b));
+// The way out is to look at the beams as a graph, with a node for every tile
+// and direction in which a beam can enter that tile, and an edge to every node
+// that the beam continues to (one, or two for a splitter hit from the side).
+// The tiles energized from a start position are the tiles of all nodes that
+// can be reached from its node. Loops in the beams are cycles in this graph,
+// so we first condense every strongly connected component (a set of nodes that
+// can all reach each other) into a single node, using Tarjan's algorithm. The
+// condensed graph has no cycles, and all nodes of a component energize exactly
+// the same tiles. Tarjan's algorithm finds the components in reverse order,
+// i.e. every component is found after all components it leads to, so we can
+// compute the energized tiles of every component from the (complete) sets of
+// the components that follow it.
+//
+// Most components are single nodes in a long chain of nodes (a beam moving
+// through empty tiles and mirrors), so storing a full set of tiles for every
+// component would take a lot of memory. Instead, we only store a set (as a bit
+// set) for the "junctions": components that consist of more than one node, or
+// that lead to more than one other component. The tiles of any other component
+// are then the tiles along its chain, up to the first junction, plus the tiles
+// of that junction. For every start position, we only have to follow its chain
+// up to the first junction, rather than tracing all beams again.
+
+const UNVISITED: usize = usize::MAX;
+
+/* --------------------------------- Bitset --------------------------------- */
+
+#[derive(Clone)]
+struct Bitset {
+    words: Vec<u64>
+}
+
+impl Bitset {
+    fn new(size: usize) -> Bitset {
+        Bitset { words: vec![0; size.div_ceil(64)] }
+    }
+
+    fn insert(&mut self, index: usize) {
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+
+    fn union_with(&mut self, other: &Bitset) {
+        self.words.iter_mut().zip(&other.words).for_each(|(word, other)| *word |= other);
+    }
+
+    fn count(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+/* ---------------------------------- Graph --------------------------------- */
+
+// The node of a beam entering the tile at the given index in a direction.
+
+fn node(tile: usize, dir: Direction) -> usize {
+    tile * 4 + dir.index()
+}
+
+fn build_graph(tiles: &Grid<Tile>) -> Vec<Vec<usize>> {
+    tiles.positions().flat_map(|pos| Direction::ALL.into_iter().map(move |dir| (pos, dir))).map(|(pos, dir)| {
+        tiles[pos].outgoing(dir).into_iter()
+            .map(|out| (out.step(pos), out))
+            .filter(|(next, _)| tiles.contains(*next))
+            .map(|(next, out)| node(tiles.to_index(next), out))
+            .collect()
+    }).collect()
+}
+
+// Tarjan's algorithm, without recursion (a chain of nodes can be thousands of
+// nodes long). Returns the component of every node, numbered in the order in
+// which they were found, along with the number of components.
+
+fn find_components(graph: &Vec<Vec<usize>>) -> (Vec<usize>, usize) {
+    let mut index: Vec<usize> = vec![UNVISITED; graph.len()];
+    let mut lowlink: Vec<usize> = vec![0; graph.len()];
+    let mut on_stack: Vec<bool> = vec![false; graph.len()];
+    let mut component: Vec<usize> = vec![UNVISITED; graph.len()];
+    let mut stack: Vec<usize> = Vec::new();
+    let mut counter: usize = 0;
+    let mut count: usize = 0;
+
+    for root in 0 .. graph.len() {
+        if index[root] != UNVISITED {
+            continue;
+        }
+
+        // Every entry is a node, and the position of the next edge to follow.
+
+        let mut work: Vec<(usize, usize)> = vec![(root, 0)];
+
+        while let Some(&(node, next)) = work.last() {
+            if next == 0 && index[node] == UNVISITED {
+                index[node] = counter;
+                lowlink[node] = counter;
+                counter += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            }
+
+            if next < graph[node].len() {
+                let child: usize = graph[node][next];
+                work.last_mut().unwrap().1 += 1;
+
+                if index[child] == UNVISITED {
+                    work.push((child, 0));
+                } else if on_stack[child] {
+                    lowlink[node] = lowlink[node].min(index[child]);
+                }
+
+                continue;
+            }
+
+            work.pop();
+
+            if let Some(&(parent, _)) = work.last() {
+                lowlink[parent] = lowlink[parent].min(lowlink[node]);
+            }
+
+            if lowlink[node] == index[node] {
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component[member] = count;
+
+                    if member == node {
+                        break;
+                    }
+                }
+
+                count += 1;
+            }
+        }
+    }
+
+    (component, count)
+}
+
+/* ------------------------------ Condensation ------------------------------ */
+
+// The condensed graph, with the tiles of the nodes of every component, the
+// components that every component leads to, and the energized tiles of every
+// junction.
+
+struct Condensation {
+    component: Vec<usize>,
+    tiles: Vec<Vec<usize>>,
+    successors: Vec<Vec<usize>>,
+    energized: Vec<Option<Bitset>>,
+    tile_count: usize
+}
+
+impl Condensation {
+    fn new(graph: &Vec<Vec<usize>>, tile_count: usize) -> Condensation {
+        let (component, count) = find_components(graph);
+        let mut tiles: Vec<Vec<usize>> = vec![Vec::new(); count];
+        let mut successors: Vec<Vec<usize>> = vec![Vec::new(); count];
+
+        for (node, edges) in graph.iter().enumerate() {
+            let from: usize = component[node];
+            tiles[from].push(node / 4);
+
+            for to in edges.iter().map(|next| component[*next]) {
+                if to != from && !successors[from].contains(&to) {
+                    successors[from].push(to);
+                }
+            }
         }
 
-        index += 1;
+        let mut condensation: Condensation = Condensation {
+            component,
+            tiles,
+            successors,
+            energized: vec![None; count],
+            tile_count
+        };
+
+        for index in 0 .. count {
+            if condensation.tiles[index].len() > 1 || condensation.successors[index].len() > 1 {
+                let mut energized: Bitset = Bitset::new(tile_count);
+                condensation.tiles[index].iter().for_each(|tile| energized.insert(*tile));
+
+                for successor in &condensation.successors[index] {
+                    condensation.add_chain(*successor, &mut energized);
+                }
+
+                condensation.energized[index] = Some(energized);
+            }
+        }
+
+        condensation
     }
 
-    grid.energy as i64
+    // Adds the tiles of the chain starting at the given component, up to and
+    // including all tiles energized by the first junction along the way.
+
+    fn add_chain(&self, start: usize, energized: &mut Bitset) {
+        let mut index: usize = start;
+
+        loop {
+            if let Some(junction) = &self.energized[index] {
+                energized.union_with(junction);
+                return;
+            }
+
+            self.tiles[index].iter().for_each(|tile| energized.insert(*tile));
+
+            match self.successors[index].first() {
+                Some(next) => index = *next,
+                None => return
+            }
+        }
+    }
+
+    fn count_energized(&self, node: usize) -> usize {
+        let mut energized: Bitset = Bitset::new(self.tile_count);
+        self.add_chain(self.component[node], &mut energized);
+        energized.count()
+    }
+}
+
+/* ------------------------------- Main logic ------------------------------- */
+
+pub fn parse(lines: &Vec<String>) -> ParseResult<Contraption> {
+    Contraption::parse(lines)
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let mut grid: Grid = Grid::parse(lines);
+pub fn solve(grid: &Contraption) -> Solution {
+    let tiles: &Grid<Tile> = &grid.tiles;
+    let (rows, cols) = (tiles.rows, tiles.cols);
+    let condensation: Condensation = Condensation::new(&build_graph(tiles), tiles.tiles().len());
 
-    let mut init_beams: Vec<Beam> = Vec::new();
-    (0 .. grid.cols).for_each(|col| init_beams.push(Beam::new((-1, col), Direction::South)));
-    (0 .. grid.cols).for_each(|col| init_beams.push(Beam::new((grid.rows, col), Direction::North)));
-    (0 .. grid.rows).for_each(|row| init_beams.push(Beam::new((row, grid.cols), Direction::West)));
-    (0 .. grid.rows).for_each(|row| init_beams.push(Beam::new((row, -1), Direction::East)));
+    let mut entries: Vec<(Pos, Direction)> = Vec::new();
+    (0 .. cols).for_each(|col| entries.push((Pos::new(0, col), Direction::South)));
+    (0 .. cols).for_each(|col| entries.push((Pos::new(rows - 1, col), Direction::North)));
+    (0 .. rows).for_each(|row| entries.push((Pos::new(row, cols - 1), Direction::West)));
+    (0 .. rows).for_each(|row| entries.push((Pos::new(row, 0), Direction::East)));
 
-    let result: i64 = init_beams.into_iter().map(|init_beam| solve_one(&mut grid, init_beam)).max().unwrap();
+    let result: usize = entries.into_iter()
+        .map(|(pos, dir)| condensation.count_energized(node(tiles.to_index(pos), dir)))
+        .max().unwrap_or(0);
 
-    return Solution::Integer(result)
+    return Solution::Integer(result as i64)
 }
@@ -0,0 +1,107 @@
+use crate::solutions::geometry::Direction;
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::day16::common::*;
+
+// A visualizer for the beams in a contraption, used by the `beams` command of
+// the runner. It traces a beam from any entry point on the edge of the grid,
+// using the same `trace` function as the A part, but one generation of beams at
+// a time: the entry beam is the first generation, and the beams created by the
+// splitters that a generation hits form the next generation. This way, the
+// beams can be rendered after every generation, to follow split beams through
+// the contraption.
+//
+// The contraption is rendered like in the puzzle description: mirrors and
+// splitters are drawn as they are, and empty tiles show the direction of the
+// beam passing through them as an arrow, or the number of directions if beams
+// pass through them in more than one direction. The energized tiles are drawn
+// separately, as '#' on a background of '.'.
+
+/* ------------------------------- Visualizer ------------------------------- */
+
+pub struct Visualizer {
+    grid: Contraption,
+    beams: Vec<Beam>,
+    pub generation: usize
+}
+
+impl Visualizer {
+    pub fn new(grid: &Contraption, entry: Beam) -> Visualizer {
+        Visualizer { grid: grid.clone(), beams: vec!(entry), generation: 0 }
+    }
+
+    // Traces all beams of the current generation, and returns false if there
+    // were none left to trace.
+
+    pub fn step(&mut self) -> bool {
+        if self.beams.is_empty() {
+            return false;
+        }
+
+        let mut next: Vec<Beam> = Vec::new();
+
+        for index in 0 .. self.beams.len() {
+            if let Some(new_beams) = trace(&mut self.grid, &mut self.beams, index) {
+                next.extend(new_beams);
+            }
+        }
+
+        self.beams = next;
+        self.generation += 1;
+        true
+    }
+
+    // The number of beams in the next generation.
+
+    pub fn beam_count(&self) -> usize {
+        self.beams.len()
+    }
+
+    pub fn energy(&self) -> usize {
+        self.grid.energy
+    }
+
+    pub fn render_beams(&self) -> Vec<String> {
+        render(&self.grid.tiles, |tile| {
+            let directions: Vec<Direction> = tile.visited_directions();
+
+            match directions.as_slice() {
+                _ if !tile.is_empty() => tile.symbol(),
+                []                    => '.',
+                [dir]                 => dir.arrow(),
+                _                     => char::from_digit(directions.len() as u32, 10).unwrap()
+            }
+        })
+    }
+
+    pub fn render_energized(&self) -> Vec<String> {
+        render(&self.grid.tiles, |tile| if tile.is_energized() { '#' } else { '.' })
+    }
+}
+
+/* ------------------------------- Main logic ------------------------------- */
+
+fn render(tiles: &Grid<Tile>, symbol: impl Fn(&Tile) -> char) -> Vec<String> {
+    (0 .. tiles.rows).map(|row| {
+        (0 .. tiles.cols).map(|col| symbol(&tiles[Pos::new(row, col)])).collect()
+    }).collect()
+}
+
+// The beam entering the grid from the given edge ("top", "bottom", "left" or
+// "right"), in the given column (for the top and bottom edges) or row (for the
+// left and right edges). The A part uses the beam entering at the left in row 0.
+
+pub fn entry_beam(tiles: &Grid<Tile>, edge: &str, index: isize) -> Option<Beam> {
+    let (size, pos, dir): (isize, Pos, Direction) = match edge {
+        "top"    => (tiles.cols, Pos::new(-1, index),         Direction::South),
+        "bottom" => (tiles.cols, Pos::new(tiles.rows, index), Direction::North),
+        "left"   => (tiles.rows, Pos::new(index, -1),         Direction::East),
+        "right"  => (tiles.rows, Pos::new(index, tiles.cols), Direction::West),
+        _        => return None
+    };
+
+    if index < 0 || index >= size {
+        return None;
+    }
+
+    Some(Beam::new(pos, dir))
+}
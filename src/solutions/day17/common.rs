@@ -1,167 +1,454 @@
-/* -------------------------------- Direction ------------------------------- */
+use crate::solutions::Solution;
+use crate::solutions::geometry::Direction;
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::parse::ParseResult;
 
-#[derive(Copy, Clone, PartialEq)]
-pub enum Direction {
-    East,
-    South,
-    West,
-    North
-}
+// Both parts are the same shortest path problem with different turning rules,
+// so they share a single solver. The crucible starts in the top-left block, and
+// every block it enters adds the heat loss of that block. Since the rules
+// depend on the shape of the path, the state of the crucible is not just its
+// position, but also the direction it moves in and the number of blocks it has
+// moved since its last turn (which I call 'sslt' in the code). We search this
+// state space with Dijkstra's algorithm, i.e. we always expand the state with
+// the lowest heat loss so far, and the first time we pop a state at the bottom-
+// right block at which the crucible is allowed to stop, we've found the answer.
+// The memo keeps the best known loss for every state, so that we only queue
+// states that improve on it.
+//
+// On top of that, the memo prunes states that are dominated by another state
+// at the same block and in the same direction, but with a lower 'sslt': if the
+// other state got there with a loss that is not higher, and is allowed to turn
+// (i.e. its 'sslt' is at least the minimum), then every way to continue from
+// our state is also open to the other state, since it can move at least as far
+// in a straight line before it has to turn. There is then no reason to continue
+// our state. (Below the minimum this does not hold: a lower 'sslt' also means
+// having to move further before being allowed to turn.) States are checked both
+// when they are queued, and when they are popped from the queue, since a better
+// state may have been found in the meantime. The solver counts how many states
+// it expanded and how many it pruned, and both parts report these counts after
+// the heat loss. The pruning can be switched off (see `search()`, and the
+// `route` command of the runner), to compare the counts with and without it.
+//
+// Every block adds a loss between 0 and 9, so all queued states have a loss
+// within ten of the lowest one. Rather than a binary heap, we can therefore use
+// a ring of ten buckets, one for every possible loss modulo ten, which makes
+// every push and pop (nearly) constant time.
+//
+// To see which path the crucible actually took (e.g. when trying out other
+// rules), the memo also remembers the previous state of the best path to every
+// state, from which we can reconstruct the full route once we reach the end.
+// The `route` command of the runner prints it on request: its segments (the
+// blocks moved in a straight line, along with the heat loss of each segment),
+// and the city map with the route drawn on it using arrows, like in the puzzle
+// description.
 
-impl Direction {
-    pub fn left(&self) -> Direction {
-        match self {
-            Direction::East  => Direction::North,
-            Direction::South => Direction::East,
-            Direction::West  => Direction::South,
-            Direction::North => Direction::West
-        }
-    }
+pub const MAX_LOSS: usize = 9;
 
-    pub fn right(&self) -> Direction {
-        match self {
-            Direction::East  => Direction::South,
-            Direction::South => Direction::West,
-            Direction::West  => Direction::North,
-            Direction::North => Direction::East
-        }
-    }
+const NO_STATE: usize = usize::MAX;
 
-    pub fn step(&self, pos: &(isize, isize)) -> (isize, isize) {
-        match self {
-            Direction::East  => (pos.0, pos.1 + 1),
-            Direction::South => (pos.0 + 1, pos.1),
-            Direction::West  => (pos.0, pos.1 - 1),
-            Direction::North => (pos.0 - 1, pos.1)
-        }
-    }
+/* ---------------------------------- Rules --------------------------------- */
+
+// The crucible has to move at least `min_straight` blocks in a straight line
+// before it can turn or stop, and can move at most `max_straight` blocks in a
+// straight line.
+
+pub struct Rules {
+    pub min_straight: usize,
+    pub max_straight: usize
 }
 
-/* ---------------------------------- Step ---------------------------------- */
+/* ---------------------------------- State --------------------------------- */
 
-pub struct Step {
-    pub pos: (isize, isize),
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct State {
+    pub pos: Pos,
     pub dir: Direction,
-    pub sslt: usize,
-    pub loss: usize
+    pub sslt: usize
 }
 
-impl Step {
-    pub fn initial() -> Vec<Step> {
+impl State {
+    // The crucible has no direction yet in the top-left block, so it starts by
+    // moving either east or south.
+
+    pub fn initial() -> Vec<State> {
         vec![
-            Step { pos: (0, 1), dir: Direction::East,  sslt: 1,  loss: 0 },
-            Step { pos: (1, 0), dir: Direction::South, sslt: 1,  loss: 0 }
+            State { pos: Pos::new(0, 1), dir: Direction::East,  sslt: 1 },
+            State { pos: Pos::new(1, 0), dir: Direction::South, sslt: 1 }
         ]
     }
 
-    pub fn get_forward_step(&self, loss: usize) -> Step {
-        Step { pos: self.dir.step(&self.pos), dir: self.dir, sslt: self.sslt + 1, loss: loss }
-    }
+    pub fn next_states(&self, rules: &Rules) -> Vec<State> {
+        let mut states: Vec<State> = Vec::with_capacity(3);
+
+        if self.sslt < rules.max_straight {
+            states.push(State { pos: self.dir.step(self.pos), dir: self.dir, sslt: self.sslt + 1 });
+        }
 
-    pub fn get_turn_steps(&self, loss: usize) -> (Step, Step) {
-        let left_dir: Direction = self.dir.left();
-        let right_dir: Direction = self.dir.right();
+        if self.sslt >= rules.min_straight {
+            for dir in [self.dir.left(), self.dir.right()] {
+                states.push(State { pos: dir.step(self.pos), dir, sslt: 1 });
+            }
+        }
+
+        states
+    }
 
-        (
-            Step { pos: left_dir.step(&self.pos),  dir: left_dir,  sslt: 1, loss: loss },
-            Step { pos: right_dir.step(&self.pos), dir: right_dir, sslt: 1, loss: loss }
-        )
+    pub fn can_stop(&self, rules: &Rules) -> bool {
+        self.sslt >= rules.min_straight
     }
 }
 
 /* ---------------------------------- Memo ---------------------------------- */
 
+// The best known loss for every state, and the previous state on the path that
+// led to it, stored in flat vectors with one entry for every combination of
+// block, direction and 'sslt'.
+
 pub struct Memo {
-    pub best_east:  Vec<usize>,
-    pub best_south: Vec<usize>,
-    pub best_west:  Vec<usize>,
-    pub best_north: Vec<usize>
+    best: Vec<usize>,
+    previous: Vec<usize>,
+    cols: usize,
+    min_straight: usize,
+    max_straight: usize,
+    prune: bool,
+    pub pruned: usize
 }
 
 impl Memo {
-    fn new(length: usize) -> Memo {
+    pub fn new(grid: &Grid<u8>, rules: &Rules, prune: bool) -> Memo {
+        let length: usize = (grid.rows * grid.cols) as usize * 4 * rules.max_straight;
+
         Memo {
-            best_east:  vec![usize::MAX; length],
-            best_south: vec![usize::MAX; length],
-            best_west:  vec![usize::MAX; length],
-            best_north: vec![usize::MAX; length]
+            best: vec![usize::MAX; length],
+            previous: vec![NO_STATE; length],
+            cols: grid.cols as usize,
+            min_straight: rules.min_straight,
+            max_straight: rules.max_straight,
+            prune,
+            pruned: 0
+        }
+    }
+
+    fn index(&self, state: &State) -> usize {
+        let cell: usize = state.pos.row as usize * self.cols + state.pos.col as usize;
+        (cell * 4 + state.dir.index()) * self.max_straight + state.sslt - 1
+    }
+
+    fn state(&self, index: usize) -> State {
+        let sslt: usize = index % self.max_straight + 1;
+        let dir: Direction = Direction::ALL[index / self.max_straight % 4];
+        let cell: usize = index / self.max_straight / 4;
+        State { pos: Pos::new((cell / self.cols) as isize, (cell % self.cols) as isize), dir, sslt }
+    }
+
+    pub fn get(&self, state: &State) -> usize {
+        self.best[self.index(state)]
+    }
+
+    // Checks whether a state with a lower 'sslt' that is allowed to turn got to
+    // the same block in the same direction with at most the given loss.
+
+    fn is_dominated(&self, state: &State, loss: usize) -> bool {
+        let index: usize = self.index(state);
+        let lowest: usize = self.min_straight.max(1);
+
+        self.prune && state.sslt > lowest &&
+            (1 ..= state.sslt - lowest).any(|offset| self.best[index - offset] <= loss)
+    }
+
+    // Checks whether a state popped from the queue still needs to be expanded,
+    // i.e. whether its loss is still the best one, and it is not dominated.
+
+    pub fn should_expand(&mut self, state: &State, loss: usize) -> bool {
+        if loss > self.get(state) {
+            return false;
+        }
+
+        if self.is_dominated(state, loss) {
+            self.pruned += 1;
+            return false;
+        }
+
+        true
+    }
+
+    // Records the loss for the state (reached from the given previous state) if
+    // it is better than the best known loss and not dominated, and returns
+    // whether it was.
+
+    pub fn update(&mut self, state: &State, loss: usize, previous: Option<&State>) -> bool {
+        let index: usize = self.index(state);
+
+        if loss >= self.best[index] {
+            return false;
+        }
+
+        if self.is_dominated(state, loss) {
+            self.pruned += 1;
+            return false;
+        }
+
+        self.best[index] = loss;
+        self.previous[index] = previous.map_or(NO_STATE, |previous| self.index(previous));
+        true
+    }
+
+    // Follows the previous states back to the start, and returns all states on
+    // the way, ending with the given state.
+
+    pub fn path_to(&self, state: &State) -> Vec<State> {
+        let mut path: Vec<State> = vec![*state];
+        let mut index: usize = self.previous[self.index(state)];
+
+        while index != NO_STATE {
+            path.push(self.state(index));
+            index = self.previous[index];
         }
+
+        path.reverse();
+        path
     }
+}
+
+/* ------------------------------- BucketQueue ------------------------------ */
+
+// A priority queue for priorities that never lie more than `MAX_LOSS` above
+// the lowest priority in the queue, and never below it.
 
-    fn update(&mut self, step: &Step, loss: usize) -> bool {
-        let best: &mut Vec<usize> = match step.dir {
-            Direction::East  => &mut self.best_east,
-            Direction::South => &mut self.best_south,
-            Direction::West  => &mut self.best_west,
-            Direction::North => &mut self.best_north
-        };
+struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    current: usize,
+    length: usize
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new() -> BucketQueue<T> {
+        BucketQueue { buckets: (0 ..= MAX_LOSS).map(|_| Vec::new()).collect(), current: 0, length: 0 }
+    }
 
-        let index: usize = step.sslt - 1;
-        let better: bool = loss < best[index];
+    pub fn push(&mut self, priority: usize, item: T) {
+        let count: usize = self.buckets.len();
+        self.buckets[priority % count].push(item);
+        self.length += 1;
+    }
 
-        if better {
-            best[index] = loss;
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        if self.length == 0 {
+            return None;
         }
 
-        better
+        let count: usize = self.buckets.len();
+
+        loop {
+            if let Some(item) = self.buckets[self.current % count].pop() {
+                self.length -= 1;
+                return Some((self.current, item));
+            }
+
+            self.current += 1;
+        }
     }
 }
 
-/* ---------------------------------- Tile ---------------------------------- */
+/* ---------------------------------- Route --------------------------------- */
+
+// The best path of the crucible, as the states after every block it entered.
+
+pub struct Route {
+    pub loss: usize,
+    pub states: Vec<State>
+}
+
+// A part of the route in which the crucible moves in a straight line.
 
-pub struct Tile {
-    pub value: usize,
-    pub memo: Memo
+pub struct Segment {
+    pub dir: Direction,
+    pub length: usize,
+    pub loss: usize
 }
 
-impl Tile {
-    fn parse(c: char, memo_length: usize) -> Tile {
-        let value: usize = c.to_string().parse().unwrap();
-        Tile { value: value, memo: Memo::new(memo_length) }
+impl Route {
+    pub fn segments(&self, grid: &Grid<u8>) -> Vec<Segment> {
+        let mut segments: Vec<Segment> = Vec::new();
+
+        for state in &self.states {
+            let loss: usize = grid[state.pos] as usize;
+
+            match segments.last_mut() {
+                Some(segment) if state.sslt > 1 => {
+                    segment.length += 1;
+                    segment.loss += loss;
+                },
+                _ => segments.push(Segment { dir: state.dir, length: 1, loss })
+            }
+        }
+
+        segments
+    }
+
+    pub fn format_segments(&self, grid: &Grid<u8>) -> String {
+        self.segments(grid).iter()
+            .map(|segment| format!("{} {} (loss {})", segment.dir.arrow(), segment.length, segment.loss))
+            .collect::<Vec<String>>().join(", ")
+    }
+
+    // Draws the route on the city map; every block the crucible entered shows
+    // the direction in which it entered the block.
+
+    pub fn render(&self, grid: &Grid<u8>) -> String {
+        let mut map: Grid<char> = Grid::new(grid.tiles().iter().map(|loss| (b'0' + loss) as char).collect(),
+            grid.rows as usize, grid.cols as usize);
+
+        for state in &self.states {
+            map[state.pos] = state.dir.arrow();
+        }
+
+        map.render(|c| *c)
     }
 }
 
 /* ---------------------------------- Grid ---------------------------------- */
 
-pub struct Grid {
-    pub tiles: Vec<Tile>,
-    pub rows: isize,
-    pub cols: isize
+pub fn parse_grid(lines: &[String]) -> ParseResult<Grid<u8>> {
+    Grid::parse(lines, 0, "a digit", |c| c.to_digit(10).map(|d| d as u8))
+}
+
+/* ------------------------------- Main logic ------------------------------- */
+
+// The number of states the solver expanded, and the number of states it
+// pruned because they were dominated by another state.
+
+pub struct Stats {
+    pub expanded: usize,
+    pub pruned: usize
+}
+
+// The result of a search: the lowest heat loss with which the crucible can
+// reach the bottom-right block (or `None` if it cannot get there under the
+// given rules), and the statistics of the search. The route itself is only
+// reconstructed from the memo when asked for.
+
+pub struct Search {
+    pub loss: Option<usize>,
+    pub stats: Stats,
+    memo: Memo,
+    end: Option<State>
+}
+
+impl Search {
+    pub fn route(&self) -> Option<Route> {
+        let loss: usize = self.loss?;
+        let states: Vec<State> = self.end.as_ref().map_or(Vec::new(), |end| self.memo.path_to(end));
+        Some(Route { loss, states })
+    }
 }
 
-impl Grid {
-    pub fn parse(lines: &[String], memo_length: usize) -> Grid {
-        let tiles: Vec<Tile> = lines.concat().chars()
-            .map(|c| Tile::parse(c, memo_length)).collect();
+// Finds the lowest heat loss under the given rules. Dominated states are only
+// pruned if `prune` is set; the heat loss is the same either way.
+
+pub fn search(grid: &Grid<u8>, rules: &Rules, prune: bool) -> Search {
+    let end: Pos = Pos::new(grid.rows - 1, grid.cols - 1);
+    let mut memo: Memo = Memo::new(grid, rules, prune);
+    let mut queue: BucketQueue<State> = BucketQueue::new();
+    let mut expanded: usize = 0;
+
+    if end == Pos::new(0, 0) {
+        return Search { loss: Some(0), stats: Stats { expanded: 0, pruned: 0 }, memo, end: None };
+    }
 
-        Grid {
-            tiles: tiles,
-            rows: lines.len() as isize,
-            cols: lines[0].len() as isize
+    for state in State::initial() {
+        if grid.contains(state.pos) && memo.update(&state, grid[state.pos] as usize, None) {
+            queue.push(grid[state.pos] as usize, state);
         }
     }
 
-    pub fn value(&self, pos: &(isize, isize)) -> usize {
-        self.tiles[self.to_index(pos)].value
+    while let Some((loss, state)) = queue.pop() {
+        if !memo.should_expand(&state, loss) {
+            continue;
+        }
+
+        if state.pos == end && state.can_stop(rules) {
+            let stats: Stats = Stats { expanded, pruned: memo.pruned };
+            return Search { loss: Some(loss), stats, memo, end: Some(state) };
+        }
+
+        expanded += 1;
+
+        for next in state.next_states(rules) {
+            if !grid.contains(next.pos) {
+                continue;
+            }
+
+            let next_loss: usize = loss + grid[next.pos] as usize;
+
+            if memo.update(&next, next_loss, Some(&state)) {
+                queue.push(next_loss, next);
+            }
+        }
     }
 
-    pub fn contains(&self, pos: &(isize, isize)) -> bool {
-        pos.0 >= 0 && pos.0 < self.rows && pos.1 >= 0 && pos.1 < self.cols
+    let stats: Stats = Stats { expanded, pruned: memo.pruned };
+    Search { loss: None, stats, memo, end: None }
+}
+
+// Solves a part with the given rules (pruning dominated states), returning the
+// heat loss along with the statistics of the search. The route is left to the
+// `route` command of the runner, since rendering it takes longer than finding it.
+
+pub fn solve_with_rules(grid: &Grid<u8>, rules: &Rules) -> Solution {
+    let search: Search = search(grid, rules, true);
+
+    let Some(loss) = search.loss else {
+        return Solution::unsolved("the crucible cannot reach the bottom-right block");
+    };
+
+    Solution::Multiple(vec![
+        ("loss",     Solution::Integer(loss as i64)),
+        ("expanded", Solution::Unsigned(search.stats.expanded as u64)),
+        ("pruned",   Solution::Unsigned(search.stats.pruned as u64))
+    ])
+}
+
+/* ---------------------------------- Tests --------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(sample: &str) -> Grid<u8> {
+        let lines: Vec<String> = sample.lines().map(String::from).collect();
+        parse_grid(&lines).unwrap()
     }
 
-    pub fn update(&mut self, step: &Step, loss: usize) -> bool {
-        let index: usize = self.to_index(&step.pos);
-        let tile: &mut Tile = &mut self.tiles[index];
-        tile.memo.update(step, loss)
+    fn loss(grid: &Grid<u8>, rules: &Rules, prune: bool) -> Option<usize> {
+        search(grid, rules, prune).loss
     }
 
-    pub fn get_best_memo(&self) -> &Memo {
-        let br_pos: (isize, isize) = (self.rows - 1, self.cols - 1);
-        let index: usize = self.to_index(&br_pos);
-        &self.tiles[index].memo
+    #[test]
+    fn pruning_keeps_loss_on_samples() {
+        let samples: Vec<Grid<u8>> = vec![load(include_str!("input/sample.txt")), load(include_str!("input/sample_b.txt"))];
+        let rules: Vec<Rules> = vec![Rules { min_straight: 1, max_straight: 3 }, Rules { min_straight: 4, max_straight: 10 }];
+
+        for grid in &samples {
+            for rules in &rules {
+                assert_eq!(loss(grid, rules, true), loss(grid, rules, false));
+            }
+        }
+
+        assert_eq!(loss(&samples[0], &rules[0], true), Some(102));
+        assert_eq!(loss(&samples[0], &rules[1], true), Some(94));
+        assert_eq!(loss(&samples[1], &rules[1], true), Some(71));
     }
 
-    fn to_index(&self, pos: &(isize, isize)) -> usize {
-        (pos.0 * self.cols + pos.1) as usize
+    #[test]
+    fn pruning_reduces_expanded_states() {
+        let grid: Grid<u8> = load(include_str!("input/sample.txt"));
+        let rules: Rules = Rules { min_straight: 1, max_straight: 3 };
+        let pruned: Stats = search(&grid, &rules, true).stats;
+        let unpruned: Stats = search(&grid, &rules, false).stats;
+
+        assert!(pruned.pruned > 0);
+        assert_eq!(unpruned.pruned, 0);
+        assert!(pruned.expanded < unpruned.expanded);
     }
 }
@@ -1,30 +1,39 @@
+pub use crate::solutions::geometry::Direction;
+use crate::solutions::parse::{Line, ParseResult};
+
 /* -------------------------------- Direction ------------------------------- */
 
-pub enum Direction {
-    North,
-    East,
-    South,
-    West
+// The A part writes directions as letters ("R", "L", "U" and "D"), while the B
+// part encodes them as the last hexadecimal digit of the color.
+
+pub fn parse_direction_a(line: &Line, input: &str) -> ParseResult<Direction> {
+    let mut chars = input.chars();
+
+    match (chars.next().and_then(Direction::from_letter), chars.next()) {
+        (Some(dir), None) => Ok(dir),
+        _ => Err(line.error(input, "'R', 'L', 'U' or 'D'"))
+    }
 }
 
-impl Direction {
-    pub fn parse_a(input: &str) -> Direction {
-        match input {
-            "R" => Direction::East,
-            "L" => Direction::West,
-            "U" => Direction::North,
-            "D" => Direction::South,
-            _ => panic!()
-        }
+pub fn parse_direction_b(line: &Line, input: &str) -> ParseResult<Direction> {
+    match input {
+        "0" => Ok(Direction::East),
+        "2" => Ok(Direction::West),
+        "3" => Ok(Direction::North),
+        "1" => Ok(Direction::South),
+        _ => Err(line.error(input, "a direction digit (0-3)"))
     }
+}
+
+/* --------------------------------- Parsing -------------------------------- */
+
+// Splits a line like "R 6 (#70c710)" into its direction, count and color.
+
+pub fn split_fields<'a>(line: &Line<'a>) -> ParseResult<(&'a str, &'a str, &'a str)> {
+    let fields: Vec<&str> = line.text.split(' ').collect();
 
-    pub fn parse_b(input: &str) -> Direction {
-        match input {
-            "0" => Direction::East,
-            "2" => Direction::West,
-            "3" => Direction::North,
-            "1" => Direction::South,
-            _ => panic!()
-        }
+    match fields[..] {
+        [dir, count, color] => Ok((dir, count, color)),
+        _ => Err(line.error(line.text, "a direction, a count and a color"))
     }
 }
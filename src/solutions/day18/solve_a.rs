@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 
 use crate::solutions::Solution;
+use crate::solutions::grid::Pos;
+use crate::solutions::parse::{self, Line, ParseResult};
 use crate::solutions::day18::common::*;
 
 // A naive solution, but it works. I've verified retroactively that the solution
@@ -17,22 +19,22 @@ use crate::solutions::day18::common::*;
 
 /* ------------------------------- Instruction ------------------------------ */
 
-struct Instruction {
+pub struct Instruction {
     dir: Direction,
     count: usize
 }
 
 impl Instruction {
-    fn parse(line: &str) -> Instruction {
-        let fields: Vec<&str> = line.split(' ').collect();
-        let dir: Direction = Direction::parse_a(fields[0]);
-        let count: usize = fields[1].parse().unwrap();
-        Instruction { dir, count }
+    fn parse(line: &Line) -> ParseResult<Instruction> {
+        let (dir_str, count_str, _) = split_fields(line)?;
+        let dir: Direction = parse_direction_a(line, dir_str)?;
+        let count: usize = line.number(count_str)?;
+        Ok(Instruction { dir, count })
     }
 
     fn apply(&self, digger: &mut Digger, dug_area: &mut DugArea) {
         for _ in 0 .. self.count {
-            digger.step(&self.dir);
+            digger.step(self.dir);
             dug_area.extend(digger.pos);
         }
     }
@@ -41,46 +43,30 @@ impl Instruction {
 /* --------------------------------- Digger --------------------------------- */
 
 struct Digger {
-    pos: (isize, isize),
-    side_l: HashSet<(isize, isize)>,
-    side_r: HashSet<(isize, isize)>
+    pos: Pos,
+    side_l: HashSet<Pos>,
+    side_r: HashSet<Pos>
 }
 
 impl Digger {
     fn new() -> Digger {
         Digger {
-            pos: (0, 0),
+            pos: Pos::new(0, 0),
             side_l: HashSet::new(),
             side_r: HashSet::new()
         }
     }
 
-    fn step(&mut self, dir: &Direction) {
-        match dir {
-            Direction::North => {
-                self.side_l.insert((self.pos.0 - 1, self.pos.1 - 1));
-                self.side_r.insert((self.pos.0 - 1, self.pos.1 + 1));
-                self.pos.0 -= 1;
-            },
-            Direction::East  => {
-                self.side_l.insert((self.pos.0 - 1, self.pos.1 + 1));
-                self.side_r.insert((self.pos.0 + 1, self.pos.1 + 1));
-                self.pos.1 += 1;
-            },
-            Direction::South => {
-                self.side_l.insert((self.pos.0 + 1, self.pos.1 + 1));
-                self.side_r.insert((self.pos.0 + 1, self.pos.1 - 1));
-                self.pos.0 += 1;
-            },
-            Direction::West  => {
-                self.side_l.insert((self.pos.0 + 1, self.pos.1 - 1));
-                self.side_r.insert((self.pos.0 - 1, self.pos.1 - 1));
-                self.pos.1 -= 1;
-            },
-        }
+    // Besides moving the digger, marks the tiles diagonally ahead of it on its
+    // left and right side.
+
+    fn step(&mut self, dir: Direction) {
+        self.side_l.insert(dir.step(dir.left().step(self.pos)));
+        self.side_r.insert(dir.step(dir.right().step(self.pos)));
+        self.pos = dir.step(self.pos);
     }
 
-    fn valid_side(&self, dug_area: &DugArea) -> &HashSet<(isize, isize)> {
+    fn valid_side(&self, dug_area: &DugArea) -> &HashSet<Pos> {
         if self.side_l.iter().all(|pos| dug_area.contains(pos)) {
             &self.side_l
         } else {
@@ -92,7 +78,7 @@ impl Digger {
 /* --------------------------------- DugArea -------------------------------- */
 
 struct DugArea {
-    tiles: HashSet<(isize, isize)>,
+    tiles: HashSet<Pos>,
     min_row: isize,
     max_row: isize,
     min_col: isize,
@@ -102,7 +88,7 @@ struct DugArea {
 impl DugArea {
     fn new() -> DugArea {
         DugArea {
-            tiles: HashSet::from([(0, 0)]),
+            tiles: HashSet::from([Pos::new(0, 0)]),
             min_row: 0,
             max_row: 0,
             min_col: 0,
@@ -110,30 +96,23 @@ impl DugArea {
         }
     }
 
-    fn extend(&mut self, pos: (isize, isize)) {
-        self.min_row = self.min_row.min(pos.0);
-        self.max_row = self.max_row.max(pos.0);
-        self.min_col = self.min_col.min(pos.1);
-        self.max_col = self.max_col.max(pos.1);
+    fn extend(&mut self, pos: Pos) {
+        self.min_row = self.min_row.min(pos.row);
+        self.max_row = self.max_row.max(pos.row);
+        self.min_col = self.min_col.min(pos.col);
+        self.max_col = self.max_col.max(pos.col);
         self.tiles.insert(pos);
     }
 
-    fn grow(&mut self, inside: &HashSet<(isize, isize)>) {
-        let mut queue: Vec<(isize, isize)> = Vec::new();
+    fn grow(&mut self, inside: &HashSet<Pos>) {
+        let mut queue: Vec<Pos> = inside.iter().copied().collect();
         let mut index: usize = 0;
 
-        for inside_tile in inside {
-            queue.push((inside_tile.0, inside_tile.1));
-        }
-
         while index < queue.len() {
-            let pos: (isize, isize) = queue[index];
+            let pos: Pos = queue[index];
 
             if !self.tiles.contains(&pos) {
-                queue.push((pos.0 - 1, pos.1));
-                queue.push((pos.0 + 1, pos.1));
-                queue.push((pos.0, pos.1 - 1));
-                queue.push((pos.0, pos.1 + 1));
+                queue.extend(pos.neighbors());
                 self.tiles.insert(pos);
             }
 
@@ -141,17 +120,18 @@ impl DugArea {
         }
     }
 
-    fn contains(&self, pos: &(isize, isize)) -> bool {
-        pos.0 >= self.min_row && pos.0 <= self.max_row && pos.1 >= self.min_col && pos.1 <= self.max_col
+    fn contains(&self, pos: &Pos) -> bool {
+        pos.row >= self.min_row && pos.row <= self.max_row && pos.col >= self.min_col && pos.col <= self.max_col
     }
 }
 
 /* ---------------------------------- Main ---------------------------------- */
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let instructions: Vec<Instruction> = lines.iter()
-        .map(|l| Instruction::parse(l)).collect();
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Instruction>> {
+    parse::lines(lines).map(|l| Instruction::parse(&l)).collect()
+}
 
+pub fn solve(instructions: &Vec<Instruction>) -> Solution {
     let mut dug_area: DugArea = DugArea::new();
     let mut digger: Digger = Digger::new();
     
@@ -159,7 +139,7 @@ pub fn solve(lines: &Vec<String>) -> Solution {
         instruction.apply(&mut digger, &mut dug_area);
     }
 
-    let inside: &HashSet<(isize, isize)> = digger.valid_side(&dug_area);
+    let inside: &HashSet<Pos> = digger.valid_side(&dug_area);
     
     dug_area.grow(inside);
 
@@ -1,6 +1,8 @@
 use std::ops::Range;
 
 use crate::solutions::Solution;
+use crate::solutions::grid::Pos;
+use crate::solutions::parse::{self, Line, ParseResult};
 use crate::solutions::day18::common::*;
 
 // The new scale makes the solution of the A part all but infeasible. Instead,
@@ -33,30 +35,37 @@ use crate::solutions::day18::common::*;
 
 /* ------------------------------- Instruction ------------------------------ */
 
-struct Instruction {
+pub struct Instruction {
     dir: Direction,
     count: usize
 }
 
 impl Instruction {
-    fn parse(line: &str) -> Instruction {
-        let fields: Vec<&str> = line.split(' ').collect();
-        let color_field: &str = fields[2];
-        let l: usize = color_field.len();
+    fn parse(line: &Line) -> ParseResult<Instruction> {
+        let (_, _, color_field) = split_fields(line)?;
+        let color: &str = line.strip_suffix(line.strip_prefix(color_field, "(#")?, ")")?;
 
-        let dir_char: &str = &color_field[l - 2 .. l - 1];
-        let dir: Direction = Direction::parse_b(dir_char);
+        if color.len() != 6 || !color.is_ascii() {
+            return Err(line.error(color, "six hexadecimal digits"));
+        }
+
+        let dir_char: &str = &color[5 ..];
+        let dir: Direction = parse_direction_b(line, dir_char)?;
+
+        let count_str: &str = &color[.. 5];
+        let count: usize = match usize::from_str_radix(count_str, 16) {
+            Ok(count) => count,
+            Err(_) => return Err(line.error(count_str, "a hexadecimal number"))
+        };
 
-        let count_str: &str = &color_field[2 .. l - 2];
-        let count: usize = usize::from_str_radix(count_str, 16).unwrap();
-        Instruction { dir, count }
+        Ok(Instruction { dir, count })
     }
 
     fn apply(&self, digger: &mut Digger) -> WallSegment {
-        let start_pos: (isize, isize) = digger.pos;
+        let start_pos: Pos = digger.pos;
 
         for _ in 0 .. self.count {
-            digger.step(&self.dir);
+            digger.step(self.dir);
         }
 
         digger.to_segment(start_pos, &self.dir)
@@ -66,29 +75,24 @@ impl Instruction {
 /* --------------------------------- Digger --------------------------------- */
 
 struct Digger {
-    pos: (isize, isize)
+    pos: Pos
 }
 
 impl Digger {
     fn new() -> Digger {
-        Digger { pos: (0, 0) }
+        Digger { pos: Pos::new(0, 0) }
     }
 
-    fn step(&mut self, dir: &Direction) {
-        match dir {
-            Direction::North => { self.pos.0 -= 1; },
-            Direction::East  => { self.pos.1 += 1; },
-            Direction::South => { self.pos.0 += 1; },
-            Direction::West  => { self.pos.1 -= 1; },
-        }
+    fn step(&mut self, dir: Direction) {
+        self.pos = dir.step(self.pos);
     }
 
-    fn to_segment(&self, start: (isize, isize), dir: &Direction) -> WallSegment {
+    fn to_segment(&self, start: Pos, dir: &Direction) -> WallSegment {
         match dir {
-            Direction::West  => WallSegment::create_horizontal(self.pos.1, start.1, self.pos.0),
-            Direction::East  => WallSegment::create_horizontal(start.1, self.pos.1, self.pos.0),
-            Direction::North => WallSegment::create_vertical(self.pos.0, start.0, self.pos.1),
-            Direction::South => WallSegment::create_vertical(start.0, self.pos.0, self.pos.1),
+            Direction::West  => WallSegment::create_horizontal(self.pos.col, start.col, self.pos.row),
+            Direction::East  => WallSegment::create_horizontal(start.col, self.pos.col, self.pos.row),
+            Direction::North => WallSegment::create_vertical(self.pos.row, start.row, self.pos.col),
+            Direction::South => WallSegment::create_vertical(start.row, self.pos.row, self.pos.col),
         }
     }
 }
@@ -200,10 +204,11 @@ fn calculate_area(h_segments: Vec<WallSegment>, v_segments: Vec<WallSegment>) ->
     area
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let instructions: Vec<Instruction> = lines.iter()
-        .map(|l| Instruction::parse(l)).collect();
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Instruction>> {
+    parse::lines(lines).map(|l| Instruction::parse(&l)).collect()
+}
 
+pub fn solve(instructions: &Vec<Instruction>) -> Solution {
     let mut digger: Digger = Digger::new();
 
     let segments: Vec<WallSegment> = instructions.iter()
@@ -1,3 +1,7 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::solutions::parse::{self, Line, ParseError, ParseResult};
+
 /* ------------------------------- Comparator ------------------------------- */
 
 pub enum Comparator {
@@ -13,28 +17,36 @@ pub enum Rule {
 }
 
 impl Rule {
-    pub fn parse(field: &str) -> Rule {
+    pub fn parse(line: &Line, field: &str, labels: &HashSet<&str>) -> ParseResult<Rule> {
         if let Some(fields) = field.split_once(':') {
-            let target: String = String::from(fields.1);
-            let (index, cmp) = Self::parse_predicate(fields.0);
-            Rule::Check(index, cmp, target)
+            let target: String = Self::parse_target(line, fields.1, labels)?;
+            let (index, cmp) = Self::parse_predicate(line, fields.0)?;
+            Ok(Rule::Check(index, cmp, target))
         } else {
-            Rule::Always(String::from(field))
+            Ok(Rule::Always(Self::parse_target(line, field, labels)?))
         }
     }
 
-    pub fn parse_predicate(field: &str) -> (usize, Comparator) {
+    fn parse_target(line: &Line, field: &str, labels: &HashSet<&str>) -> ParseResult<String> {
+        if field == "A" || field == "R" || labels.contains(field) {
+            Ok(String::from(field))
+        } else {
+            Err(line.error(field, "'A', 'R' or the label of a workflow"))
+        }
+    }
+
+    pub fn parse_predicate(line: &Line, field: &str) -> ParseResult<(usize, Comparator)> {
         if let Some(fields) = field.split_once('<') {
-            let index: usize = xmas_to_index(fields.0);
-            let value: usize = fields.1.parse().unwrap();
-            return (index, Comparator::LessThan(value));
+            let index: usize = xmas_to_index(line, fields.0)?;
+            let value: usize = line.number(fields.1)?;
+            return Ok((index, Comparator::LessThan(value)));
         } else if let Some(fields) = field.split_once('>') {
-            let index: usize = xmas_to_index(fields.0);
-            let value: usize = fields.1.parse().unwrap();
-            return (index, Comparator::GreaterThan(value));
+            let index: usize = xmas_to_index(line, fields.0)?;
+            let value: usize = line.number(fields.1)?;
+            return Ok((index, Comparator::GreaterThan(value)));
         }
 
-        panic!();
+        Err(line.error(field, "a comparison using '<' or '>'"))
     }
 }
 
@@ -46,23 +58,51 @@ pub struct Workflow {
 }
 
 impl Workflow {
-    pub fn parse(line: &str) -> Workflow {
-        let (label, rules_str) = line.split_once('{').unwrap();
-        let rule_fields: Vec<&str> = rules_str[0 .. rules_str.len() - 1].split(',').collect();
-        let rules: Vec<Rule> = rule_fields.into_iter().map(|f| Rule::parse(f)).collect();
+    // Every rule must send parts to "A", "R", or one of the given labels, and
+    // the last rule must not have a condition, so that every part has a target.
+
+    pub fn parse(line: &Line, labels: &HashSet<&str>) -> ParseResult<Workflow> {
+        let (label, rules_str) = line.split_once(line.text, "{")?;
+        let rule_fields: Vec<&str> = line.strip_suffix(rules_str, "}")?.split(',').collect();
+        let rules: Vec<Rule> = rule_fields.iter().map(|f| Rule::parse(line, f, labels)).collect::<ParseResult<Vec<Rule>>>()?;
+
+        if !matches!(rules.last(), Some(Rule::Always(_))) {
+            return Err(line.error(rule_fields[rule_fields.len() - 1], "a final rule without a condition"));
+        }
 
-        Workflow { label: String::from(label), rules: rules }
+        Ok(Workflow { label: String::from(label), rules })
     }
 }
 
+// The workflows are listed before the first empty line of the input, and must
+// include the "in" workflow that all parts start at.
+
+pub fn parse_workflows(lines: &Vec<String>) -> ParseResult<HashMap<String, Workflow>> {
+    let workflow_lines: Vec<Line> = parse::lines(lines).take_while(|line| !line.text.is_empty()).collect();
+    let labels: HashSet<&str> = workflow_lines.iter()
+        .filter_map(|line| line.text.split_once('{')).map(|(label, _)| label).collect();
+    let mut workflow_map: HashMap<String, Workflow> = HashMap::new();
+
+    for line in &workflow_lines {
+        let workflow: Workflow = Workflow::parse(line, &labels)?;
+        workflow_map.insert(workflow.label.clone(), workflow);
+    }
+
+    if !workflow_map.contains_key("in") {
+        return Err(ParseError::new(workflow_lines.len() + 1, None, "a workflow labeled 'in'", ""));
+    }
+
+    Ok(workflow_map)
+}
+
 /* --------------------------------- Helper --------------------------------- */
 
-pub fn xmas_to_index(char_str: &str) -> usize {
+pub fn xmas_to_index(line: &Line, char_str: &str) -> ParseResult<usize> {
     match char_str {
-        "x" => 0,
-        "m" => 1,
-        "a" => 2,
-        "s" => 3,
-        _ => panic!()
+        "x" => Ok(0),
+        "m" => Ok(1),
+        "a" => Ok(2),
+        "s" => Ok(3),
+        _ => Err(line.error(char_str, "'x', 'm', 'a' or 's'"))
     }
 }
\ No newline at end of file
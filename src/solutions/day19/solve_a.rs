@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use crate::solutions::Solution;
+use crate::solutions::parse::{self, Line, ParseResult};
 use crate::solutions::day19::common::*;
 
 // Fairly straightforward, if maybe a bit too verbose. Not much to say about this
@@ -40,32 +41,32 @@ impl Rule {
 
 /* ---------------------------------- Part ---------------------------------- */
 
-struct Part {
+pub struct Part {
     values: Vec<usize>,
     total: usize
 }
 
 impl Part {
-    fn parse(line: &str) -> Part {
-        let fields: Vec<&str> = line[1 .. line.len() - 1].split(',').collect();
+    fn parse(line: &Line) -> ParseResult<Part> {
+        let fields: Vec<&str> = line.strip_suffix(line.strip_prefix(line.text, "{")?, "}")?.split(',').collect();
         let pairs: Vec<(&str, &str)> = fields.into_iter()
-            .map(|f| f.split_once('=').unwrap()).collect();
+            .map(|f| line.split_once(f, "=")).collect::<ParseResult<Vec<(&str, &str)>>>()?;
 
         let mut values: Vec<usize> = vec![0; 4];
         let mut total: usize = 0;
 
         for pair in pairs {
-            let index: usize = xmas_to_index(pair.0);
-            let value: usize = pair.1.parse().unwrap();
+            let index: usize = xmas_to_index(line, pair.0)?;
+            let value: usize = line.number(pair.1)?;
             values[index] = value;
             total += value;
         }
 
-        Part { values, total }
+        Ok(Part { values, total })
     }
 
     fn apply(&self, workflow_label: String, workflow_map: &HashMap<String, Workflow>) -> String {
-        let workflow: &Workflow = workflow_map.get(&workflow_label).unwrap();
+        let workflow: &Workflow = &workflow_map[&workflow_label];
 
         for rule in &workflow.rules {
             if let Some(target) = rule.check(self) {
@@ -73,20 +74,25 @@ impl Part {
             }
         }
 
-        panic!()
+        unreachable!("the last rule of a workflow has no condition")
     }
 }
 
 /* ---------------------------------- Main ---------------------------------- */
 
-fn apply_all(parts: Vec<Part>, workflow_map: HashMap<String, Workflow>) -> usize {
+pub struct System {
+    parts: Vec<Part>,
+    workflow_map: HashMap<String, Workflow>
+}
+
+fn apply_all(parts: &Vec<Part>, workflow_map: &HashMap<String, Workflow>) -> usize {
     let mut total: usize = 0;
 
     for part in parts {
         let mut current_workflow: String = String::from("in");
 
         loop {
-            current_workflow = part.apply(current_workflow, &workflow_map);
+            current_workflow = part.apply(current_workflow, workflow_map);
 
             if current_workflow == "A" {
                 total += part.total;
@@ -100,14 +106,17 @@ fn apply_all(parts: Vec<Part>, workflow_map: HashMap<String, Workflow>) -> usize
     total
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let blocks: Vec<&[String]> = lines.split(|line| line.is_empty()).collect();
-    let workflows: Vec<Workflow> = blocks[0].iter().map(|line| Workflow::parse(line)).collect();
-    let parts: Vec<Part> = blocks[1].iter().map(|line| Part::parse(line)).collect();
+pub fn parse(lines: &Vec<String>) -> ParseResult<System> {
+    let workflow_map: HashMap<String, Workflow> = parse_workflows(lines)?;
+    let parts: Vec<Part> = parse::lines(lines)
+        .skip_while(|line| !line.text.is_empty()).skip(1)
+        .map(|line| Part::parse(&line))
+        .collect::<ParseResult<Vec<Part>>>()?;
 
-    let mut workflow_map: HashMap<String, Workflow> = HashMap::new();
-    workflows.into_iter().for_each(|w| { workflow_map.insert(w.label.clone(), w); });
+    Ok(System { parts, workflow_map })
+}
 
-    let result: usize = apply_all(parts, workflow_map);
+pub fn solve(system: &System) -> Solution {
+    let result: usize = apply_all(&system.parts, &system.workflow_map);
     return Solution::Integer(result as i64)
 }
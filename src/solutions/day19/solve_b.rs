@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use crate::solutions::Solution;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day19::common::*;
 
 // Two days in a row where the solution to the B part comes down to "let's
@@ -150,7 +151,7 @@ impl Part {
 
 /* ---------------------------------- Main ---------------------------------- */
 
-fn apply_all(workflow_map: HashMap<String, Workflow>) -> usize {
+fn apply_all(workflow_map: &HashMap<String, Workflow>) -> usize {
     let mut parts: Vec<Part> = vec!(Part::initial());
     let mut total: usize = 0;
     let mut index: usize = 0;
@@ -158,7 +159,7 @@ fn apply_all(workflow_map: HashMap<String, Workflow>) -> usize {
 
     while index < parts.len() {
         let part: &Part = &parts[index];
-        let workflow: &Workflow = workflow_map.get(&part.at).unwrap();
+        let workflow: &Workflow = &workflow_map[&part.at];
         let new_parts: Vec<Part> = workflow.apply(part);
 
         for new_part in new_parts {
@@ -175,12 +176,11 @@ fn apply_all(workflow_map: HashMap<String, Workflow>) -> usize {
     total
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let blocks: Vec<&[String]> = lines.split(|line| line.is_empty()).collect();
-    let workflows: Vec<Workflow> = blocks[0].iter().map(|line| Workflow::parse(line)).collect();
+pub fn parse(lines: &Vec<String>) -> ParseResult<HashMap<String, Workflow>> {
+    parse_workflows(lines)
+}
 
-    let mut workflow_map: HashMap<String, Workflow> = HashMap::new();
-    workflows.into_iter().for_each(|w| { workflow_map.insert(w.label.clone(), w); });
+pub fn solve(workflow_map: &HashMap<String, Workflow>) -> Solution {
     let result: usize = apply_all(workflow_map);
-    return Solution::Integer(result as i64)
+    return Solution::Unsigned(result as u64)
 }
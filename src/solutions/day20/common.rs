@@ -1,4 +1,7 @@
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use crate::solutions::parse::{self, Line, ParseError, ParseResult};
 
 /* ------------------------------- PulseValue ------------------------------- */
 
@@ -10,6 +13,7 @@ pub enum PulseValue {
 
 /* ---------------------------------- State --------------------------------- */
 
+#[derive(Clone)]
 pub enum State {
     On,
     Off
@@ -24,6 +28,15 @@ impl State {
     }
 }
 
+/* ------------------------------- ModuleKind ------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ModuleKind {
+    Broadcaster,
+    FlipFlop,
+    Conjunction
+}
+
 /* ---------------------------------- Pulse --------------------------------- */
 
 pub struct Pulse {
@@ -49,10 +62,15 @@ pub trait Module {
     fn register_input(&mut self, input_id: usize);
     fn get_outputs(&self) -> &Vec<usize>;
     fn get_inputs(&self) -> &Vec<usize>;
+    fn get_state(&self) -> Vec<bool>;
+    fn set_state(&mut self, state: &[bool]);
+    fn get_kind(&self) -> ModuleKind;
+    fn clone_box(&self) -> Box<dyn Module>;
 }
 
 /* ------------------------------- Broadcaster ------------------------------ */
 
+#[derive(Clone)]
 pub struct Broadcaster {
     id:      usize,
     outputs: Vec<usize>,
@@ -75,16 +93,21 @@ impl Module for Broadcaster {
     fn register_input(&mut self, input_id: usize) { self.inputs.push(input_id); }
     fn get_outputs(&self) -> &Vec<usize> { &self.outputs }
     fn get_inputs(&self) -> &Vec<usize> { &self.inputs }
+    fn get_state(&self) -> Vec<bool> { Vec::new() }
+    fn set_state(&mut self, _state: &[bool]) {}
+    fn get_kind(&self) -> ModuleKind { ModuleKind::Broadcaster }
+    fn clone_box(&self) -> Box<dyn Module> { Box::new(self.clone()) }
 }
 
 impl Broadcaster {
-    pub fn new(id: usize, output_ids: Vec<usize>) -> Box<dyn Module> {
-        Box::new(Broadcaster { id: id, outputs: output_ids, inputs: Vec::new() })
+    pub fn boxed(id: usize, output_ids: Vec<usize>) -> Box<dyn Module> {
+        Box::new(Broadcaster { id, outputs: output_ids, inputs: Vec::new() })
     }
 }
 
 /* -------------------------------- FlipFlop -------------------------------- */
 
+#[derive(Clone)]
 pub struct FlipFlop {
     id:      usize,
     state:   State,
@@ -118,12 +141,16 @@ impl Module for FlipFlop {
     fn register_input(&mut self, input_id: usize) { self.inputs.push(input_id); }
     fn get_outputs(&self) -> &Vec<usize> { &self.outputs }
     fn get_inputs(&self) -> &Vec<usize> { &self.inputs }
+    fn get_state(&self) -> Vec<bool> { vec![matches!(self.state, State::On)] }
+    fn set_state(&mut self, state: &[bool]) { self.state = if state[0] { State::On } else { State::Off }; }
+    fn get_kind(&self) -> ModuleKind { ModuleKind::FlipFlop }
+    fn clone_box(&self) -> Box<dyn Module> { Box::new(self.clone()) }
 }
 
 impl FlipFlop {
-    pub fn new(id: usize, output_ids: Vec<usize>) -> Box<dyn Module> {
+    pub fn boxed(id: usize, output_ids: Vec<usize>) -> Box<dyn Module> {
         Box::new(FlipFlop {
-            id:      id,
+            id,
             state:   State::Off,
             outputs: output_ids.clone(),
             inputs:  Vec::new()
@@ -133,6 +160,7 @@ impl FlipFlop {
 
 /* ------------------------------- Conjunction ------------------------------ */
 
+#[derive(Clone)]
 pub struct Conjunction {
     id:      usize,
     memory:  HashMap<usize, PulseValue>,
@@ -162,12 +190,28 @@ impl Module for Conjunction {
 
     fn get_outputs(&self) -> &Vec<usize> { &self.outputs }
     fn get_inputs(&self) -> &Vec<usize> { &self.inputs }
+
+    // The last pulse value received from each input, in the order of the inputs.
+
+    fn get_state(&self) -> Vec<bool> {
+        self.inputs.iter().map(|input_id| self.memory[input_id] == PulseValue::High).collect()
+    }
+
+    fn set_state(&mut self, state: &[bool]) {
+        for (input_id, high) in self.inputs.iter().zip(state) {
+            self.memory.insert(*input_id, if *high { PulseValue::High } else { PulseValue::Low });
+        }
+    }
+
+    fn get_kind(&self) -> ModuleKind { ModuleKind::Conjunction }
+
+    fn clone_box(&self) -> Box<dyn Module> { Box::new(self.clone()) }
 }
 
 impl Conjunction {
-    pub fn new(id: usize, output_ids: Vec<usize>) -> Box<dyn Module> {
+    pub fn boxed(id: usize, output_ids: Vec<usize>) -> Box<dyn Module> {
         Box::new(Conjunction {
-            id:      id,
+            id,
             memory:  HashMap::new(),
             outputs: output_ids.clone(),
             inputs:  Vec::new()
@@ -188,8 +232,8 @@ pub fn assign_ids(lines: &Vec<String>) -> HashMap<String, usize> {
         for field in fields {
             let key: String = String::from(field);
 
-            if !map.contains_key(&key) {
-                map.insert(key, counter);
+            if let Entry::Vacant(e) = map.entry(key) {
+                e.insert(counter);
                 counter += 1;
             }
         }
@@ -198,39 +242,50 @@ pub fn assign_ids(lines: &Vec<String>) -> HashMap<String, usize> {
     map
 }
 
-pub fn parse(lines: &Vec<String>, id_map: HashMap<String, usize>) -> HashMap<usize, Box<dyn Module>> {
+pub fn parse_modules(lines: &Vec<String>, id_map: &HashMap<String, usize>) -> ParseResult<HashMap<usize, Box<dyn Module>>> {
     let mut modules: HashMap<usize, Box<dyn Module>> = HashMap::new();
 
-    for line in lines {
-        let (input, output) = line.split_once(" -> ").unwrap();
+    let find_id = |line: &Line, name: &str| match id_map.get(name) {
+        Some(id) => Ok(*id),
+        None => Err(line.error(name, "an alphabetic module name"))
+    };
 
-        let output_ids: Vec<usize> = output.split(", ").map(|f| *id_map.get(f).unwrap()).collect();
+    for line in parse::lines(lines) {
+        let (input, output) = line.split_once(line.text, " -> ")?;
 
-        if input.starts_with('%') {
-            let input_id: usize = *id_map.get(&input[1 ..]).unwrap();
-            let module: Box<dyn Module> = FlipFlop::new(input_id, output_ids);
+        let output_ids: Vec<usize> = output.split(", ").map(|f| find_id(&line, f)).collect::<ParseResult<Vec<usize>>>()?;
+
+        if let Some(name) = input.strip_prefix('%') {
+            let input_id: usize = find_id(&line, name)?;
+            let module: Box<dyn Module> = FlipFlop::boxed(input_id, output_ids);
             modules.insert(input_id, module);
-        } else if input.starts_with('&') {
-            let input_id: usize = *id_map.get(&input[1 ..]).unwrap();
-            let module: Box<dyn Module> = Conjunction::new(input_id, output_ids);
+        } else if let Some(name) = input.strip_prefix('&') {
+            let input_id: usize = find_id(&line, name)?;
+            let module: Box<dyn Module> = Conjunction::boxed(input_id, output_ids);
             modules.insert(input_id, module);
         } else if input == "broadcaster" {
-            let input_id: usize = *id_map.get(input).unwrap();
-            let module: Box<dyn Module> = Broadcaster::new(input_id, output_ids);
+            let input_id: usize = find_id(&line, input)?;
+            let module: Box<dyn Module> = Broadcaster::boxed(input_id, output_ids);
             modules.insert(input_id, module);
         } else {
-            panic!();
+            return Err(line.error(input, "'broadcaster', or a module name prefixed by '%' or '&'"));
         }
     }
 
-    modules
+    Ok(modules)
 }
 
+// Registers the inputs of every module in the order of their IDs, i.e. in the
+// order in which they first appear in the input, so that the state of a
+// Conjunction module always lists its inputs in the same order.
+
 pub fn register_inputs(modules: &mut HashMap<usize, Box<dyn Module>>) {
-    let outputs: Vec<(usize, Vec<usize>)> = modules.iter().map(|(k, v)| {
+    let mut outputs: Vec<(usize, Vec<usize>)> = modules.iter().map(|(k, v)| {
         (*k, v.get_outputs().clone())
     }).collect();
 
+    outputs.sort_by_key(|entry| entry.0);
+
     for entry in outputs {
         for target_id in entry.1 {
             if let Some(target_module) = modules.get_mut(&target_id) {
@@ -239,3 +294,52 @@ pub fn register_inputs(modules: &mut HashMap<usize, Box<dyn Module>>) {
         }
     }
 }
+
+// Pushes the button once, and returns all pulses sent as a result in the order
+// in which they were processed, starting with the pulse from the button itself.
+
+pub fn push_button(modules: &mut HashMap<usize, Box<dyn Module>>, broadcaster_id: usize) -> Vec<Pulse> {
+    let mut pulses: Vec<Pulse> = vec!(Pulse::initial(broadcaster_id));
+    let mut index: usize = 0;
+
+    while index < pulses.len() {
+        let pulse: &Pulse = &pulses[index];
+
+        if let Some(module) = modules.get_mut(&pulse.to) {
+            let new_pulses: Vec<Pulse> = module.process(pulse);
+            new_pulses.into_iter().for_each(|p| pulses.push(p));
+        }
+
+        index += 1;
+    }
+
+    pulses
+}
+
+/* --------------------------------- Network -------------------------------- */
+
+// The parsed module network. Since the modules are stateful, every solve
+// works on its own copy of the modules, obtained through `clone_modules()`.
+
+pub struct Network {
+    pub id_map: HashMap<String, usize>,
+    modules: HashMap<usize, Box<dyn Module>>
+}
+
+impl Network {
+    pub fn parse(lines: &Vec<String>) -> ParseResult<Network> {
+        let id_map: HashMap<String, usize> = assign_ids(lines);
+        let mut modules: HashMap<usize, Box<dyn Module>> = parse_modules(lines, &id_map)?;
+
+        if !id_map.contains_key("broadcaster") {
+            return Err(ParseError::input(lines, "a 'broadcaster' module", ""));
+        }
+
+        register_inputs(&mut modules);
+        Ok(Network { id_map, modules })
+    }
+
+    pub fn clone_modules(&self) -> HashMap<usize, Box<dyn Module>> {
+        self.modules.iter().map(|(id, module)| (*id, module.clone_box())).collect()
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use crate::solutions::day20::common::*;
+
+// A standalone simulator for networks of pulse modules, used by the `pulses`
+// command of the runner to inspect what a network actually does, rather than
+// only computing the puzzle answers. It presses the button one press at a time,
+// and can describe every pulse sent during a press (in the same format as the
+// puzzle description), the state of every module, and the network as a whole
+// in the DOT language of Graphviz.
+//
+// The state of a module is written as a bit vector: a single bit for each
+// FlipFlop module (1 if it is on), and one bit for every input of a Conjunction
+// module (1 if the last pulse from that input was high), in the order in which
+// the inputs first appear in the input file. The state of the whole network is
+// the concatenation of the states of all modules, sorted by name.
+
+/* -------------------------------- Simulator ------------------------------- */
+
+pub struct Simulator {
+    modules: HashMap<usize, Box<dyn Module>>,
+    names: HashMap<usize, String>,
+    order: Vec<usize>,
+    broadcaster_id: usize,
+    pub presses: usize,
+    pub low_pulses: usize,
+    pub high_pulses: usize
+}
+
+impl Simulator {
+    pub fn new(network: &Network) -> Simulator {
+        let modules: HashMap<usize, Box<dyn Module>> = network.clone_modules();
+        let names: HashMap<usize, String> = network.id_map.iter().map(|(name, id)| (*id, name.clone())).collect();
+
+        let mut order: Vec<usize> = modules.keys().copied().collect();
+        order.sort_by_key(|id| &names[id]);
+
+        Simulator {
+            modules,
+            names,
+            order,
+            broadcaster_id: network.id_map["broadcaster"],
+            presses: 0,
+            low_pulses: 0,
+            high_pulses: 0
+        }
+    }
+
+    // Presses the button once, and returns all pulses sent as a result.
+
+    pub fn press(&mut self) -> Vec<Pulse> {
+        let pulses: Vec<Pulse> = push_button(&mut self.modules, self.broadcaster_id);
+        let high_pulses: usize = pulses.iter().filter(|pulse| pulse.value == PulseValue::High).count();
+
+        self.presses += 1;
+        self.high_pulses += high_pulses;
+        self.low_pulses += pulses.len() - high_pulses;
+        pulses
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        match self.names.get(&id) {
+            Some(name) => name,
+            None => "button"
+        }
+    }
+
+    pub fn format_pulse(&self, pulse: &Pulse) -> String {
+        let value: &str = match pulse.value {
+            PulseValue::Low  => "low",
+            PulseValue::High => "high"
+        };
+
+        format!("{} -{value}-> {}", self.name(pulse.from), self.name(pulse.to))
+    }
+
+    // The state of the whole network as a single bit vector.
+
+    pub fn state_bits(&self) -> String {
+        self.order.iter().map(|id| format_bits(&self.modules[id].get_state())).collect()
+    }
+
+    // The state of every module with a state, one line per module.
+
+    pub fn module_states(&self) -> Vec<String> {
+        let stateful: Vec<(String, Vec<bool>)> = self.order.iter().map(|id| {
+            let module: &dyn Module = self.modules[id].as_ref();
+            (format!("{}{}", prefix(module.get_kind()), self.names[id]), module.get_state())
+        }).filter(|(_, state)| !state.is_empty()).collect();
+
+        let width: usize = stateful.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        stateful.iter().map(|(label, state)| format!("{label:<width$}  {}", format_bits(state))).collect()
+    }
+}
+
+/* ------------------------------- Main logic ------------------------------- */
+
+fn format_bits(bits: &[bool]) -> String {
+    bits.iter().map(|bit| if *bit { '1' } else { '0' }).collect()
+}
+
+fn prefix(kind: ModuleKind) -> &'static str {
+    match kind {
+        ModuleKind::Broadcaster => "",
+        ModuleKind::FlipFlop    => "%",
+        ModuleKind::Conjunction => "&"
+    }
+}
+
+// Describes the network in the DOT language. Modules are colored by their type;
+// names that only appear as an output (such as "rx") are drawn as plain text.
+
+pub fn to_dot(network: &Network) -> String {
+    let modules: HashMap<usize, Box<dyn Module>> = network.clone_modules();
+    let mut names: Vec<(&String, &usize)> = network.id_map.iter().collect();
+    names.sort();
+
+    let name_of: HashMap<usize, &String> = names.iter().map(|(name, id)| (**id, *name)).collect();
+    let mut lines: Vec<String> = vec![String::from("digraph network {"), String::from("    rankdir=LR;")];
+
+    for (name, id) in &names {
+        let attributes: &str = match modules.get(id).map(|module| module.get_kind()) {
+            Some(ModuleKind::Broadcaster) => "shape=doubleoctagon, style=filled, fillcolor=palegreen",
+            Some(ModuleKind::FlipFlop)    => "shape=box, style=filled, fillcolor=lightblue",
+            Some(ModuleKind::Conjunction) => "shape=ellipse, style=filled, fillcolor=orange",
+            None                          => "shape=plaintext"
+        };
+
+        let label: String = match modules.get(id) {
+            Some(module) => format!("{}{name}", prefix(module.get_kind())),
+            None => name.to_string()
+        };
+
+        lines.push(format!("    \"{name}\" [label=\"{label}\", {attributes}];"));
+    }
+
+    for (name, id) in &names {
+        if let Some(module) = modules.get(id) {
+            for output_id in module.get_outputs() {
+                lines.push(format!("    \"{name}\" -> \"{}\";", name_of[output_id]));
+            }
+        }
+    }
+
+    lines.push(String::from("}"));
+    lines.join("\n")
+}
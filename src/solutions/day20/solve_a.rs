@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use crate::solutions::Solution;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day20::common::*;
 
 // As usual, for the A part I went with a straightforward implementation of
@@ -18,42 +19,23 @@ use crate::solutions::day20::common::*;
 // the number of high and low pulses generated during each press, and at
 // the end we multiply the sums of these two sets of values.
 
-fn push_button(modules: &mut HashMap<usize, Box<dyn Module>>, broadcaster_id: usize) -> (i64, i64) {
-    let mut pulses: Vec<Pulse> = vec!(Pulse::initial(broadcaster_id));
-    let mut high_pulses: usize = 0;
-    let mut low_pulses: usize = 1;
-    let mut index: usize = 0;
+fn count_pulses(modules: &mut HashMap<usize, Box<dyn Module>>, broadcaster_id: usize) -> (i64, i64) {
+    let pulses: Vec<Pulse> = push_button(modules, broadcaster_id);
+    let high_pulses: usize = pulses.iter().filter(|pulse| pulse.value == PulseValue::High).count();
 
-    while index < pulses.len() {
-        let pulse: &Pulse = &pulses[index];
-
-        if let Some(module) = modules.get_mut(&pulse.to) {
-            let new_pulses: Vec<Pulse> = module.process(pulse);
-            
-            for new_pulse in new_pulses {
-                match new_pulse.value {
-                    PulseValue::High => { high_pulses += 1; }
-                    PulseValue::Low  => { low_pulses  += 1; }
-                }
-
-                pulses.push(new_pulse);
-            }
-        }
-
-        index += 1;
-    }
+    ((pulses.len() - high_pulses) as i64, high_pulses as i64)
+}
 
-    (low_pulses as i64, high_pulses as i64)
+pub fn parse(lines: &Vec<String>) -> ParseResult<Network> {
+    Network::parse(lines)
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let id_map: HashMap<String, usize> = assign_ids(lines);
-    let broadcaster_id: usize = *id_map.get("broadcaster").unwrap();
-    let mut modules: HashMap<usize, Box<dyn Module>> = parse(lines, id_map);
-    register_inputs(&mut modules);
+pub fn solve(network: &Network) -> Solution {
+    let broadcaster_id: usize = *network.id_map.get("broadcaster").unwrap();
+    let mut modules: HashMap<usize, Box<dyn Module>> = network.clone_modules();
 
     let (low_pulses, high_pulses) = (0 .. 1000)
-        .map(|_| push_button(&mut modules, broadcaster_id) )
+        .map(|_| count_pulses(&mut modules, broadcaster_id))
         .fold((0, 0), |acc, v| (acc.0 + v.0, acc.1 + v.1));
 
     return Solution::Integer(low_pulses * high_pulses)
@@ -1,115 +1,357 @@
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
 
 use crate::solutions::Solution;
+use crate::solutions::cycle::{self, History};
+use crate::solutions::number::{self, Congruence, CrtError};
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day20::common::*;
 
-// I feel like I cheated on this one. The general gist is obvious; the button
-// press count we're looking for is going to be way too high for us to simulate
-// it all (trust me, I've tried), so instead we're going to once again look for
-// cycles. In particular, we want to find cycles in specific subsets of the net-
-// work of modules. We find appropraite subsets by working backwards from the
-// target "rx" module until we've found a group of more than one module leading
-// to our "rx" output. Multiplying the cycle size - i.e., the number of button
-// presses after which a module starts to repeat itself - for each of these
-// subsets gives us the least common multiple, which will be our target count.
+// I originally felt like I cheated on this one. The general gist is obvious;
+// the button press count we're looking for is going to be way too high for us
+// to simulate it all (trust me, I've tried), so instead we're going to once
+// again look for cycles. My first version found the group of modules leading
+// to "rx", and returned the least common multiple of the cycle sizes of the
+// parts of the network feeding them. That relied on a whole list of things I
+// had only confirmed by looking at the input: that each part sends exactly one
+// high pulse per cycle, at the very end; that "rx" is preceded by a Conjunction
+// module; that the cycles are short; that their lengths are coprime; and that
+// they start at zero button presses. This version no longer assumes any of
+// them, and instead checks what it needs while running.
 //
-// There are several issues with this approach however:
-// - It assumes that a cycle contains exactly one high pulse at the very end
-//   of the cycle, while in reality a cycle might have multiple high pulses.
-// - It assumes that we're looking for a high pulse in order to determine the
-//   cycle boundary. This is based on me visually confirming that there's a
-//   Conjunction module on the path to "rx", and that the output of this module
-//   is inverted through a second Conjunction module. If the network topology
-//   had been even slight different (e.g. if "rx" had been preceded by a
-//   flip-flop instead of a conjunction), none of this would have worked.
-// - It assumes that the cycles of these subsets are small enough that we
-//   can find them in a reasonable amount of time; for all we know, the
-//   length of these subset cycles could still have been in the trillions.
-// - It assumes that the cycle lengths are all coprimes, meaning that their
-//   least common multiple can be found by simple multiplication.
-// - It assumes that the cycle starts at zero button presses.
+// The one structure we do need is the following. "rx" has to be fed by a single
+// Conjunction module (the "hub"), which only sends a low pulse once all of its
+// inputs have most recently sent it a high pulse. For each input of the hub, we
+// find all modules from which pulses can reach it; this is the "part" of the
+// network behind that input. If no two parts share a module (other than the
+// broadcaster, which has no state), and no part depends on the hub itself, the
+// parts evolve independently of each other: pulses in one part never reach
+// another part, and leaving out the other parts doesn't change the order in
+// which the pulses in this part are processed. If any of this doesn't hold,
+// the network cannot be split up, and we give up with an explanation.
 //
-// All of these assumptions hold for the given input, and so it spits out
-// the correct answer in a fairly short amount of time. It still doesn't
-// sit right with me however; as a personal rule, I want the solutions to
-// these problems to be as generic as possible, and avoid relying on infor-
-// mation or assumptions not explicitly stated in the description. This is
-// clearly not the case here, which is why I consider it cheating.
+// For every part, we simulate button presses on just that part (plus the hub)
+// using the shared cycle detector, which gives us the offset and period after
+// which the states of the part repeat. If that takes more than a million or so
+// presses, we give up. While doing so, we record for which presses the hub's
+// memory for this part's input is high at some point during the press. Only
+// presses for which this holds for every part can possibly send a low pulse to
+// "rx". Every such press corresponds to a congruence for each part (the press
+// is one of the recorded ones, modulo the period of the part), so we can find
+// all of them by combining one congruence from each part with the CRT; this
+// works whether or not the periods are coprime. Presses before all parts have
+// entered their cycles are simply checked one by one.
 //
-// On the other hand, rewriting this to produce the correct solution for
-// any hypothetical network in a reasonable amount of time would have been
-// a royal pain in the ass. Most of the assumptions outlined above can be
-// verified, but the third one especially - the one about the subset cycles
-// being reasonably short - is much harder. If this assumption doesn't hold,
-// we'd still be looking at a runtime in the order of hours. It should be
-// possible to work around that issue (something something network graph
-// partitioning), but since we've already got the right answer by making
-// a bunch of shaky but true assumptions, why should we?
-
-struct ModuleCycle {
-    id: usize,
-    count: i64
+// That is still only a necessary condition, since the hub's memory for the
+// different inputs also needs to be high at the same time. To check this, we
+// go through the candidate presses in order, and for each of them, rebuild the
+// state of the whole network right before the press from the states of the
+// parts at that point in their cycles. We then simulate that single press, and
+// check that "rx" actually receives a low pulse; the first candidate for which
+// it does is our answer.
+
+const MAX_PRESSES: usize = 1 << 20;
+
+const MAX_CANDIDATES: usize = 1 << 12;
+
+type Modules = HashMap<usize, Box<dyn Module>>;
+
+/* ---------------------------------- Part ---------------------------------- */
+
+// One of the independent parts of the network, feeding the input of the hub
+// at `index`. The state of a part consists of the states of its modules, in
+// the order of `ids`, followed by the hub's memory for the part's input.
+
+struct Part {
+    index: usize,
+    ids: Vec<usize>,
+    history: History<Vec<bool>>,
+    highs: Vec<bool>
 }
 
-fn find_target_modules(modules: &HashMap<usize, Box<dyn Module>>, rx_id: usize) -> Vec<usize> {
-    let mut ids: Vec<usize> = modules.iter()
-        .filter(|(_, module)| module.get_outputs().contains(&rx_id))
-        .map(|(id, _)| *id).collect();
+impl Part {
+    fn simulate(network: &Network, broadcaster_id: usize, hub_id: usize, index: usize, ids: Vec<usize>) -> Option<Part> {
+        let mut modules: Modules = network.clone_modules();
+        modules.retain(|id, _| *id == hub_id || *id == broadcaster_id || ids.contains(id));
+
+        let input_id: usize = modules[&hub_id].get_inputs()[index];
+        let get_part_state = |modules: &Modules| -> Vec<bool> {
+            let mut state: Vec<bool> = get_state(modules, &ids);
+            state.push(modules[&hub_id].get_state()[index]);
+            state
+        };
+
+        let initial: Vec<bool> = get_part_state(&modules);
+        let mut highs: Vec<bool> = Vec::new();
+
+        let history: History<Vec<bool>> = cycle::detect_within(initial, MAX_PRESSES, |state| {
+            let pulses: Vec<Pulse> = push_button(&mut modules, broadcaster_id);
+            let sends_high: bool = pulses.iter()
+                .any(|pulse| pulse.from == input_id && pulse.to == hub_id && pulse.value == PulseValue::High);
+
+            highs.push(*state.last().unwrap() || sends_high);
+            get_part_state(&modules)
+        })?;
 
-    while ids.len() == 1 {
-        let module: &Box<dyn Module> = modules.get(&ids[0]).unwrap();
-        ids = module.get_inputs().clone();
+        Some(Part { index, ids, history, highs })
     }
 
-    ids
+    // Whether the hub's memory for this part is high at some point during the
+    // press that follows the given number of presses.
+
+    fn is_high(&self, presses: usize) -> bool {
+        self.highs[self.history.cycle.reduce(presses)]
+    }
+
+    // The numbers of presses (at least the offset of the cycle) after which the
+    // next press makes the hub's memory for this part high.
+
+    fn congruences(&self) -> Vec<Congruence> {
+        let cycle: cycle::Cycle = self.history.cycle;
+
+        (cycle.offset .. cycle.offset + cycle.period)
+            .filter(|presses| self.highs[*presses])
+            .map(|presses| Congruence::new(presses as i128, cycle.period as i128))
+            .collect()
+    }
 }
 
-fn push_button(modules: &mut HashMap<usize, Box<dyn Module>>,
-        target_cycles: &mut Vec<ModuleCycle>,
-        broadcaster_id: usize, count: i64) {
-    let mut pulses: Vec<Pulse> = vec!(Pulse::initial(broadcaster_id));
+/* ------------------------------- Main logic ------------------------------- */
+
+fn get_state(modules: &Modules, ids: &Vec<usize>) -> Vec<bool> {
+    ids.iter().flat_map(|id| modules[id].get_state()).collect()
+}
+
+fn set_state(modules: &mut Modules, ids: &Vec<usize>, state: &[bool]) {
     let mut index: usize = 0;
 
-    while index < pulses.len() {
-        let pulse: &Pulse = &pulses[index];
+    for id in ids {
+        let module: &mut Box<dyn Module> = modules.get_mut(id).unwrap();
+        let length: usize = module.get_state().len();
+        module.set_state(&state[index .. index + length]);
+        index += length;
+    }
+}
+
+// Finds all modules from which pulses can reach the target module, including
+// the target module itself. Pulses sent to other modules cannot influence the
+// states of these modules, so we can leave all other modules out.
+
+fn find_subnetwork(modules: &Modules, target_id: usize) -> Vec<usize> {
+    let mut ids: Vec<usize> = vec!(target_id);
+    let mut index: usize = 0;
 
-        if let Some(module) = modules.get_mut(&pulse.to) {
-            let new_pulses: Vec<Pulse> = module.process(pulse);
-            new_pulses.into_iter().for_each(|p| pulses.push(p));
+    while index < ids.len() {
+        if let Some(module) = modules.get(&ids[index]) {
+            for input_id in module.get_inputs() {
+                if !ids.contains(input_id) {
+                    ids.push(*input_id);
+                }
+            }
         }
 
         index += 1;
     }
 
-    target_cycles.iter_mut().filter(|cycle| cycle.count == -1).for_each(|cycle| {
-        if pulses.iter().any(|pulse| pulse.from == cycle.id && pulse.value == PulseValue::High) {
-            cycle.count = count;
+    ids.sort();
+    ids
+}
+
+fn find_hub(modules: &Modules, names: &HashMap<usize, &str>, rx_id: usize) -> Result<usize, String> {
+    let feeders: Vec<usize> = modules.iter()
+        .filter(|(_, module)| module.get_outputs().contains(&rx_id))
+        .map(|(id, _)| *id).collect();
+
+    match feeders[..] {
+        [id] if modules[&id].get_kind() == ModuleKind::Conjunction => Ok(id),
+        [id] => Err(format!("\"rx\" is fed by \"{}\", which is not a conjunction module", names[&id])),
+        []   => Err(String::from("no module sends pulses to \"rx\"")),
+        _    => Err(String::from("\"rx\" is fed by more than one module"))
+    }
+}
+
+// Splits the modules feeding the hub into parts, one for each input of the hub.
+// The broadcaster is part of every part, but is left out of the lists, as it
+// has no state.
+
+fn find_parts(modules: &Modules, names: &HashMap<usize, &str>, hub_id: usize, broadcaster_id: usize) -> Result<Vec<Vec<usize>>, String> {
+    let mut parts: Vec<Vec<usize>> = Vec::new();
+
+    for input_id in modules[&hub_id].get_inputs() {
+        let mut ids: Vec<usize> = find_subnetwork(modules, *input_id);
+        ids.retain(|id| *id != broadcaster_id && modules.contains_key(id));
+
+        if ids.contains(&hub_id) {
+            return Err(format!("\"{}\" depends on the output of \"{}\"", names[input_id], names[&hub_id]));
         }
-    });
+
+        if let Some(id) = ids.iter().find(|id| parts.iter().any(|part| part.contains(id))) {
+            return Err(format!("\"{}\" is shared by several inputs of \"{}\"", names[id], names[&hub_id]));
+        }
+
+        parts.push(ids);
+    }
+
+    Ok(parts)
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let id_map: HashMap<String, usize> = assign_ids(lines);
-    let broadcaster_id: usize = *id_map.get("broadcaster").unwrap();
-    let rx_id: usize = *id_map.get("rx").unwrap();
+// Combines one congruence of every part in every possible way, giving all
+// numbers of presses (after the offsets) that are candidates for the answer.
 
-    let mut modules: HashMap<usize, Box<dyn Module>> = parse(lines, id_map);
-    register_inputs(&mut modules);
+fn combine_parts(parts: &Vec<Part>) -> Result<Vec<Congruence>, String> {
+    let groups: Vec<Vec<Congruence>> = parts.iter().map(|part| part.congruences()).collect();
 
-    let target_modules: Vec<usize> = find_target_modules(&modules, rx_id);
-    let mut target_cycles: Vec<ModuleCycle> = target_modules.into_iter()
-        .map(|id| ModuleCycle { id: id, count: -1 }).collect();
+    match number::combine_all(&groups) {
+        Ok(combined) => Ok(combined),
+        Err(CrtError::TooManyCombinations) => Err(String::from("the parts of the network have too many combinations of high pulses")),
+        Err(_) => Err(String::from("the cycles of the parts do not fit in 128 bits"))
+    }
+}
+
+// Rebuilds the state of the whole network after the given number of presses,
+// and checks whether the next press sends a low pulse to "rx".
+
+fn sends_low_pulse(network: &Network, parts: &Vec<Part>, hub_id: usize, broadcaster_id: usize, rx_id: usize, presses: usize) -> bool {
+    let mut modules: Modules = network.clone_modules();
+    let mut hub_state: Vec<bool> = modules[&hub_id].get_state();
+
+    for part in parts {
+        let state: &Vec<bool> = part.history.state_at(presses);
+        set_state(&mut modules, &part.ids, &state[.. state.len() - 1]);
+        hub_state[part.index] = *state.last().unwrap();
+    }
+
+    modules.get_mut(&hub_id).unwrap().set_state(&hub_state);
+
+    push_button(&mut modules, broadcaster_id).iter()
+        .any(|pulse| pulse.to == rx_id && pulse.value == PulseValue::Low)
+}
+
+fn find_press_count(network: &Network) -> Result<usize, String> {
+    let (Some(broadcaster_id), Some(rx_id)) = (network.id_map.get("broadcaster"), network.id_map.get("rx")) else {
+        return Err(String::from("the network does not contain an \"rx\" module"));
+    };
+
+    let (broadcaster_id, rx_id): (usize, usize) = (*broadcaster_id, *rx_id);
+    let names: HashMap<usize, &str> = network.id_map.iter().map(|(name, id)| (*id, name.as_str())).collect();
+    let modules: Modules = network.clone_modules();
+
+    let hub_id: usize = find_hub(&modules, &names, rx_id)?;
+    let mut parts: Vec<Part> = Vec::new();
 
-    let mut button_presses: i64 = 0;
+    for (index, ids) in find_parts(&modules, &names, hub_id, broadcaster_id)?.into_iter().enumerate() {
+        let input_id: usize = modules[&hub_id].get_inputs()[index];
 
-    while target_cycles.iter().any(|cycle| cycle.count == -1) {
-        button_presses += 1;
-        
-        push_button(&mut modules, &mut target_cycles,
-            broadcaster_id, button_presses);
+        match Part::simulate(network, broadcaster_id, hub_id, index, ids) {
+            Some(part) => parts.push(part),
+            None => return Err(format!("the part feeding \"{}\" does not repeat within {MAX_PRESSES} presses", names[&input_id]))
+        }
     }
 
-    let result: i64 = target_cycles.into_iter().map(|cycle| cycle.count).product();
+    // Presses before every part has entered its cycle are checked one by one;
+    // after that, we go through the candidates of all combinations in order.
+
+    let start: usize = parts.iter().map(|part| part.history.cycle.offset).max().unwrap_or(0);
+    let early: Vec<usize> = (0 .. start).filter(|presses| parts.iter().all(|part| part.is_high(*presses))).collect();
+
+    let mut queue: BinaryHeap<Reverse<(i128, i128)>> = combine_parts(&parts)?.into_iter().map(|congruence| {
+        let skipped: i128 = (start as i128 - congruence.residue).max(0);
+        let first: i128 = congruence.residue + (skipped + congruence.modulus - 1) / congruence.modulus * congruence.modulus;
+        Reverse((first, congruence.modulus))
+    }).collect();
+
+    for attempt in 0 .. MAX_CANDIDATES {
+        let presses: usize = match early.get(attempt) {
+            Some(presses) => *presses,
+            None => {
+                let Some(Reverse((presses, modulus))) = queue.pop() else {
+                    return Err(String::from("\"rx\" never receives a low pulse"));
+                };
+
+                queue.push(Reverse((presses + modulus, modulus)));
+                usize::try_from(presses).map_err(|_| String::from("the button press count does not fit in 64 bits"))?
+            }
+        };
+
+        if sends_low_pulse(network, &parts, hub_id, broadcaster_id, rx_id, presses) {
+            return Ok(presses + 1);
+        }
+    }
+
+    Err(format!("none of the first {MAX_CANDIDATES} candidate button press counts sends a low pulse to \"rx\""))
+}
+
+pub fn parse(lines: &Vec<String>) -> ParseResult<Network> {
+    Network::parse(lines)
+}
+
+pub fn solve(network: &Network) -> Solution {
+    let result: i64 = match find_press_count(network).map(i64::try_from) {
+        Ok(Ok(value)) => value,
+        Ok(Err(_)) => return Solution::unsolved("button press count does not fit in 64 bits"),
+        Err(reason) => return Solution::unsolved(&reason)
+    };
 
     return Solution::Integer(result)
 }
+
+/* ---------------------------------- Tests --------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(text: &str) -> Network {
+        let lines: Vec<String> = text.lines().map(String::from).collect();
+        Network::parse(&lines).unwrap()
+    }
+
+    // Presses the button until "rx" receives a low pulse.
+
+    fn brute_force(network: &Network, max_presses: usize) -> Option<usize> {
+        let mut modules: Modules = network.clone_modules();
+        let (broadcaster_id, rx_id): (usize, usize) = (network.id_map["broadcaster"], network.id_map["rx"]);
+
+        (1 ..= max_presses).find(|_| {
+            push_button(&mut modules, broadcaster_id).iter()
+                .any(|pulse| pulse.to == rx_id && pulse.value == PulseValue::Low)
+        })
+    }
+
+    // Three parts with periods 2, 4 and 8: a flip-flop behind an inverter (which
+    // sends a high pulse on every even press), and counters of two and three
+    // flip-flops. The first press during which the hub remembers a high pulse
+    // from all three at once is the sixth.
+
+    #[test]
+    fn finds_press_count_of_counters() {
+        let network: Network = network(include_str!("input/sample_rx.txt"));
+        assert_eq!(find_press_count(&network), Ok(6));
+        assert_eq!(brute_force(&network, 100), Some(6));
+    }
+
+    #[test]
+    fn reports_missing_hub() {
+        let cases: [(&str, &str); 3] = [
+            ("broadcaster -> a\n%a -> a", "the network does not contain an \"rx\" module"),
+            ("broadcaster -> a\n%a -> rx", "\"rx\" is fed by \"a\", which is not a conjunction module"),
+            ("broadcaster -> a, b\n%a -> rx\n%b -> rx", "\"rx\" is fed by more than one module")
+        ];
+
+        for (text, reason) in cases {
+            assert_eq!(find_press_count(&network(text)), Err(String::from(reason)));
+        }
+    }
+
+    #[test]
+    fn reports_shared_module() {
+        let network: Network = network("broadcaster -> s\n%s -> a, b\n%a -> hub\n%b -> hub\n&hub -> rx");
+        assert_eq!(find_press_count(&network), Err(String::from("\"s\" is shared by several inputs of \"hub\"")));
+    }
+
+    #[test]
+    fn reports_part_depending_on_hub() {
+        let network: Network = network("broadcaster -> a\n%a -> hub\n&hub -> rx, a");
+        assert_eq!(find_press_count(&network), Err(String::from("\"a\" depends on the output of \"hub\"")));
+    }
+}
@@ -1,5 +1,8 @@
 use std::collections::HashSet;
 
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::parse::{ParseError, ParseResult};
+
 /* ---------------------------------- Tile ---------------------------------- */
 
 pub enum Tile {
@@ -10,84 +13,51 @@ pub enum Tile {
 
 /* ---------------------------------- Grid ---------------------------------- */
 
-pub struct Grid {
-    pub tiles: Vec<Tile>,
-    pub rows: isize,
-    pub cols: isize
-}
-
-impl Grid {
-    pub fn parse(lines: &Vec<String>) -> Grid {
-        let tiles: Vec<Tile> = lines.concat().chars().map(|c| {
-            match c {
-                '.' => Tile::Empty,
-                'S' => Tile::Start,
-                '#' => Tile::Rock,
-                _ => panic!()
-            }
-        }).collect();
-
-        Grid {
-            tiles: tiles,
-            rows:  lines.len() as isize,
-            cols:  lines[0].len() as isize
-        }
-    }
-
-    pub fn get(&self, pos: &(isize, isize)) -> &Tile {
-        let index: usize = self.to_index(pos);
-        &self.tiles[index]
-    }
-
-    pub fn contains(&self, pos: &(isize, isize)) -> bool {
-        pos.0 >= 0 && pos.0 < self.rows && pos.1 >= 0 && pos.1 < self.cols
-    }
-
-    pub fn is_empty(&self, pos: &(isize, isize)) -> bool {
-        match self.get(pos) {
+impl Tile {
+    pub fn is_empty(&self) -> bool {
+        match self {
             Tile::Empty => true,
             Tile::Start => true,
             Tile::Rock  => false
         }
     }
+}
 
-    fn to_index(&self, pos: &(isize, isize)) -> usize {
-        (pos.0 * self.cols + pos.1) as usize
-    }
+pub fn parse_grid(lines: &Vec<String>) -> ParseResult<Grid<Tile>> {
+    let grid: Grid<Tile> = Grid::parse(lines, 0, "'.', 'S' or '#'", |c| {
+        match c {
+            '.' => Some(Tile::Empty),
+            'S' => Some(Tile::Start),
+            '#' => Some(Tile::Rock),
+            _ => None
+        }
+    })?;
 
-    fn to_coordinates(&self, index: isize) -> (isize, isize) {
-        (index / self.cols, index % self.cols)
+    if grid.find(|tile| matches!(tile, Tile::Start)).is_none() {
+        return Err(ParseError::input(lines, "a start tile 'S'", ""));
     }
 
-    pub fn find_start(&self) -> (isize, isize) {
-        let index: usize = self.tiles.iter().enumerate().find(|(_, tile)| {
-            match tile {
-                Tile::Start => true,
-                _ => false
-            }
-        }).map(|(i, _)| i).unwrap();
-    
-        self.to_coordinates(index as isize)
-    }
+    Ok(grid)
 }
 
-/* ---------------------------------- Main ---------------------------------- */
-
-pub fn add_next(grid: &Grid, next: &mut HashSet<(isize, isize)>, pos: (isize, isize)) {
-    if grid.contains(&pos) && grid.is_empty(&pos) {
-        next.insert(pos);
-    }
+pub fn find_start(grid: &Grid<Tile>) -> Pos {
+    grid.find(|tile| matches!(tile, Tile::Start)).unwrap()
 }
 
-pub fn step(grid: &Grid, current: HashSet<(isize, isize)>) -> HashSet<(isize, isize)> {
-    let mut next: HashSet<(isize, isize)> = HashSet::new();
+/* ---------------------------------- Main ---------------------------------- */
+
+pub fn step(grid: &Grid<Tile>, current: HashSet<Pos>) -> HashSet<Pos> {
+    let mut next: HashSet<Pos> = HashSet::new();
 
     for pos in current {
-        add_next(grid, &mut next, (pos.0 + 1, pos.1));
-        add_next(grid, &mut next, (pos.0 - 1, pos.1));
-        add_next(grid, &mut next, (pos.0, pos.1 + 1));
-        add_next(grid, &mut next, (pos.0, pos.1 - 1));
+        next.extend(grid.neighbors(pos).filter(|neighbor| grid[*neighbor].is_empty()));
     }
 
     next
 }
+
+// Maps a position in the infinite garden to the corresponding grid position.
+
+pub fn wrap(grid: &Grid<Tile>, pos: Pos) -> Pos {
+    Pos::new(pos.row.rem_euclid(grid.rows), pos.col.rem_euclid(grid.cols))
+}
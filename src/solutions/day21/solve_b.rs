@@ -1,27 +1,375 @@
+use std::collections::{HashMap, VecDeque};
+
 use crate::solutions::Solution;
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::parse::ParseResult;
+use crate::solutions::day21::common::*;
 
-// Did not finish, and I genuinely think this is the worst question this year.
-// Obviously we won't perform the actual simulation, and the 'correct' approach
-// seems pretty clear: run the simulation from different corners and sides of
-// the field until you reach a steady state, then figure out how many copies
-// of the field are in this steady state after the target number of steps,
-// and figure out the number of active cells in the field copies that are
-// not yet in the steady state. This is all doable – if a huge pain in the
-// ass to implement – but it only works because the input has a couple of
+// I initially skipped this one, and I still think this is the worst question
+// this year. Obviously we won't perform the actual simulation. The 'correct'
+// approach that most people used only works because the input has a couple of
 // very specific properties: it has a border consisting of only open cells,
-// and the center row and column are also open, meaning the 'wave-front'
-// can always propagate in the cardinal directions unimpeded.
+// and the center row and column are also open, meaning the 'wave-front' can
+// always propagate in the cardinal directions unimpeded. In that case, the
+// number of reachable cells after `S + K * N` steps (with `N` the size of the
+// grid and `S` the number of steps from the start to the edge) is a quadratic
+// function of `K`, so it is enough to count the cells for K = 0, 1 and 2 and
+// extrapolate from there. We still use this as a fast path for such inputs.
 //
-// If the input did not have these properties (like in the sample, actually),
-// the question would have been even harder to solve, and I personally dis-
-// like questions where you need to tailor the algorithm to a specific input;
-// ideally, the solution should work on any input that matches the description,
-// and conversely, if the solution that the author has in mind depends on pro-
-// perties of the input, those properties should be mentioned in the text.
+// For other inputs (like the sample), we use a more general approach. A cell
+// can be reached after exactly `T` steps if its shortest distance to the start
+// is at most `T`, and has the same parity as `T` (since we can always step
+// back and forth). We compute the shortest distances to all cells in a window
+// of copies of the grid around the start copy using a breadth-first search.
+// Far enough from the start, the distances become "steady": moving one copy
+// further away increases the distance to each cell by exactly the size of the
+// grid. We check this on the outer ring of copies in the window, and double
+// the size of the window until it holds. Copies inside the window are counted
+// directly. Beyond the window, each copy on the edge of the window starts a
+// line of copies going outward, where the distances increase by the grid size
+// for every copy, and each corner copy starts a quadrant of copies, in which
+// the distances increase by the grid size in both directions; for both, we can
+// count the number of copies in which a cell is reachable with some algebra.
 //
-// Between my misgivings about the question and just how much of an error-
-// prone slog it would be to implement, I decided to simply skip this one.
+// Since it's easy to get these counts subtly wrong, we cross-check either
+// approach against a brute-force simulation, and refuse to answer if they
+// differ. The step counts we check are chosen so that they actually go through
+// the extrapolation: K = 2 and 3 for the quadratic formula (K = 3 is the first
+// one that is not one of the samples it is built from), and step counts that
+// reach several copies beyond the window for the general approach.
+
+const STEPS: usize = 26501365;
+
+const UNREACHABLE: usize = usize::MAX;
+
+const MIN_RADIUS: isize = 2;
+
+const MAX_WINDOW_CELLS: isize = 1 << 24;
+
+/* ---------------------------------- Counts -------------------------------- */
+
+fn is_reachable(distance: usize, steps: usize) -> bool {
+    distance <= steps && (steps - distance).is_multiple_of(2)
+}
+
+// The number of copies `K >= 0` in a line of copies in which a cell with
+// distance `D + K * period` is reachable.
+
+fn count_line(distance: usize, period: usize, steps: usize) -> u64 {
+    if distance > steps {
+        return 0;
+    }
+
+    let max_k: u64 = ((steps - distance) / period) as u64;
+    let parity: u64 = ((steps - distance) % 2) as u64;
+
+    if period.is_multiple_of(2) {
+        return if parity == 0 { max_k + 1 } else { 0 };
+    }
+
+    if parity == 0 { max_k / 2 + 1 } else { max_k.div_ceil(2) }
+}
+
+// The number of copies `(I, J)` with `I, J >= 0` in a quadrant of copies in
+// which a cell with distance `D + I * rows + J * cols` is reachable. For square
+// grids, there are `K + 1` copies with distance `D + K * size`, which gives us
+// a closed form; otherwise, we add up the lines of copies one by one.
+
+fn count_quadrant(distance: usize, rows: usize, cols: usize, steps: usize) -> u64 {
+    if distance > steps {
+        return 0;
+    }
+
+    if rows != cols {
+        return (0 ..= (steps - distance) / rows)
+            .map(|i| count_line(distance + i * rows, cols, steps)).sum();
+    }
+
+    let max_k: u64 = ((steps - distance) / rows) as u64;
+    let parity: u64 = ((steps - distance) % 2) as u64;
+
+    if rows.is_multiple_of(2) {
+        return if parity == 0 { (max_k + 1) * (max_k + 2) / 2 } else { 0 };
+    }
+
+    if max_k < parity {
+        return 0;
+    }
+
+    let t: u64 = (max_k - parity) / 2;
+    (t + 1) * (parity + 1) + t * (t + 1)
+}
+
+/* --------------------------------- Window --------------------------------- */
+
+// The shortest distances from the start to all cells in the copies of the
+// grid that are at most `radius` copies away from the start copy (in both
+// directions). Copies are identified by their offset from the start copy.
+
+struct Window {
+    distances: Grid<usize>,
+    radius: isize,
+    rows: isize,
+    cols: isize
+}
+
+impl Window {
+    fn compute(grid: &Grid<Tile>, start: Pos, radius: isize) -> Window {
+        let size: isize = 2 * radius + 1;
+        let mut distances: Grid<usize> = Grid::filled(size * grid.rows, size * grid.cols, UNREACHABLE);
+        let origin: Pos = Pos::new(radius * grid.rows + start.row, radius * grid.cols + start.col);
+        let mut queue: VecDeque<Pos> = VecDeque::from([origin]);
+        distances[origin] = 0;
+
+        while let Some(pos) = queue.pop_front() {
+            let distance: usize = distances[pos];
+
+            for neighbor in pos.neighbors() {
+                if !distances.contains(neighbor) || distances[neighbor] != UNREACHABLE {
+                    continue;
+                }
+
+                if grid[wrap(grid, neighbor)].is_empty() {
+                    distances[neighbor] = distance + 1;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        Window { distances, radius, rows: grid.rows, cols: grid.cols }
+    }
+
+    fn get(&self, copy: Pos, cell: Pos) -> usize {
+        let row: isize = (copy.row + self.radius) * self.rows + cell.row;
+        let col: isize = (copy.col + self.radius) * self.cols + cell.col;
+        self.distances[Pos::new(row, col)]
+    }
+
+    fn copies(&self) -> impl Iterator<Item = Pos> + '_ {
+        (-self.radius ..= self.radius).flat_map(move |row| {
+            (-self.radius ..= self.radius).map(move |col| Pos::new(row, col))
+        })
+    }
+
+    // Checks whether every distance in each copy on the outer ring is exactly
+    // one grid size more than in the adjacent copy one step closer to the start.
+
+    fn is_steady(&self, grid: &Grid<Tile>) -> bool {
+        let r: isize = self.radius;
+
+        let is_one_copy_further = |outer: Pos, inner: Pos, size: isize| grid.positions().all(|cell| {
+            let (outer, inner) = (self.get(outer, cell), self.get(inner, cell));
+            (outer == UNREACHABLE && inner == UNREACHABLE) || (inner != UNREACHABLE && outer == inner + size as usize)
+        });
+
+        (-r ..= r).all(|i| {
+            is_one_copy_further(Pos::new( r, i), Pos::new( r - 1, i), self.rows) &&
+            is_one_copy_further(Pos::new(-r, i), Pos::new(-r + 1, i), self.rows) &&
+            is_one_copy_further(Pos::new(i,  r), Pos::new(i,  r - 1), self.cols) &&
+            is_one_copy_further(Pos::new(i, -r), Pos::new(i, -r + 1), self.cols)
+        })
+    }
+
+    // Counts the reachable cells inside the window only.
+
+    fn count_inside(&self, steps: usize) -> u64 {
+        self.distances.tiles().iter().filter(|distance| is_reachable(**distance, steps)).count() as u64
+    }
+
+    // Counts the reachable cells in the infinite garden, assuming the window is
+    // steady; see the top of this file.
+
+    fn count(&self, grid: &Grid<Tile>, steps: usize) -> u64 {
+        let (rows, cols): (usize, usize) = (self.rows as usize, self.cols as usize);
+        let mut corners: HashMap<usize, u64> = HashMap::new();
+        let mut count: u64 = 0;
+
+        for copy in self.copies() {
+            let on_edge_row: bool = copy.row.abs() == self.radius;
+            let on_edge_col: bool = copy.col.abs() == self.radius;
+
+            for cell in grid.positions() {
+                let distance: usize = self.get(copy, cell);
+
+                if distance == UNREACHABLE {
+                    continue;
+                }
+
+                match (on_edge_row, on_edge_col) {
+                    (false, false) => count += is_reachable(distance, steps) as u64,
+                    (true,  false) => count += count_line(distance, rows, steps),
+                    (false, true)  => count += count_line(distance, cols, steps),
+                    (true,  true)  => *corners.entry(distance).or_insert(0) += 1
+                }
+            }
+        }
+
+        for (distance, cells) in corners {
+            count += cells * count_quadrant(distance, rows, cols, steps);
+        }
+
+        count
+    }
+}
+
+/* ------------------------------- Main logic ------------------------------- */
+
+fn has_open_corridors(grid: &Grid<Tile>, start: Pos) -> bool {
+    let is_open_row = |row: isize| (0 .. grid.cols).all(|col| grid[Pos::new(row, col)].is_empty());
+    let is_open_col = |col: isize| (0 .. grid.rows).all(|row| grid[Pos::new(row, col)].is_empty());
+
+    grid.rows == grid.cols && start == Pos::new(grid.rows / 2, grid.cols / 2) &&
+        is_open_row(0) && is_open_row(grid.rows - 1) && is_open_row(start.row) &&
+        is_open_col(0) && is_open_col(grid.cols - 1) && is_open_col(start.col)
+}
+
+// The fast path for inputs with open corridors. Along with the count, both
+// approaches return the counts they predict for a couple of smaller step
+// counts, for the cross-check.
+
+fn count_fast(grid: &Grid<Tile>, start: Pos, steps: usize) -> Option<(u64, Vec<(usize, u64)>)> {
+    let size: usize = grid.rows as usize;
+
+    if steps % size != size / 2 {
+        return None;
+    }
+
+    let window: Window = Window::compute(grid, start, 3);
+    let samples: Vec<i128> = (0 .. 3).map(|k| window.count_inside(size / 2 + k * size) as i128).collect();
+    let (f0, f1, f2): (i128, i128, i128) = (samples[0], samples[1], samples[2]);
+
+    let extrapolate = |k: usize| -> Option<u64> {
+        let k: i128 = k as i128;
+        u64::try_from(f0 + k * (f1 - f0) + k * (k - 1) / 2 * (f2 - 2 * f1 + f0)).ok()
+    };
+
+    let checks: Vec<(usize, u64)> = [2, 3].into_iter()
+        .map(|k| Some((size / 2 + k * size, extrapolate(k)?)))
+        .collect::<Option<Vec<(usize, u64)>>>()?;
+
+    Some((extrapolate(steps / size)?, checks))
+}
+
+fn count_general(grid: &Grid<Tile>, start: Pos, steps: usize) -> Option<(u64, Vec<(usize, u64)>)> {
+    let mut radius: isize = MIN_RADIUS;
+
+    while (2 * radius + 1) * (2 * radius + 1) * grid.rows * grid.cols <= MAX_WINDOW_CELLS {
+        let window: Window = Window::compute(grid, start, radius);
+
+        if window.is_steady(grid) {
+            // The corner copies of the window are about `2 * radius` copies away
+            // from the start; a few copies further, every quadrant and line
+            // counts several copies, for both parities.
+
+            let size: usize = grid.rows.max(grid.cols) as usize;
+            let beyond: usize = (2 * radius as usize + 4) * size + size / 2;
+            let checks: Vec<(usize, u64)> = [beyond, beyond + 1].into_iter()
+                .map(|steps| (steps, window.count(grid, steps))).collect();
+
+            return Some((window.count(grid, steps), checks));
+        }
+
+        radius *= 2;
+    }
+
+    None
+}
+
+// Counts the reachable cells with a plain breadth-first search through the
+// infinite garden, one step at a time, without any assumptions about how the
+// distances repeat. Simulating the set of positions after every step is too
+// slow for the step counts we check, so we only visit every cell once, and
+// count the cells first reached after a number of steps with the right parity.
+// No cell further than `steps` from the start can be reached, so the visited
+// cells fit in a square around the start.
+
+fn count_brute_force(grid: &Grid<Tile>, start: Pos, steps: usize) -> u64 {
+    let reach: isize = steps as isize;
+    let mut seen: Grid<bool> = Grid::filled(2 * reach + 1, 2 * reach + 1, false);
+    let mut frontier: Vec<Pos> = vec!(start);
+    seen[Pos::new(reach, reach)] = true;
+    let mut count: u64 = steps.is_multiple_of(2) as u64;
+
+    for step in 1 ..= steps {
+        let mut next: Vec<Pos> = Vec::new();
+
+        for pos in frontier {
+            for neighbor in pos.neighbors() {
+                let local: Pos = Pos::new(neighbor.row - start.row + reach, neighbor.col - start.col + reach);
+
+                if seen.contains(local) && !seen[local] && grid[wrap(grid, neighbor)].is_empty() {
+                    seen[local] = true;
+                    next.push(neighbor);
+                }
+            }
+        }
+
+        if is_reachable(step, steps) {
+            count += next.len() as u64;
+        }
+
+        frontier = next;
+    }
+
+    count
+}
+
+pub fn parse(lines: &Vec<String>) -> ParseResult<Grid<Tile>> {
+    parse_grid(lines)
+}
+
+pub fn solve(grid: &Grid<Tile>) -> Solution {
+    let start: Pos = find_start(grid);
+
+    let counted: Option<(u64, Vec<(usize, u64)>)> = if has_open_corridors(grid, start) {
+        count_fast(grid, start, STEPS).or_else(|| count_general(grid, start, STEPS))
+    } else {
+        count_general(grid, start, STEPS)
+    };
+
+    let Some((count, checks)) = counted else {
+        return Solution::unsolved("the distances do not become steady");
+    };
+
+    if checks.iter().any(|(steps, count)| count_brute_force(grid, start, *steps) != *count) {
+        return Solution::unsolved("the count does not match a brute-force simulation");
+    }
+
+    return Solution::Unsigned(count)
+}
+
+/* ---------------------------------- Tests --------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<Tile> {
+        let lines: Vec<String> = include_str!("input/sample.txt").lines().map(String::from).collect();
+        parse(&lines).unwrap()
+    }
+
+    // The step counts and counts given in the puzzle description.
+
+    #[test]
+    fn general_count_matches_sample() {
+        let grid: Grid<Tile> = sample();
+        let start: Pos = find_start(&grid);
+
+        for (steps, expected) in [(6, 16), (10, 50), (50, 1594), (100, 6536), (500, 167004), (1000, 668697), (5000, 16733044)] {
+            let (count, _) = count_general(&grid, start, steps).unwrap();
+            assert_eq!(count, expected, "{steps} steps");
+        }
+    }
+
+    #[test]
+    fn general_count_matches_brute_force() {
+        let grid: Grid<Tile> = sample();
+        let start: Pos = find_start(&grid);
 
-pub fn solve(_: &Vec<String>) -> Solution {
-    return Solution::Integer(-1 as i64)
+        for steps in [50, 100, 500] {
+            let (count, _) = count_general(&grid, start, steps).unwrap();
+            assert_eq!(count, count_brute_force(&grid, start, steps), "{steps} steps");
+        }
+    }
 }
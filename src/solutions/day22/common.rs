@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 use std::ops::RangeInclusive;
 
+use crate::solutions::parse::{self, Line, ParseError, ParseResult};
+
 /* -------------------------------- Position -------------------------------- */
 
 struct Position {
@@ -10,16 +12,20 @@ struct Position {
 }
 
 impl Position {
-    fn parse(pos_str: &str) -> Position {
-        let fields: Vec<usize> = pos_str.split(',')
-            .map(|f| f.parse().unwrap()).collect();
+    fn parse(line: &Line, pos_str: &str) -> ParseResult<Position> {
+        let fields: Vec<usize> = line.fixed_numbers(pos_str, ",", 3)?;
+
+        if fields[2] == 0 {
+            return Err(line.error(pos_str, "a position above the ground (z > 0)"));
+        }
 
-        Position { x: fields[0], y: fields[1], z: fields[2] - 1 }
+        Ok(Position { x: fields[0], y: fields[1], z: fields[2] - 1 })
     }
 }
 
 /* ---------------------------------- Brick --------------------------------- */
 
+#[derive(Clone)]
 pub struct Brick {
     pub id: usize,
     pub positions: Vec<(usize, usize)>,
@@ -31,10 +37,14 @@ pub struct Brick {
 }
 
 impl Brick {
-    fn parse(line: &str) -> Brick {
-        let (start, end) = line.split_once('~').unwrap();
-        let start_pos: Position = Position::parse(start);
-        let end_pos: Position = Position::parse(end);
+    fn parse(line: &Line) -> ParseResult<Brick> {
+        let (start, end) = line.split_once(line.text, "~")?;
+        let start_pos: Position = Position::parse(line, start)?;
+        let end_pos: Position = Position::parse(line, end)?;
+
+        if start_pos.x > end_pos.x || start_pos.y > end_pos.y || start_pos.z > end_pos.z {
+            return Err(line.error(end, "an end position not below the start position"));
+        }
 
         let mut positions: Vec<(usize, usize)> = Vec::new();
 
@@ -44,15 +54,15 @@ impl Brick {
             }
         }
 
-        Brick {
+        Ok(Brick {
             id: usize::MAX,
-            positions: positions,
+            positions,
             range_x: (start_pos.x ..= end_pos.x),
             range_y: (start_pos.y ..= end_pos.y),
             range_z: (start_pos.z ..= end_pos.z),
             height: end_pos.z - start_pos.z + 1,
             on: HashSet::new()
-        }
+        })
     }
 }
 
@@ -105,15 +115,19 @@ impl HeightMap {
 
 /* --------------------------------- Parsing -------------------------------- */
 
-pub fn parse_bricks(lines: &Vec<String>) -> Vec<Brick> {
-    let mut bricks: Vec<Brick> = lines.into_iter()
-        .map(|line| Brick::parse(line)).collect();
+pub fn parse_bricks(lines: &Vec<String>) -> ParseResult<Vec<Brick>> {
+    let mut bricks: Vec<Brick> = parse::lines(lines)
+        .map(|line| Brick::parse(&line)).collect::<ParseResult<Vec<Brick>>>()?;
+
+    if bricks.is_empty() {
+        return Err(ParseError::input(lines, "a list of bricks", ""));
+    }
 
     bricks.sort_by(|a, b| 
-        a.range_z.start().cmp(&b.range_z.start())
-        .then(a.range_z.end().cmp(&b.range_z.end())));
+        a.range_z.start().cmp(b.range_z.start())
+        .then(a.range_z.end().cmp(b.range_z.end())));
 
     bricks.iter_mut().enumerate().for_each(|(i, b)| { b.id = i; });
 
-    bricks
+    Ok(bricks)
 }
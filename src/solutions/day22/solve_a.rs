@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use crate::solutions::Solution;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day22::common::*;
 
 // Instead of modeling the whole 3D structure, we only keep track of a height
@@ -39,8 +40,12 @@ fn count_safe_bricks(bricks: Vec<Brick>) -> usize {
     safe_bricks.len()
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let mut bricks: Vec<Brick> = parse_bricks(lines);
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Brick>> {
+    parse_bricks(lines)
+}
+
+pub fn solve(bricks: &Vec<Brick>) -> Solution {
+    let mut bricks: Vec<Brick> = bricks.clone();
     let mut height_map: HeightMap = HeightMap::create(&bricks);
     
     for brick in bricks.iter_mut() {
@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use crate::solutions::Solution;
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day22::common::*;
 
 // We start by repeating the A part; after this, each brick contains a list of
@@ -50,8 +51,12 @@ fn drop_all(initial_brick_index: usize, on_map: &Vec<HashSet<usize>>, under_map:
     dropped.len()
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let mut bricks: Vec<Brick> = parse_bricks(lines);
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Brick>> {
+    parse_bricks(lines)
+}
+
+pub fn solve(bricks: &Vec<Brick>) -> Solution {
+    let mut bricks: Vec<Brick> = bricks.clone();
     let mut height_map: HeightMap = HeightMap::create(&bricks);
     
     for brick in bricks.iter_mut() {
@@ -1,4 +1,7 @@
 use crate::solutions::Solution;
+use crate::solutions::geometry::Direction;
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day23::common::*;
 
 // We convert the input grid into a graph by first looking for all nodes
@@ -23,36 +26,32 @@ use crate::solutions::day23::common::*;
 // fairly fast, especially for the A part; the total graph contains only
 // 35 nodes, and the slopes limit the number of paths.
 
-impl Grid {
-    fn parse_a(lines: &Vec<String>) -> Grid {
-        let tiles: Vec<Tile> = lines.concat().chars().map(|c| {
+impl Grid<Tile> {
+    fn parse_a(lines: &Vec<String>) -> ParseResult<Grid<Tile>> {
+        Grid::parse(lines, 0, "'.', '#' or a slope", |c| {
             match c {
-                '.' => Tile::Empty,
-                '#' => Tile::Wall,
-                '<' => Tile::SlopeL,
-                '>' => Tile::SlopeR,
-                'v' => Tile::SlopeD,
-                '^' => Tile::SlopeU,
-                _ => panic!()
+                '.' => Some(Tile::Empty),
+                '#' => Some(Tile::Wall),
+                _ => Direction::from_arrow(c).map(Tile::Slope)
             }
-        }).collect();
-    
-        Grid {
-            tiles: tiles,
-            rows: lines.len() as isize,
-            cols: lines[0].len() as isize,
-        }
+        })
     }
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let grid: Grid = Grid::parse_a(lines);
-    let start_pos: (isize, isize) = (0, 1);
-    let target_pos: (isize, isize) = (grid.rows - 1, grid.cols - 2);
-    let graph: Graph = Graph::create(&grid, start_pos, target_pos);
+pub fn parse(lines: &Vec<String>) -> ParseResult<Grid<Tile>> {
+    Grid::parse_a(lines)
+}
+
+pub fn solve(grid: &Grid<Tile>) -> Solution {
+    let start_pos: Pos = Pos::new(0, 1);
+    let target_pos: Pos = Pos::new(grid.rows - 1, grid.cols - 2);
+    let graph: Graph = Graph::create(grid, start_pos, target_pos);
     let visited: Vec<bool> = vec![false; graph.nodes.len()];
 
     let result: Option<usize> = find_longest_path(&graph, graph.start_id, visited);
 
-    return Solution::Integer(result.unwrap() as i64)
+    match result {
+        Some(steps) => Solution::Integer(steps as i64),
+        None        => Solution::unsolved("there is no path to the target")
+    }
 }
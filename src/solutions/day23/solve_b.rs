@@ -1,4 +1,6 @@
 use crate::solutions::Solution;
+use crate::solutions::grid::{Grid, Pos};
+use crate::solutions::parse::ParseResult;
 use crate::solutions::day23::common::*;
 
 // Exactly the same as the A part; the only difference is that slope characters
@@ -6,36 +8,36 @@ use crate::solutions::day23::common::*;
 // more connections as a result, the exhaustive search for the longest path
 // is significantly slower, but at under a second it is still acceptable.
 
-impl Grid {
-    fn parse_b(lines: &Vec<String>) -> Grid {
-        let tiles: Vec<Tile> = lines.concat().chars().map(|c| {
+impl Grid<Tile> {
+    fn parse_b(lines: &Vec<String>) -> ParseResult<Grid<Tile>> {
+        Grid::parse(lines, 0, "'.', '#' or a slope", |c| {
             match c {
-                '.' => Tile::Empty,
-                '#' => Tile::Wall,
-                '<' => Tile::Empty,
-                '>' => Tile::Empty,
-                'v' => Tile::Empty,
-                '^' => Tile::Empty,
-                _ => panic!()
+                '.' => Some(Tile::Empty),
+                '#' => Some(Tile::Wall),
+                '<' => Some(Tile::Empty),
+                '>' => Some(Tile::Empty),
+                'v' => Some(Tile::Empty),
+                '^' => Some(Tile::Empty),
+                _ => None
             }
-        }).collect();
-    
-        Grid {
-            tiles: tiles,
-            rows: lines.len() as isize,
-            cols: lines[0].len() as isize,
-        }
+        })
     }
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let grid: Grid = Grid::parse_b(lines);
-    let start_pos: (isize, isize) = (0, 1);
-    let target_pos: (isize, isize) = (grid.rows - 1, grid.cols - 2);
-    let graph: Graph = Graph::create(&grid, start_pos, target_pos);
+pub fn parse(lines: &Vec<String>) -> ParseResult<Grid<Tile>> {
+    Grid::parse_b(lines)
+}
+
+pub fn solve(grid: &Grid<Tile>) -> Solution {
+    let start_pos: Pos = Pos::new(0, 1);
+    let target_pos: Pos = Pos::new(grid.rows - 1, grid.cols - 2);
+    let graph: Graph = Graph::create(grid, start_pos, target_pos);
     let visited: Vec<bool> = vec![false; graph.nodes.len()];
 
     let result: Option<usize> = find_longest_path(&graph, graph.start_id, visited);
 
-    return Solution::Integer(result.unwrap() as i64)
+    match result {
+        Some(steps) => Solution::Integer(steps as i64),
+        None        => Solution::unsolved("there is no path to the target")
+    }
 }
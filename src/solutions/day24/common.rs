@@ -1,19 +1,21 @@
+use crate::solutions::parse::{Line, ParseResult};
+
 pub struct Hailstone {
-    pub sx: f64,
-    pub sy: f64,
-    pub sz: f64,
-    pub dx: f64,
-    pub dy: f64,
-    pub dz: f64
+    pub sx: i64,
+    pub sy: i64,
+    pub sz: i64,
+    pub dx: i64,
+    pub dy: i64,
+    pub dz: i64
 }
 
 impl Hailstone {
-    pub fn parse(line: &str) -> Hailstone {
-        let (s_fields, d_fields) = line.split_once('@').unwrap();
-        let s_values: Vec<f64> = s_fields.trim().split(", ").map(|f| f.trim().parse().unwrap()).collect();
-        let d_values: Vec<f64> = d_fields.trim().split(", ").map(|f| f.trim().parse().unwrap()).collect();
-        
-        Hailstone {
+    pub fn parse(line: &Line) -> ParseResult<Hailstone> {
+        let (s_fields, d_fields) = line.split_once(line.text, "@")?;
+        let s_values: Vec<i64> = line.fixed_numbers(s_fields.trim(), ",", 3)?;
+        let d_values: Vec<i64> = line.fixed_numbers(d_fields.trim(), ",", 3)?;
+
+        Ok(Hailstone {
             sx: s_values[0],
             sy: s_values[1],
             sz: s_values[2],
@@ -21,6 +23,14 @@ impl Hailstone {
             dx: d_values[0],
             dy: d_values[1],
             dz: d_values[2]
-        }
+        })
+    }
+
+    pub fn position(&self) -> [i128; 3] {
+        [self.sx as i128, self.sy as i128, self.sz as i128]
+    }
+
+    pub fn velocity(&self) -> [i128; 3] {
+        [self.dx as i128, self.dy as i128, self.dz as i128]
     }
 }
@@ -1,4 +1,6 @@
 use crate::solutions::Solution;
+use crate::solutions::parse::{self, ParseResult};
+use crate::solutions::rational::Rational;
 use crate::solutions::day24::common::*;
 
 // A straightforward exhaustive solution, simply check all pairs of hailstones
@@ -6,82 +8,59 @@ use crate::solutions::day24::common::*;
 // figuring out how to find intersections for these kinds of equations. Note
 // that the area bounds need to be changed in the code in order to get the
 // correct answer for the sample input, see constants defined below.
+//
+// Writing the paths as `S_a + t * D_a` and `S_b + u * D_b`, the intersection
+// follows from solving `S_a + t * D_a = S_b + u * D_b` for `t` and `u` using
+// Cramer's rule; the paths are parallel if the determinant is zero. Both times
+// must be non-negative, since the hailstones only move forward. We compute the
+// times and the intersection as exact fractions, so that intersections close
+// to the edge of the area are never rounded to the wrong side.
 
 // sample
-// const AREA_MIN: f64 =  7.0;
-// const AREA_MAX: f64 = 27.0;
+// const AREA_MIN: i128 =  7;
+// const AREA_MAX: i128 = 27;
 
 // main
-const AREA_MIN: f64 = 200000000000000.0;
-const AREA_MAX: f64 = 400000000000000.0;
+const AREA_MIN: i128 = 200000000000000;
+const AREA_MAX: i128 = 400000000000000;
 
-/* ----------------------------- HailstonePath2D ---------------------------- */
+/* ------------------------------- Main logic ------------------------------- */
 
-struct HailstonePath2D {
-    dx: f64,
-    sx: f64,
-    a: f64,
-    b: f64
+fn in_area(value: Rational) -> bool {
+    value >= Rational::from(AREA_MIN) && value <= Rational::from(AREA_MAX)
 }
 
-impl HailstonePath2D {
-    fn compute(hailstone: Hailstone) -> HailstonePath2D {
-        let a: f64 = hailstone.dy / hailstone.dx;
-        let b: f64 = hailstone.sy - a * hailstone.sx;
+fn intersect(a: &Hailstone, b: &Hailstone) -> bool {
+    let det: i128 = (a.dx as i128) * (b.dy as i128) - (a.dy as i128) * (b.dx as i128);
 
-        HailstonePath2D {
-            dx: hailstone.dx,
-            sx: hailstone.sx,
-            a: a,
-            b: b
-        }
+    if det == 0 {
+        return false;
     }
 
-    fn intersect(path_a: &HailstonePath2D, path_b: &HailstonePath2D) -> bool {
-        if path_a.a == path_b.a {
-            return false;
-        }
-
-        let ix: f64 = (path_b.b - path_a.b) / (path_a.a - path_b.a);
-
-        if ix < AREA_MIN || ix > AREA_MAX {
-            return false;
-        }
-
-        let ta: f64 = (ix - path_a.sx) / path_a.dx;
+    let ox: i128 = (b.sx - a.sx) as i128;
+    let oy: i128 = (b.sy - a.sy) as i128;
 
-        if ta < 0.0 {
-            return false;
-        }
+    let t: Rational = Rational::new(ox * b.dy as i128 - oy * b.dx as i128, det);
+    let u: Rational = Rational::new(ox * a.dy as i128 - oy * a.dx as i128, det);
 
-        let tb: f64 = (ix - path_b.sx) / path_b.dx;
-
-        if tb < 0.0 {
-            return false;
-        }
-
-        let iy: f64 = path_a.a * ix + path_a.b;
+    if t.is_negative() || u.is_negative() {
+        return false;
+    }
 
-        if iy < AREA_MIN || iy > AREA_MAX {
-            return false;
-        }
+    let ix: Rational = Rational::from(a.sx) + t * Rational::from(a.dx);
+    let iy: Rational = Rational::from(a.sy) + t * Rational::from(a.dy);
 
-        true
-    }
+    in_area(ix) && in_area(iy)
 }
 
-/* ------------------------------- Main logic ------------------------------- */
-
-fn count_intersects(paths: Vec<HailstonePath2D>) -> i64 {
+fn count_intersects(hailstones: &Vec<Hailstone>) -> i64 {
     let mut count: i64 = 0;
 
-    for index_a in 1 .. paths.len() {
-        let path_a: &HailstonePath2D = &paths[index_a];
-
-        for index_b in 0 .. index_a {
-            let path_b: &HailstonePath2D = &paths[index_b];
+    for index_a in 1 .. hailstones.len() {
+        let hailstone_a: &Hailstone = &hailstones[index_a];
 
-            if HailstonePath2D::intersect(path_a, path_b) {
+        for hailstone_b in &hailstones[0 .. index_a] {
+            if intersect(hailstone_a, hailstone_b) {
                 count += 1;
             }
         }
@@ -90,13 +69,11 @@ fn count_intersects(paths: Vec<HailstonePath2D>) -> i64 {
     count
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    let hailstones: Vec<Hailstone> = lines.into_iter()
-        .map(|line| Hailstone::parse(line)).collect();
-    let paths: Vec<HailstonePath2D> = hailstones.into_iter()
-        .map(|hs| HailstonePath2D::compute(hs)).collect();
-
-    let result: i64 = count_intersects(paths);
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Hailstone>> {
+    parse::lines(lines).map(|line| Hailstone::parse(&line)).collect()
+}
 
+pub fn solve(hailstones: &Vec<Hailstone>) -> Solution {
+    let result: i64 = count_intersects(hailstones);
     return Solution::Integer(result)
 }
@@ -1,127 +1,227 @@
 use crate::solutions::Solution;
+use crate::solutions::linear;
+use crate::solutions::number::gcd;
+use crate::solutions::parse::{self, ParseResult};
+use crate::solutions::rational::Rational;
+use crate::solutions::day24::common::*;
+
+// I originally did not finish this one; my attempt searched for the X and Y
+// velocity of the rock exhaustively, which worked for the sample but not for
+// the actual input. It turns out that with the right point of view, the whole
+// problem reduces to a couple of small linear systems.
+//
+// The trick is to look at everything from the point of view of one hailstone
+// `H0`, i.e. to subtract its position and velocity from all hailstones and from
+// the rock. In this frame of reference, `H0` sits still at the origin, so the
+// rock has to pass through the origin. For any other hailstone `Hi`, the path
+// of the rock and the path of `Hi` intersect, so the rock's path lies in the
+// plane through the origin that contains the path of `Hi`. The normal of that
+// plane is the cross product of the position and velocity of `Hi`, and the
+// rock's velocity `V` is perpendicular to it. With two hailstones, this gives
+// us two linear equations in `V`; their solutions form a line, i.e. they give
+// us the direction `D` of the rock, but not yet its speed.
+//
+// Next, we find where the rock's path hits two hailstones: the rock is at
+// `L * D` for some `L`, and hailstone `Hi` is at `Pi + T * Vi` at time `T`, so
+// solving `L * D - T * Vi = Pi` (three equations, two unknowns) gives us both
+// the point where they meet and the time `T` at which they do so. Two such
+// collisions tell us how far the rock moves in how much time, which gives us
+// its velocity, and from there its starting position. Moving back to the
+// original frame of reference then gives us the answer.
+//
+// All of this uses exact rational arithmetic. Working relative to `H0` keeps
+// the values small enough for 128-bit integers; if a system still overflows
+// (or if a pair of hailstones happens to be degenerate, e.g. parallel to the
+// rock, or gives a rock that is not at integer coordinates), we simply try the
+// next pair. Finally, we check that the rock actually hits every single
+// hailstone at a non-negative integer time.
+
+/* ---------------------------------- Vector -------------------------------- */
+
+type Vector = [i128; 3];
+
+fn add(a: &Vector, b: &Vector) -> Vector {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: &Vector, b: &Vector) -> Vector {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: &Vector, b: &Vector) -> Vector {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn to_rationals(v: &Vector) -> Vec<Rational> {
+    v.iter().map(|value| Rational::from(*value)).collect()
+}
+
+// Converts a vector of rationals that are known to be integers.
+
+fn to_vector(v: &[Rational]) -> Vector {
+    [v[0].numerator(), v[1].numerator(), v[2].numerator()]
+}
+
+// Scales a rational vector to the shortest integer vector in the same direction,
+// or returns `None` if that does not fit.
+
+fn to_integers(v: &[Rational]) -> Option<Vector> {
+    let denominators: i128 = v.iter().try_fold(1, |acc: i128, value| {
+        (acc / gcd(acc, value.denominator())).checked_mul(value.denominator())
+    })?;
+
+    let scaled: Vec<i128> = v.iter()
+        .map(|value| value.numerator().checked_mul(denominators / value.denominator()))
+        .collect::<Option<Vec<i128>>>()?;
+
+    let divisor: i128 = scaled.iter().fold(0, |acc, value| gcd(acc, *value)).max(1);
+    Some([scaled[0] / divisor, scaled[1] / divisor, scaled[2] / divisor])
+}
+
+/* ---------------------------------- Rock ---------------------------------- */
+
+struct Rock {
+    position: Vector,
+    velocity: Vector
+}
+
+impl Rock {
+    // Returns the time at which the rock hits the hailstone, if it does so at
+    // a non-negative integer time.
+
+    fn collision_time(&self, hailstone: &Hailstone) -> Option<i128> {
+        let offset: Vector = sub(&hailstone.position(), &self.position);
+        let speed: Vector = sub(&self.velocity, &hailstone.velocity());
+        let mut time: Option<i128> = None;
+
+        for axis in 0 .. 3 {
+            if speed[axis] == 0 {
+                if offset[axis] != 0 {
+                    return None;
+                }
+            } else {
+                if offset[axis] % speed[axis] != 0 {
+                    return None;
+                }
+
+                let axis_time: i128 = offset[axis] / speed[axis];
+
+                if axis_time < 0 || time.is_some_and(|t| t != axis_time) {
+                    return None;
+                }
+
+                time = Some(axis_time);
+            }
+        }
+
+        Some(time.unwrap_or(0))
+    }
+}
+
+/* ------------------------------- Main logic ------------------------------- */
+
+// The direction of the rock (relative to `H0`), given the relative positions
+// and velocities of two other hailstones.
+
+fn find_direction(a: &(Vector, Vector), b: &(Vector, Vector)) -> Option<Vector> {
+    let normals: Vec<Vec<Rational>> = vec![to_rationals(&cross(&a.0, &a.1)), to_rationals(&cross(&b.0, &b.1))];
+    let basis: Vec<Vec<Rational>> = linear::null_space(&normals).ok()?;
+
+    match &basis[..] {
+        [direction] => to_integers(direction),
+        _ => None
+    }
+}
+
+// Finds `(L, T)` such that `L * D = P + T * V`, i.e. the point along the rock's
+// path where it hits the hailstone, and the time at which it does so.
+
+fn find_collision(direction: &Vector, hailstone: &(Vector, Vector)) -> Option<(Rational, Rational)> {
+    let (position, velocity) = hailstone;
+
+    let coefficients: Vec<Vec<Rational>> = (0 .. 3)
+        .map(|axis| vec![Rational::from(direction[axis]), Rational::from(-velocity[axis])])
+        .collect();
+
+    let solution: Vec<Rational> = linear::solve(&coefficients, &to_rationals(position)).ok()?;
+    Some((solution[0], solution[1]))
+}
+
+// The position and velocity of the rock (relative to `H0`), or `None` if the
+// pair of hailstones is degenerate or any of the values overflows.
+
+fn find_relative_rock(a: &(Vector, Vector), b: &(Vector, Vector)) -> Option<(Vec<Rational>, Vec<Rational>)> {
+    let direction: Vector = find_direction(a, b)?;
+    let (distance_a, time_a) = find_collision(&direction, a)?;
+    let (distance_b, time_b) = find_collision(&direction, b)?;
+
+    if time_a == time_b {
+        return None;
+    }
+
+    let speed: Rational = distance_a.checked_sub(&distance_b)?.checked_div(&time_a.checked_sub(&time_b)?)?;
+
+    let velocity: Vec<Rational> = direction.iter()
+        .map(|d| Rational::from(*d).checked_mul(&speed))
+        .collect::<Option<Vec<Rational>>>()?;
+
+    let position: Vec<Rational> = direction.iter().zip(&velocity)
+        .map(|(d, v)| Rational::from(*d).checked_mul(&distance_a)?.checked_sub(&v.checked_mul(&time_a)?))
+        .collect::<Option<Vec<Rational>>>()?;
+
+    Some((position, velocity))
+}
+
+fn find_rock(hailstones: &Vec<Hailstone>) -> Option<Rock> {
+    let origin: &Hailstone = hailstones.first()?;
+
+    let relative: Vec<(Vector, Vector)> = hailstones[1 ..].iter().map(|hailstone| {
+        (sub(&hailstone.position(), &origin.position()), sub(&hailstone.velocity(), &origin.velocity()))
+    }).collect();
+
+    for index_a in 0 .. relative.len() {
+        for index_b in index_a + 1 .. relative.len() {
+            let Some((position, velocity)) = find_relative_rock(&relative[index_a], &relative[index_b]) else {
+                continue;
+            };
+
+            if !position.iter().chain(&velocity).all(|value| value.is_integer()) {
+                continue;
+            }
+
+            return Some(Rock {
+                position: add(&to_vector(&position), &origin.position()),
+                velocity: add(&to_vector(&velocity), &origin.velocity())
+            });
+        }
+    }
+
+    None
+}
+
+pub fn parse(lines: &Vec<String>) -> ParseResult<Vec<Hailstone>> {
+    parse::lines(lines).map(|line| Hailstone::parse(&line)).collect()
+}
+
+pub fn solve(hailstones: &Vec<Hailstone>) -> Solution {
+    let Some(rock) = find_rock(hailstones) else {
+        return Solution::unsolved("no rock position and velocity found");
+    };
+
+    if !hailstones.iter().all(|hailstone| rock.collision_time(hailstone).is_some()) {
+        return Solution::unsolved("the rock does not hit every hailstone");
+    }
+
+    let [x, y, z] = rock.position;
+    let [dx, dy, dz] = rock.velocity;
+
+    let Some(result) = x.checked_add(y).and_then(|sum| sum.checked_add(z)) else {
+        return Solution::unsolved("the answer does not fit in 128 bits");
+    };
 
-// I did not finish this one. Mathematics isn't really my strong suit, and so
-// despite a lot of scribbled equations and poorly drawn graphs, I wasn't able
-// to come up with a way to find the rock's starting point and velocity. The
-// commented-out attempt below was based on the idea to first find an X velo-
-// city dx and a Y velocity dy, subtract dx and dy from the velocity of each
-// hailstone, and (ignoring the Z dimension) check if all modified hailstones
-// pass through the same integer point. This approach – which I admittedly got
-// from Reddit – could then theoretically be used to find the Z velocity, and
-// from there the starting point.
-
-// This approach works for the sample input, but for the actual input it either
-// doesn't work, or the required dx and dy are so high that the current exhaus-
-// tive approach of finding dx and dy isn't realistic. Having already spent far
-// too much time on this, and with no motivation for finding and implementing a
-// new approach, I felt fine leaving it here.
-
-// const DELTA: f64 = 0.001;
-
-// impl Hailstone {
-//     fn intersect_xy(hailstone1: &Hailstone, hailstone2: &Hailstone, rock_velocity: &(isize, isize)) -> Option<(f64, f64)> {
-//         let dx1: f64 = hailstone1.dx - (rock_velocity.0 as f64);
-//         let dx2: f64 = hailstone2.dx - (rock_velocity.0 as f64);
-
-//         if f64::abs(dx1) < DELTA && f64::abs(dx2) >= DELTA {
-//             let ix: f64 = hailstone1.sx;
-//             let dy2: f64 = hailstone2.dy - (rock_velocity.1 as f64);
-
-//             let a2: f64 = dy2 / dx2;
-//             let b2: f64 = hailstone2.sy - a2 * hailstone2.sx;
-
-//             let iy: f64 = a2 * ix + b2;
-//             return Some((ix, iy))
-//         } else if f64::abs(dx2) < DELTA  && f64::abs(dx1) >= DELTA {
-//             let ix: f64 = hailstone2.sx;
-//             let dy1: f64 = hailstone1.dy - (rock_velocity.1 as f64);
-
-//             let a1: f64 = dy1 / dx1;
-//             let b1: f64 = hailstone1.sy - a1 * hailstone1.sx;
-
-//             let iy: f64 = a1 * ix + b1;
-//             return Some((ix, iy))
-//         }
-
-//         let dy1: f64 = hailstone1.dy - (rock_velocity.1 as f64);
-//         let dy2: f64 = hailstone2.dy - (rock_velocity.1 as f64);
-
-//         let a1: f64 = dy1 / dx1;
-//         let a2: f64 = dy2 / dx2;
-
-//         if f64::abs(a1 - a2) < DELTA {
-//             // lines are parallel
-//             return None;
-//         }
-
-//         let b1: f64 = hailstone1.sy - a1 * hailstone1.sx;
-//         let b2: f64 = hailstone2.sy - a2 * hailstone2.sx;
-
-//         let ix: f64 = (b2 - b1) / (a1 - a2);
-//         let iy: f64 = a1 * ix + b1;
-//         Some((ix, iy))
-//     }
-// }
-
-// fn is_integer(v: f64) -> bool { f64::abs(f64::round(v) - v) < DELTA }
-// fn is_integer_pair(p: (f64, f64)) -> bool { is_integer(p.0) && is_integer(p.1) }
-// fn to_integer_pair(p: (f64, f64)) -> (isize, isize) { (f64::round(p.0) as isize, f64::round(p.1) as isize) }
-
-// fn test_xy_velocity(hailstones: &Vec<Hailstone>, rock_velocity: (isize, isize)) -> bool {
-//     let mut found_intersection: bool = false;
-//     let mut intersection: (isize, isize) = (0, 0);
-
-//     for i in 0 .. hailstones.len() - 1 {
-//         for j in i + 1 .. hailstones.len() {
-//             if let Some(new_intersection) = Hailstone::intersect_xy(&hailstones[i], &hailstones[j], &rock_velocity) {
-//                 if !is_integer_pair(new_intersection) {
-//                     // println!("{:?}: not an integer pair: {:?}", rock_velocity, new_intersection);
-//                     return false;
-//                 }
-
-//                 let ip: (isize, isize) = to_integer_pair(new_intersection);
-
-//                 if !found_intersection {
-//                     found_intersection = true;
-//                     intersection = ip;
-//                 } else if intersection != ip {
-//                     // println!("{:?}: different intersection: {:?} != {:?}", rock_velocity, intersection, ip);
-//                     return false;
-//                 }
-//             }
-//         } 
-//     }
-
-//     println!("{:?}: valid intersection: {:?}", rock_velocity, intersection);
-//     return true;
-// }
-
-// fn test_xy_velocities(hailstones: &Vec<Hailstone>, ix: isize, iy: isize) -> Option<(isize, isize)> {
-//     if test_xy_velocity(hailstones, (ix, iy)) {
-//         return Some((ix, iy));
-//     } else if ix > 0 && test_xy_velocity(hailstones, (-ix, iy)) {
-//         return Some((-ix, iy));
-//     } else if iy > 0 && test_xy_velocity(hailstones, (ix, -iy)) {
-//         return Some((ix, -iy));
-//     } else if ix > 0 && iy > 0 && test_xy_velocity(hailstones, (-ix, -iy)) {
-//         return Some((-ix, -iy));
-//     }
-
-//     None
-// }
-// fn find_xy_velocity(hailstones: &Vec<Hailstone>) {
-//     for ix in 0 .. 500000 {//isize::MAX {
-//         for iy in 0 ..= ix {
-//             test_xy_velocities(hailstones, ix, iy);
-//         }
-//     }
-// }
-
-pub fn solve(_: &Vec<String>) -> Solution {
-    // let hailstones: Vec<Hailstone> = lines.into_iter()
-    //     .map(|line| Hailstone::parse(line)).collect();
-
-    // find_xy_velocity(&hailstones);
-
-    return Solution::Integer(-1)
+    return Solution::Multiple(vec![
+        ("answer",   Solution::Wide(result)),
+        ("position", Solution::Text(format!("{x}, {y}, {z}"))),
+        ("velocity", Solution::Text(format!("{dx}, {dy}, {dz}")))
+    ])
 }
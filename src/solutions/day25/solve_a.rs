@@ -1,7 +1,7 @@
 use std::collections::HashMap;
-use std::collections::HashSet;
 
 use crate::solutions::Solution;
+use crate::solutions::parse::{self, ParseError, ParseResult};
 
 // A slightly simplified version of the Kernighan–Lin algorithm. After parsing
 // the graph, we create an initial partition by picking an arbitrary node, and
@@ -22,10 +22,10 @@ use crate::solutions::Solution;
 //
 // I wasn't sure if this would always work, or if the algorithm might get stuck
 // in an local optimum. Just in case, I added a clause where the whole solution
-// is retried (relying on the sets used during parsing to select a different
-// starting node) in case there's no possible improvement and the total cost
-// is still more than three. This clause doesn't get used in practice however,
-// so it's possible that we will always reach the optimal partition.
+// is retried, starting the expansion from the next node, in case there's no
+// possible improvement and the total cost is still more than three. This
+// clause doesn't get used in practice however, so it's possible that we will
+// always reach the optimal partition.
 
 /* ---------------------------------- Group --------------------------------- */
 
@@ -57,7 +57,7 @@ struct Node {
 impl Node {
     fn new(to: Vec<usize>) -> Node {
         Node {
-            to: to,
+            to,
             group: Group::A,
             internal_cost: 0,
             external_cost: 0,
@@ -66,6 +66,46 @@ impl Node {
     }
 }
 
+/* ---------------------------------- Wires --------------------------------- */
+
+pub struct Wires {
+    node_count: usize,
+    edges: Vec<(usize, usize)>
+}
+
+impl Wires {
+    fn parse(lines: &Vec<String>) -> ParseResult<Wires> {
+        if lines.is_empty() {
+            return Err(ParseError::input(lines, "a list of connected components", ""));
+        }
+
+        let mut node_map: HashMap<&str, usize> = HashMap::new();
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+
+        for line in parse::lines(lines) {
+            let (from_str, to_field) = line.split_once(line.text, ":")?;
+
+            if to_field.trim().is_empty() {
+                return Err(line.error(to_field, "a list of connected components"));
+            }
+
+            let from_id: usize = Self::get_node_id(&mut node_map, from_str);
+
+            for to_str in to_field.trim().split(' ') {
+                let to_id: usize = Self::get_node_id(&mut node_map, to_str);
+                edges.push((from_id, to_id));
+            }
+        }
+
+        Ok(Wires { node_count: node_map.len(), edges })
+    }
+
+    fn get_node_id<'a>(node_map: &mut HashMap<&'a str, usize>, key: &'a str) -> usize {
+        let next_id: usize = node_map.len();
+        *node_map.entry(key).or_insert(next_id)
+    }
+}
+
 /* ---------------------------------- Graph --------------------------------- */
 
 struct Graph {
@@ -74,52 +114,28 @@ struct Graph {
 }
 
 impl Graph {
-    pub fn new() -> Graph {
-        Graph { nodes: Vec::new(), cost: 0 }
-    }
+    fn new(wires: &Wires) -> Graph {
+        let mut links: Vec<Vec<usize>> = vec!(Vec::new(); wires.node_count);
 
-    pub fn parse(lines: &Vec<String>) ->  Graph {
-        let mut node_map: HashMap<String, usize> = HashMap::new();
-        let mut graph_map: HashMap<usize, HashSet<usize>> = HashMap::new();
-    
-        for line in lines {
-            let (from_str, to_field) = line.split_once(':').unwrap();
-            let from_id: usize = Self::get_node_id(&mut node_map, from_str);
-    
-            let to_strs: Vec<&str> = to_field.trim().split(' ').collect();
-            
-            to_strs.into_iter()
-                .map(|to_str| Self::get_node_id(&mut node_map, to_str))
-                .for_each(|to_id| Self::add_links(&mut graph_map, from_id, to_id));
+        for &(from_id, to_id) in &wires.edges {
+            links[from_id].push(to_id);
+            links[to_id].push(from_id);
         }
 
-        let mut graph: Graph = Graph::new();
-    
-        for node_id in 0 .. graph_map.len() {
-            let node_id_set: &HashSet<usize> = graph_map.get(&node_id).unwrap();
-            let node_id_vec: Vec<usize> = node_id_set.into_iter().cloned().collect();
-            let node: Node = Node::new(node_id_vec);
-            graph.nodes.push(node);
-        }
-    
-        graph
-    }
+        let nodes: Vec<Node> = links.into_iter().map(|mut to| {
+            to.sort_unstable();
+            to.dedup();
+            Node::new(to)
+        }).collect();
 
-    fn get_node_id(node_map: &mut HashMap<String, usize>, key: &str) -> usize {
-        let next_id: usize = node_map.len();
-        *node_map.entry(String::from(key)).or_insert(next_id)
-    }
-    
-    fn add_links(graph_map: &mut HashMap<usize, HashSet<usize>>, from_id: usize, to_id: usize) {
-        graph_map.entry(from_id).or_insert(HashSet::new()).insert(to_id);
-        graph_map.entry(to_id).or_insert(HashSet::new()).insert(from_id);
+        Graph { nodes, cost: 0 }
     }
 }
 
 /* ----------------------------- Initialization ----------------------------- */
 
-fn create_initial_split(graph: &mut Graph) {
-    let mut queue: Vec<usize> = vec!(0);
+fn create_initial_split(graph: &mut Graph, start_id: usize) {
+    let mut queue: Vec<usize> = vec!(start_id);
     let mut nr_processed: usize = 0;
     let mut index: usize = 0;
 
@@ -215,16 +231,15 @@ fn step(graph: &mut Graph) -> bool {
     true
 }
 
-fn try_solve(lines: &Vec<String>) -> Option<usize> {
-    let mut graph: Graph = Graph::parse(lines);
-    create_initial_split(&mut graph);
+fn try_solve(wires: &Wires, start_id: usize) -> Option<usize> {
+    let mut graph: Graph = Graph::new(wires);
+    create_initial_split(&mut graph, start_id);
     compute_initial_costs(&mut graph);
 
     while graph.cost > 3 {
         let ok: bool = step(&mut graph);
 
         if !ok {
-            println!("Failed attempt");
             return None
         }
     }
@@ -235,12 +250,18 @@ fn try_solve(lines: &Vec<String>) -> Option<usize> {
     Some(group_a_size * group_b_size)
 }
 
-pub fn solve(lines: &Vec<String>) -> Solution {
-    loop {
-        if let Some(result) = try_solve(lines) {
+pub fn parse(lines: &Vec<String>) -> ParseResult<Wires> {
+    Wires::parse(lines)
+}
+
+pub fn solve(wires: &Wires) -> Solution {
+    for start_id in 0 .. wires.node_count {
+        if let Some(result) = try_solve(wires, start_id) {
             return Solution::Integer(result as i64);
         }
     }
+
+    Solution::unsolved("no starting node leads to a cut of three wires")
 }
 
 // 543906 too high
@@ -0,0 +1,286 @@
+use std::fmt;
+
+use crate::solutions::grid::Pos;
+
+// Directions on a grid, shared by all days that move around on one. Following
+// the `Grid` convention, rows increase going south and columns increase going
+// east, so north is an offset of one row up, i.e. `Pos::new(-1, 0)`.
+//
+// `Direction` covers the four cardinal directions and `Ordinal` the four
+// diagonal ones; both can be turned (by a quarter turn for cardinal, and by
+// an eighth turn for ordinal directions) and reversed. `Turn` describes a
+// relative turn to the left or right, as used in instructions like "LRRL".
+// The puzzles write directions in a couple of different ways, so directions
+// can be parsed from letters ("U", "D", "L", "R"), compass points ("N", "E",
+// "S", "W") and arrows ("^", ">", "v", "<"), each with its own parser, so that
+// a day only accepts the notation its input actually uses.
+
+/* ---------------------------------- Turn ---------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Turn {
+    Left,
+    Right
+}
+
+impl Turn {
+    pub fn parse(c: char) -> Option<Turn> {
+        match c {
+            'L' => Some(Turn::Left),
+            'R' => Some(Turn::Right),
+            _ => None
+        }
+    }
+}
+
+/* -------------------------------- Direction ------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West
+}
+
+impl Direction {
+    // All directions in clockwise order, starting north; `index()` returns the
+    // position of a direction in this array, e.g. for per-direction lookups.
+
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    pub fn index(self) -> usize {
+        match self {
+            Direction::North => 0,
+            Direction::East  => 1,
+            Direction::South => 2,
+            Direction::West  => 3
+        }
+    }
+
+    pub fn left(self) -> Direction {
+        Direction::ALL[(self.index() + 3) % 4]
+    }
+
+    pub fn right(self) -> Direction {
+        Direction::ALL[(self.index() + 1) % 4]
+    }
+
+    pub fn reverse(self) -> Direction {
+        Direction::ALL[(self.index() + 2) % 4]
+    }
+
+    pub fn turn(self, turn: Turn) -> Direction {
+        match turn {
+            Turn::Left  => self.left(),
+            Turn::Right => self.right()
+        }
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Direction::East | Direction::West)
+    }
+
+    pub fn offset(self) -> Pos {
+        match self {
+            Direction::North => Pos::new(-1,  0),
+            Direction::East  => Pos::new( 0,  1),
+            Direction::South => Pos::new( 1,  0),
+            Direction::West  => Pos::new( 0, -1)
+        }
+    }
+
+    // Returns the position one step from `pos` in this direction, or `count`
+    // steps for `step_by()`.
+
+    pub fn step(self, pos: Pos) -> Pos {
+        pos + self.offset()
+    }
+
+    pub fn step_by(self, pos: Pos, count: isize) -> Pos {
+        let offset: Pos = self.offset();
+        Pos::new(pos.row + offset.row * count, pos.col + offset.col * count)
+    }
+
+    // Parses "U", "D", "L" and "R".
+
+    pub fn from_letter(c: char) -> Option<Direction> {
+        match c {
+            'U' => Some(Direction::North),
+            'R' => Some(Direction::East),
+            'D' => Some(Direction::South),
+            'L' => Some(Direction::West),
+            _ => None
+        }
+    }
+
+    // Parses "N", "E", "S" and "W".
+
+    pub fn from_compass(c: char) -> Option<Direction> {
+        match c {
+            'N' => Some(Direction::North),
+            'E' => Some(Direction::East),
+            'S' => Some(Direction::South),
+            'W' => Some(Direction::West),
+            _ => None
+        }
+    }
+
+    // Parses "^", ">", "v" and "<".
+
+    pub fn from_arrow(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Direction::North),
+            '>' => Some(Direction::East),
+            'v' => Some(Direction::South),
+            '<' => Some(Direction::West),
+            _ => None
+        }
+    }
+
+    // Parses a direction in any of the notations above.
+
+    pub fn parse(c: char) -> Option<Direction> {
+        Direction::from_letter(c)
+            .or_else(|| Direction::from_compass(c))
+            .or_else(|| Direction::from_arrow(c))
+    }
+
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::East  => '>',
+            Direction::South => 'v',
+            Direction::West  => '<'
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Direction::North => write!(f, "north"),
+            Direction::East  => write!(f, "east"),
+            Direction::South => write!(f, "south"),
+            Direction::West  => write!(f, "west")
+        }
+    }
+}
+
+/* --------------------------------- Ordinal -------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum Ordinal {
+    NorthEast,
+    SouthEast,
+    SouthWest,
+    NorthWest
+}
+
+impl Ordinal {
+    pub const ALL: [Ordinal; 4] = [Ordinal::NorthEast, Ordinal::SouthEast, Ordinal::SouthWest, Ordinal::NorthWest];
+
+    pub fn index(self) -> usize {
+        match self {
+            Ordinal::NorthEast => 0,
+            Ordinal::SouthEast => 1,
+            Ordinal::SouthWest => 2,
+            Ordinal::NorthWest => 3
+        }
+    }
+
+    pub fn reverse(self) -> Ordinal {
+        Ordinal::ALL[(self.index() + 2) % 4]
+    }
+
+    // The cardinal directions on either side of this ordinal direction, i.e.
+    // the result of turning it an eighth turn to the left or to the right.
+
+    pub fn left(self) -> Direction {
+        Direction::ALL[self.index()]
+    }
+
+    pub fn right(self) -> Direction {
+        Direction::ALL[(self.index() + 1) % 4]
+    }
+
+    pub fn offset(self) -> Pos {
+        self.left().offset() + self.right().offset()
+    }
+
+    pub fn step(self, pos: Pos) -> Pos {
+        pos + self.offset()
+    }
+}
+
+/* -------------------------------- Distance -------------------------------- */
+
+pub fn manhattan(a: Pos, b: Pos) -> usize {
+    a.row.abs_diff(b.row) + a.col.abs_diff(b.col)
+}
+
+/* ---------------------------------- Tests --------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turn_follows_left_and_right() {
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn(Turn::Left), dir.left());
+            assert_eq!(dir.turn(Turn::Right), dir.right());
+        }
+
+        assert_eq!(Direction::North.turn(Turn::Right), Direction::East);
+        assert_eq!(Direction::North.turn(Turn::Left), Direction::West);
+    }
+
+    #[test]
+    fn is_horizontal_for_east_and_west() {
+        let horizontal: Vec<Direction> = Direction::ALL.into_iter().filter(|dir| dir.is_horizontal()).collect();
+        assert_eq!(horizontal, vec![Direction::East, Direction::West]);
+    }
+
+    #[test]
+    fn step_by_moves_several_steps() {
+        let pos: Pos = Pos::new(2, 3);
+        assert_eq!(Direction::North.step_by(pos, 2), Pos::new(0, 3));
+        assert_eq!(Direction::West.step_by(pos, 5), Pos::new(2, -2));
+        assert_eq!(Direction::South.step_by(pos, 1), Direction::South.step(pos));
+        assert_eq!(Direction::East.step_by(pos, 0), pos);
+    }
+
+    #[test]
+    fn parsers_accept_only_their_notation() {
+        assert_eq!(Direction::from_compass('N'), Some(Direction::North));
+        assert_eq!(Direction::from_compass('W'), Some(Direction::West));
+        assert_eq!(Direction::from_compass('U'), None);
+        assert_eq!(Direction::from_letter('N'), None);
+    }
+
+    #[test]
+    fn parse_accepts_every_notation() {
+        for (notation, expected) in [("URDL", Direction::ALL), ("NESW", Direction::ALL), ("^>v<", Direction::ALL)] {
+            let parsed: Vec<Option<Direction>> = notation.chars().map(Direction::parse).collect();
+            assert_eq!(parsed, expected.map(Some).to_vec());
+        }
+
+        assert_eq!(Direction::parse('x'), None);
+    }
+
+    #[test]
+    fn ordinal_lies_between_cardinals() {
+        assert_eq!(Ordinal::NorthEast.left(), Direction::North);
+        assert_eq!(Ordinal::NorthEast.right(), Direction::East);
+        assert_eq!(Ordinal::NorthWest.left(), Direction::West);
+        assert_eq!(Ordinal::NorthWest.right(), Direction::North);
+        assert_eq!(Ordinal::SouthWest.step(Pos::new(0, 0)), Pos::new(1, -1));
+
+        for ordinal in Ordinal::ALL {
+            assert_eq!(Ordinal::ALL[ordinal.index()], ordinal);
+            assert_eq!(ordinal.reverse().reverse(), ordinal);
+            assert_eq!(ordinal.offset() + ordinal.reverse().offset(), Pos::new(0, 0));
+        }
+    }
+}
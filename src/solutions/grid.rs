@@ -0,0 +1,343 @@
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Sub};
+
+use crate::solutions::parse::{self, ParseResult};
+
+// Shared two-dimensional grid for all days whose input is a grid of characters.
+// Tiles are stored in row-major order in a single vector, and are addressed by
+// a `Pos`, which holds a zero-based row and column. Coordinates are signed, so
+// that positions just outside of the grid (e.g. the position from which a beam
+// enters the grid) can be represented as well; `contains()` checks whether a
+// position lies inside the grid, and `get()` returns `None` for positions that
+// do not. Indexing a grid with a position outside of it panics.
+//
+// Besides the grid itself, a `View` presents a grid either as-is or transposed
+// (with rows and columns swapped) without copying any tiles, which allows the
+// same algorithm to be applied to both the rows and the columns of a grid.
+
+/* ----------------------------------- Pos ---------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Pos {
+    pub row: isize,
+    pub col: isize
+}
+
+// Offsets of the four orthogonal neighbors (clockwise, starting north), and of
+// all eight neighbors including the diagonal ones (in row-major order).
+
+const NEIGHBORS_4: [Pos; 4] = [Pos::new(-1, 0), Pos::new(0, 1), Pos::new(1, 0), Pos::new(0, -1)];
+
+const NEIGHBORS_8: [Pos; 8] = [Pos::new(-1, -1), Pos::new(-1,  0), Pos::new(-1,  1),
+                               Pos::new( 0, -1),                   Pos::new( 0,  1),
+                               Pos::new( 1, -1), Pos::new( 1,  0), Pos::new( 1,  1)];
+
+impl Pos {
+    pub const fn new(row: isize, col: isize) -> Pos {
+        Pos { row, col }
+    }
+
+    pub fn neighbors(self) -> impl Iterator<Item = Pos> {
+        NEIGHBORS_4.into_iter().map(move |offset| self + offset)
+    }
+
+    pub fn neighbors8(self) -> impl Iterator<Item = Pos> {
+        NEIGHBORS_8.into_iter().map(move |offset| self + offset)
+    }
+
+    fn transposed(self) -> Pos {
+        Pos { row: self.col, col: self.row }
+    }
+}
+
+impl Add for Pos {
+    type Output = Pos;
+
+    fn add(self, other: Pos) -> Pos {
+        Pos { row: self.row + other.row, col: self.col + other.col }
+    }
+}
+
+impl Sub for Pos {
+    type Output = Pos;
+
+    fn sub(self, other: Pos) -> Pos {
+        Pos { row: self.row - other.row, col: self.col - other.col }
+    }
+}
+
+impl fmt::Display for Pos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+/* ---------------------------------- Grid ---------------------------------- */
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Grid<T> {
+    tiles: Vec<T>,
+    pub rows: isize,
+    pub cols: isize
+}
+
+impl<T> Grid<T> {
+    pub fn new(tiles: Vec<T>, rows: usize, cols: usize) -> Grid<T> {
+        if tiles.len() != rows * cols {
+            panic!("Grid of {rows} by {cols} cannot contain {} tiles", tiles.len());
+        }
+
+        Grid { tiles, rows: rows as isize, cols: cols as isize }
+    }
+
+    pub fn filled(rows: isize, cols: isize, tile: T) -> Grid<T> where T: Clone {
+        Grid::new(vec![tile; (rows * cols) as usize], rows as usize, cols as usize)
+    }
+
+    // Parses a grid from the given lines, mapping every character to a tile;
+    // see `parse::grid()` for the meaning of the arguments.
+
+    pub fn parse<F: Fn(char) -> Option<T>>(lines: &[String], offset: usize, expected: &str, map: F) -> ParseResult<Grid<T>> {
+        let (tiles, rows, cols) = parse::grid(lines, offset, expected, map)?;
+        Ok(Grid::new(tiles, rows, cols))
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.row >= 0 && pos.row < self.rows && pos.col >= 0 && pos.col < self.cols
+    }
+
+    pub fn to_index(&self, pos: Pos) -> usize {
+        (pos.row * self.cols + pos.col) as usize
+    }
+
+    pub fn to_pos(&self, index: usize) -> Pos {
+        Pos::new(index as isize / self.cols, index as isize % self.cols)
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.contains(pos) { Some(&self.tiles[self.to_index(pos)]) } else { None }
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            let index: usize = self.to_index(pos);
+            Some(&mut self.tiles[index])
+        } else {
+            None
+        }
+    }
+
+    pub fn tiles(&self) -> &[T] {
+        &self.tiles
+    }
+
+    pub fn tiles_mut(&mut self) -> &mut [T] {
+        &mut self.tiles
+    }
+
+    // All positions in the grid, in row-major order.
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let cols: isize = self.cols;
+        (0 .. self.rows).flat_map(move |row| (0 .. cols).map(move |col| Pos::new(row, col)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.tiles.iter())
+    }
+
+    pub fn find<P: Fn(&T) -> bool>(&self, predicate: P) -> Option<Pos> {
+        self.tiles.iter().position(predicate).map(|index| self.to_pos(index))
+    }
+
+    // The orthogonal (or all eight) neighbors of a position that lie inside the grid.
+
+    pub fn neighbors(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        pos.neighbors().filter(|neighbor| self.contains(*neighbor))
+    }
+
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        pos.neighbors8().filter(|neighbor| self.contains(*neighbor))
+    }
+
+    pub fn row(&self, row: isize) -> &[T] {
+        let start: usize = self.to_index(Pos::new(row, 0));
+        &self.tiles[start .. start + self.cols as usize]
+    }
+
+    pub fn column(&self, col: isize) -> impl Iterator<Item = &T> {
+        self.tiles.iter().skip(col as usize).step_by(self.cols as usize)
+    }
+
+    pub fn view(&self) -> View<'_, T> {
+        View { grid: self, transposed: false }
+    }
+
+    pub fn transposed(&self) -> View<'_, T> {
+        View { grid: self, transposed: true }
+    }
+
+    // Returns a copy of the grid rotated by a quarter turn; for a clockwise
+    // rotation, the first column (read from bottom to top) becomes the first row.
+
+    pub fn rotate_clockwise(&self) -> Grid<T> where T: Clone {
+        let tiles: Vec<T> = (0 .. self.cols)
+            .flat_map(|col| (0 .. self.rows).rev().map(move |row| Pos::new(row, col)))
+            .map(|pos| self[pos].clone())
+            .collect();
+
+        Grid::new(tiles, self.cols as usize, self.rows as usize)
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Grid<T> where T: Clone {
+        let tiles: Vec<T> = (0 .. self.cols).rev()
+            .flat_map(|col| (0 .. self.rows).map(move |row| Pos::new(row, col)))
+            .map(|pos| self[pos].clone())
+            .collect();
+
+        Grid::new(tiles, self.cols as usize, self.rows as usize)
+    }
+
+    // Renders the grid as one line of text per row, mapping every tile to a
+    // character; mostly useful for debugging.
+
+    pub fn render<F: Fn(&T) -> char>(&self, map: F) -> String {
+        (0 .. self.rows)
+            .map(|row| self.row(row).iter().map(&map).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.get(pos) {
+            Some(tile) => tile,
+            None => panic!("Position {pos} is outside of the {} by {} grid", self.rows, self.cols)
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (rows, cols) = (self.rows, self.cols);
+
+        match self.get_mut(pos) {
+            Some(tile) => tile,
+            None => panic!("Position {pos} is outside of the {} by {} grid", rows, cols)
+        }
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(|c| *c))
+    }
+}
+
+/* ---------------------------------- View ---------------------------------- */
+
+pub struct View<'a, T> {
+    grid: &'a Grid<T>,
+    transposed: bool
+}
+
+// Implemented manually, since deriving would require `T` itself to be `Copy`.
+
+impl<T> Clone for View<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for View<'_, T> {}
+
+impl<'a, T> View<'a, T> {
+    fn grid_pos(&self, pos: Pos) -> Pos {
+        if self.transposed { pos.transposed() } else { pos }
+    }
+
+    pub fn rows(&self) -> isize {
+        if self.transposed { self.grid.cols } else { self.grid.rows }
+    }
+
+    pub fn cols(&self) -> isize {
+        if self.transposed { self.grid.rows } else { self.grid.cols }
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.grid.contains(self.grid_pos(pos))
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&'a T> {
+        self.grid.get(self.grid_pos(pos))
+    }
+
+    pub fn row(&self, row: isize) -> impl Iterator<Item = &'a T> {
+        let view: View<'a, T> = *self;
+        (0 .. view.cols()).map(move |col| &view.grid[view.grid_pos(Pos::new(row, col))])
+    }
+}
+
+impl<T> Index<Pos> for View<'_, T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        &self.grid[self.grid_pos(pos)]
+    }
+}
+
+/* ---------------------------------- Tests --------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1 2 3
+    // 4 5 6
+
+    fn sample() -> Grid<u8> {
+        Grid::new(vec![1, 2, 3, 4, 5, 6], 2, 3)
+    }
+
+    #[test]
+    fn tiles_mut_changes_tiles() {
+        let mut grid: Grid<u8> = sample();
+        grid.tiles_mut().iter_mut().for_each(|tile| *tile *= 10);
+        assert_eq!(grid.tiles(), &[10, 20, 30, 40, 50, 60]);
+    }
+
+    #[test]
+    fn column_reads_top_to_bottom() {
+        let grid: Grid<u8> = sample();
+        assert_eq!(grid.column(0).copied().collect::<Vec<u8>>(), vec![1, 4]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<u8>>(), vec![3, 6]);
+    }
+
+    #[test]
+    fn rotations_are_inverse() {
+        let grid: Grid<u8> = sample();
+        let clockwise: Grid<u8> = grid.rotate_clockwise();
+        let counterclockwise: Grid<u8> = grid.rotate_counterclockwise();
+
+        assert_eq!((clockwise.rows, clockwise.cols), (3, 2));
+        assert_eq!(clockwise.tiles(), &[4, 1, 5, 2, 6, 3]);
+        assert_eq!(counterclockwise.tiles(), &[3, 6, 2, 5, 1, 4]);
+        assert_eq!(clockwise.rotate_counterclockwise(), grid);
+        assert_eq!(clockwise.rotate_clockwise().rotate_clockwise(), counterclockwise);
+    }
+
+    #[test]
+    fn transposed_view_swaps_rows_and_columns() {
+        let grid: Grid<u8> = sample();
+        let view: View<u8> = grid.transposed();
+
+        assert_eq!((view.rows(), view.cols()), (3, 2));
+        assert_eq!(view.get(Pos::new(2, 1)), Some(&6));
+        assert_eq!(view.get(Pos::new(1, 2)), None);
+        assert_eq!(view.row(1).copied().collect::<Vec<u8>>(), vec![2, 5]);
+        assert_eq!(grid.view().row(1).copied().collect::<Vec<u8>>(), vec![4, 5, 6]);
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt;
+
+use crate::solutions::rational::Rational;
+
+// Exact linear algebra on small systems of equations, using Gauss-Jordan
+// elimination on `Rational` values. A matrix is a list of rows of equal length.
+// Elimination brings a matrix into its reduced row echelon form, from which the
+// solution of a system (or the set of all solutions of a homogeneous system)
+// can be read off directly.
+//
+// Rational values grow quickly during elimination, so all arithmetic is checked,
+// and a value that does not fit is reported as an `Overflow` error instead of a
+// panic. Callers that can choose which equations to use can then try again with
+// different ones, which is why it pays to keep the coefficients small (e.g. by
+// moving the origin close to the values involved).
+
+/* ---------------------------------- Error --------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LinearError {
+    Inconsistent,
+    Underdetermined,
+    Overflow
+}
+
+impl fmt::Display for LinearError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LinearError::Inconsistent    => write!(f, "the equations have no solution"),
+            LinearError::Underdetermined => write!(f, "the equations have more than one solution"),
+            LinearError::Overflow        => write!(f, "the values do not fit in 128 bits")
+        }
+    }
+}
+
+/* ------------------------------- Elimination ------------------------------ */
+
+fn checked(value: Option<Rational>) -> Result<Rational, LinearError> {
+    value.ok_or(LinearError::Overflow)
+}
+
+// Brings the matrix into reduced row echelon form, using only the first
+// `columns` columns as pivot columns (so that the last column of an augmented
+// matrix is left alone). Returns the pivot column of every non-zero row.
+
+pub fn row_reduce(matrix: &mut [Vec<Rational>], columns: usize) -> Result<Vec<usize>, LinearError> {
+    let mut pivots: Vec<usize> = Vec::new();
+
+    for col in 0 .. columns {
+        let row: usize = pivots.len();
+
+        let Some(pivot) = (row .. matrix.len()).find(|r| matrix[*r][col] != Rational::ZERO) else {
+            continue;
+        };
+
+        matrix.swap(row, pivot);
+
+        let factor: Rational = matrix[row][col];
+
+        for value in matrix[row].iter_mut() {
+            *value = checked(value.checked_div(&factor))?;
+        }
+
+        let pivot_row: Vec<Rational> = matrix[row].clone();
+
+        for (other, values) in matrix.iter_mut().enumerate() {
+            let factor: Rational = values[col];
+
+            if other == row || factor == Rational::ZERO {
+                continue;
+            }
+
+            for (value, pivot_value) in values.iter_mut().zip(&pivot_row) {
+                *value = checked(value.checked_sub(&checked(factor.checked_mul(pivot_value))?))?;
+            }
+        }
+
+        pivots.push(col);
+
+        if pivots.len() == matrix.len() {
+            break;
+        }
+    }
+
+    Ok(pivots)
+}
+
+// Solves `coefficients * x = constants` for `x`, if the system has exactly
+// one solution.
+
+pub fn solve(coefficients: &[Vec<Rational>], constants: &[Rational]) -> Result<Vec<Rational>, LinearError> {
+    let columns: usize = coefficients.first().map_or(0, |row| row.len());
+
+    let mut matrix: Vec<Vec<Rational>> = coefficients.iter().zip(constants)
+        .map(|(row, constant)| row.iter().copied().chain([*constant]).collect())
+        .collect();
+
+    let pivots: Vec<usize> = row_reduce(&mut matrix, columns)?;
+
+    if matrix[pivots.len() ..].iter().any(|row| row[columns] != Rational::ZERO) {
+        return Err(LinearError::Inconsistent);
+    }
+
+    if pivots.len() < columns {
+        return Err(LinearError::Underdetermined);
+    }
+
+    Ok(matrix.iter().take(columns).map(|row| row[columns]).collect())
+}
+
+// Returns a basis for the solutions of `matrix * x = 0`, with one vector for
+// every column without a pivot; the basis is empty if `x = 0` is the only
+// solution.
+
+pub fn null_space(matrix: &[Vec<Rational>]) -> Result<Vec<Vec<Rational>>, LinearError> {
+    let columns: usize = matrix.first().map_or(0, |row| row.len());
+    let mut matrix: Vec<Vec<Rational>> = matrix.to_vec();
+    let pivots: Vec<usize> = row_reduce(&mut matrix, columns)?;
+
+    (0 .. columns).filter(|col| !pivots.contains(col)).map(|free| {
+        let mut vector: Vec<Rational> = vec![Rational::ZERO; columns];
+        vector[free] = Rational::ONE;
+
+        for (row, pivot) in pivots.iter().enumerate() {
+            vector[*pivot] = checked(matrix[row][free].checked_neg())?;
+        }
+
+        Ok(vector)
+    }).collect()
+}
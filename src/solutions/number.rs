@@ -0,0 +1,352 @@
+use std::fmt;
+
+// Number theory shared by the days that combine cycles of different lengths,
+// i.e. days whose answer is the first step at which several independent loops
+// line up. All arithmetic is done on `i128` values and is overflow-checked, so
+// that an answer that does not fit is reported as such instead of silently
+// wrapping around; functions that can overflow therefore return an `Option` or
+// a `Result`.
+//
+// The central piece is `crt()`, a generalized version of the Chinese Remainder
+// Theorem: given a system of congruences "x = r (mod m)", it finds the single
+// congruence that describes all of their common solutions. Unlike the textbook
+// version, the moduli do not need to be coprime; for example, "x = 2 (mod 4)"
+// and "x = 4 (mod 6)" combine into "x = 10 (mod 12)", while "x = 1 (mod 4)"
+// and "x = 2 (mod 6)" have no common solution at all.
+//
+// When each loop can line up in several ways (e.g. a loop that passes several
+// end nodes), `combine_all()` picks one congruence per loop in every possible
+// way; the number of combinations grows quickly, so it is capped.
+
+pub const MAX_COMBINATIONS: usize = 1 << 16;
+
+/* ---------------------------------- Error --------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CrtError {
+    NoSolution,
+    Overflow,
+    TooManyCombinations
+}
+
+impl fmt::Display for CrtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CrtError::NoSolution          => write!(f, "the congruences have no common solution"),
+            CrtError::Overflow            => write!(f, "the combined modulus does not fit in 128 bits"),
+            CrtError::TooManyCombinations => write!(f, "there are more than {MAX_COMBINATIONS} combinations of congruences")
+        }
+    }
+}
+
+/* --------------------------------- Divisors ------------------------------- */
+
+// The greatest common divisor of two numbers, which is never negative; the GCD
+// of zero and zero is zero. The magnitudes are taken as unsigned values, since
+// `i128::MIN` has no positive counterpart; the only GCD that does not fit (of
+// `i128::MIN` with itself or with zero) panics.
+
+pub fn gcd(a: i128, b: i128) -> i128 {
+    let mut a: u128 = a.unsigned_abs();
+    let mut b: u128 = b.unsigned_abs();
+
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    match i128::try_from(a) {
+        Ok(divisor) => divisor,
+        Err(_) => panic!("GCD of {a} does not fit in 128 bits")
+    }
+}
+
+// The least common multiple of two numbers, or `None` if it does not fit; the
+// LCM of zero and any other number is zero.
+
+pub fn lcm(a: i128, b: i128) -> Option<i128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+
+    (a / gcd(a, b)).checked_mul(b).map(|value| value.abs())
+}
+
+// The least common multiple of all given numbers (one if there are none).
+
+pub fn lcm_all<I: IntoIterator<Item = i128>>(values: I) -> Option<i128> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+// Returns `(g, x, y)` such that `a * x + b * y = g`, where `g` is the GCD of
+// `a` and `b`.
+
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r): (i128, i128) = (a, b);
+    let (mut old_x, mut x): (i128, i128) = (1, 0);
+    let (mut old_y, mut y): (i128, i128) = (0, 1);
+
+    while r != 0 {
+        let quotient: i128 = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        return (-old_r, -old_x, -old_y)
+    }
+
+    (old_r, old_x, old_y)
+}
+
+// The inverse of `a` modulo `modulus`, in the range `[0, modulus)`, or `None`
+// if `a` and `modulus` are not coprime.
+
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/* ---------------------------------- Roots --------------------------------- */
+
+// The integer square root, i.e. the largest number whose square is at most the
+// given number, or `None` for negative numbers. Newton's method, starting above
+// the root, decreases monotonically towards it, and stops at exactly the root
+// when working with integer division.
+
+pub fn isqrt(n: i128) -> Option<i128> {
+    if n < 0 {
+        return None;
+    }
+
+    if n < 2 {
+        return Some(n);
+    }
+
+    let mut x: i128 = 1 << ((128 - n.leading_zeros()).div_ceil(2));
+
+    loop {
+        let y: i128 = (x + n / x) / 2;
+
+        if y >= x {
+            return Some(x);
+        }
+
+        x = y;
+    }
+}
+
+/* ------------------------------- Congruence ------------------------------- */
+
+// The congruence "x = residue (mod modulus)", i.e. all numbers that leave the
+// given remainder when divided by the modulus. The modulus is always positive,
+// and the residue is always in the range `[0, modulus)`.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Congruence {
+    pub residue: i128,
+    pub modulus: i128
+}
+
+impl Congruence {
+    pub fn new(residue: i128, modulus: i128) -> Congruence {
+        if modulus <= 0 {
+            panic!("Modulus of a congruence must be positive, got {modulus}");
+        }
+
+        Congruence { residue: residue.rem_euclid(modulus), modulus }
+    }
+
+    // Combines two congruences into one that holds exactly when both do. Since
+    // "x = r1 (mod m1)" means that "x = r1 + k * m1" for some k, we need a k for
+    // which "k * m1 = r2 - r1 (mod m2)". With g the GCD of both moduli, this only
+    // has a solution if g divides "r2 - r1"; if so, dividing everything by g
+    // leaves us with coprime moduli, so that k follows from a modular inverse.
+    // All intermediate values stay below the combined modulus, which is the LCM
+    // of both moduli.
+
+    pub fn combine(&self, other: &Congruence) -> Result<Congruence, CrtError> {
+        let g: i128 = gcd(self.modulus, other.modulus);
+        let diff: i128 = other.residue - self.residue;
+
+        if diff % g != 0 {
+            return Err(CrtError::NoSolution);
+        }
+
+        let reduced_modulus: i128 = other.modulus / g;
+        let modulus: i128 = (self.modulus / g).checked_mul(other.modulus).ok_or(CrtError::Overflow)?;
+
+        let inverse: i128 = mod_inverse(self.modulus / g, reduced_modulus).unwrap();
+        let k: i128 = (diff / g).rem_euclid(reduced_modulus)
+            .checked_mul(inverse).ok_or(CrtError::Overflow)?
+            .rem_euclid(reduced_modulus);
+
+        let offset: i128 = self.modulus.checked_mul(k).ok_or(CrtError::Overflow)?;
+        let residue: i128 = self.residue.checked_add(offset).ok_or(CrtError::Overflow)?;
+
+        Ok(Congruence::new(residue, modulus))
+    }
+}
+
+impl fmt::Display for Congruence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "x = {} (mod {})", self.residue, self.modulus)
+    }
+}
+
+/* ----------------------------------- CRT ---------------------------------- */
+
+// Combines all given congruences into a single one; see the top of this file.
+// An empty system is solved by every number, i.e. by "x = 0 (mod 1)".
+
+pub fn crt<I: IntoIterator<Item = Congruence>>(congruences: I) -> Result<Congruence, CrtError> {
+    congruences.into_iter().try_fold(Congruence::new(0, 1), |combined, congruence| {
+        combined.combine(&congruence)
+    })
+}
+
+// Combines one congruence of every group in every possible way, and returns
+// the distinct combinations that have a solution (none if no combination has
+// one). Fails if a step would produce more than `MAX_COMBINATIONS` of them.
+
+pub fn combine_all(groups: &[Vec<Congruence>]) -> Result<Vec<Congruence>, CrtError> {
+    let mut combined: Vec<Congruence> = vec!(Congruence::new(0, 1));
+
+    for group in groups {
+        if combined.len().saturating_mul(group.len()) > MAX_COMBINATIONS {
+            return Err(CrtError::TooManyCombinations);
+        }
+
+        let mut next: Vec<Congruence> = Vec::new();
+
+        for a in &combined {
+            for b in group {
+                match a.combine(b) {
+                    Ok(congruence) => next.push(congruence),
+                    Err(CrtError::NoSolution) => (),
+                    Err(error) => return Err(error)
+                }
+            }
+        }
+
+        next.sort_by_key(|congruence| (congruence.modulus, congruence.residue));
+        next.dedup();
+        combined = next;
+    }
+
+    Ok(combined)
+}
+
+/* ---------------------------------- Tests --------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_is_never_negative() {
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(0, -5), 5);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(i128::MIN, 6), 2);
+    }
+
+    #[test]
+    fn lcm_of_pairs_and_lists() {
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(-4, 6), Some(12));
+        assert_eq!(lcm(0, 7), Some(0));
+        assert_eq!(lcm(i128::MAX, i128::MAX - 1), None);
+        assert_eq!(lcm_all([2, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all([]), Some(1));
+    }
+
+    #[test]
+    fn extended_gcd_finds_coefficients() {
+        for (a, b) in [(240, 46), (46, 240), (17, 5), (0, 9), (-12, 18)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g.abs(), gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn mod_inverse_only_for_coprime() {
+        assert_eq!(mod_inverse(3, 7), Some(5));
+        assert_eq!(mod_inverse(-3, 7), Some(2));
+        assert_eq!(mod_inverse(4, 6), None);
+    }
+
+    #[test]
+    fn crt_combines_systems() {
+        let system: Vec<Congruence> = vec![Congruence::new(2, 3), Congruence::new(3, 5), Congruence::new(2, 7)];
+        assert_eq!(crt(system), Ok(Congruence::new(23, 105)));
+        assert_eq!(crt([Congruence::new(2, 4), Congruence::new(4, 6)]), Ok(Congruence::new(10, 12)));
+        assert_eq!(crt([Congruence::new(1, 4), Congruence::new(2, 6)]), Err(CrtError::NoSolution));
+        assert_eq!(crt([]), Ok(Congruence::new(0, 1)));
+    }
+
+    #[test]
+    fn combine_coprime_moduli() {
+        let combined = Congruence::new(2, 3).combine(&Congruence::new(3, 5));
+        assert_eq!(combined, Ok(Congruence::new(8, 15)));
+    }
+
+    #[test]
+    fn combine_non_coprime_moduli() {
+        let combined = Congruence::new(2, 4).combine(&Congruence::new(4, 6));
+        assert_eq!(combined, Ok(Congruence::new(10, 12)));
+    }
+
+    #[test]
+    fn combine_without_solution() {
+        let combined = Congruence::new(1, 4).combine(&Congruence::new(2, 6));
+        assert_eq!(combined, Err(CrtError::NoSolution));
+    }
+
+    #[test]
+    fn combine_reports_overflow() {
+        let combined = Congruence::new(0, i128::MAX).combine(&Congruence::new(1, i128::MAX - 1));
+        assert_eq!(combined, Err(CrtError::Overflow));
+    }
+
+    #[test]
+    fn combine_all_keeps_combinations_with_solution() {
+        let groups: Vec<Vec<Congruence>> = vec![
+            vec![Congruence::new(0, 2)],
+            vec![Congruence::new(1, 3), Congruence::new(2, 3)],
+            vec![Congruence::new(0, 4), Congruence::new(1, 4)]
+        ];
+
+        assert_eq!(combine_all(&groups), Ok(vec![Congruence::new(4, 12), Congruence::new(8, 12)]));
+        assert_eq!(combine_all(&[]), Ok(vec![Congruence::new(0, 1)]));
+    }
+
+    #[test]
+    fn combine_all_is_capped() {
+        let group: Vec<Congruence> = (0 .. 512).map(|residue| Congruence::new(residue, 1024)).collect();
+        assert_eq!(combine_all(&[group.clone(), group]), Err(CrtError::TooManyCombinations));
+    }
+
+    #[test]
+    fn isqrt_around_squares() {
+        assert_eq!(isqrt(-1), None);
+        assert_eq!(isqrt(0), Some(0));
+        assert_eq!(isqrt(1), Some(1));
+
+        for root in [2, 3, 10, 1_000_003, 3_037_000_499, 1 << 40] {
+            let square: i128 = root * root;
+            assert_eq!(isqrt(square - 1), Some(root - 1));
+            assert_eq!(isqrt(square), Some(root));
+            assert_eq!(isqrt(square + 1), Some(root));
+        }
+    }
+
+    #[test]
+    fn isqrt_near_max() {
+        let root: i128 = 13043817825332782212;
+        assert_eq!(isqrt(i128::MAX), Some(root));
+        assert_eq!(isqrt(root * root), Some(root));
+        assert_eq!(isqrt(root * root - 1), Some(root - 1));
+    }
+}
@@ -0,0 +1,218 @@
+use std::fmt;
+use std::str::FromStr;
+
+// Shared error type for the parse phase of all solvers. A parse error points at
+// a line of the input (one-based), and optionally at a column within that line
+// (also one-based), and describes what the parser expected to find there as
+// well as what it actually found. The day is filled in by the registry, since
+// the parsers themselves do not know which day they belong to.
+//
+// Most parsers process the input line by line; `Line` wraps a single line with
+// its line number, and provides a couple of fallible versions of the string
+// operations used by the parsers (splitting, parsing numbers, etc.) that
+// produce a `ParseError` pointing at the offending part of the line.
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/* ------------------------------- ParseError ------------------------------- */
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub day: Option<u8>,
+    pub line: usize,
+    pub column: Option<usize>,
+    pub expected: String,
+    pub found: String
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: Option<usize>, expected: &str, found: &str) -> ParseError {
+        ParseError { day: None, line, column, expected: String::from(expected), found: String::from(found) }
+    }
+
+    // An error that concerns the input as a whole rather than a specific line,
+    // e.g. a missing section; it points at the line after the last line.
+
+    pub fn input(lines: &[String], expected: &str, found: &str) -> ParseError {
+        ParseError::new(lines.len() + 1, None, expected, found)
+    }
+
+    pub fn with_day(self, day: u8) -> ParseError {
+        ParseError { day: Some(day), ..self }
+    }
+
+    // Renders the error along with the offending line of the input, and a row
+    // of carets under the part of the line that could not be parsed.
+
+    pub fn render(&self, lines: &Vec<String>) -> String {
+        let mut result: String = self.to_string();
+
+        if let Some(text) = lines.get(self.line - 1) {
+            let gutter: String = " ".repeat(self.line.to_string().len());
+            result.push_str(&format!("\n {} | {}", self.line, text));
+
+            if let Some(column) = self.column {
+                let width: usize = self.found.chars().count().max(1);
+                result.push_str(&format!("\n {} | {}{}", gutter, " ".repeat(column - 1), "^".repeat(width)));
+            }
+        }
+
+        result
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Parse error")?;
+
+        if let Some(day) = self.day {
+            write!(f, " in day {:02}", day)?;
+        }
+
+        write!(f, " at line {}", self.line)?;
+
+        if let Some(column) = self.column {
+            write!(f, ", column {}", column)?;
+        }
+
+        let found: String = if self.found.is_empty() { String::from("nothing") } else { format!("'{}'", self.found) };
+        write!(f, ": expected {}, found {}", self.expected, found)
+    }
+}
+
+/* ---------------------------------- Line ---------------------------------- */
+
+#[derive(Clone, Copy)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str
+}
+
+impl<'a> Line<'a> {
+    pub fn new(index: usize, text: &'a str) -> Line<'a> {
+        Line { number: index + 1, text }
+    }
+
+    // Returns the one-based column of `part`, which should be a slice of the
+    // text of this line; for any other string, the end of the line is used.
+
+    fn column_of(&self, part: &str) -> usize {
+        let start: usize = self.text.as_ptr() as usize;
+        let offset: usize = (part.as_ptr() as usize).wrapping_sub(start);
+
+        if offset <= self.text.len() {
+            self.text[.. offset].chars().count() + 1
+        } else {
+            self.text.chars().count() + 1
+        }
+    }
+
+    pub fn error(&self, part: &str, expected: &str) -> ParseError {
+        ParseError::new(self.number, Some(self.column_of(part)), expected, part)
+    }
+
+    pub fn split_once(&self, part: &'a str, separator: &str) -> ParseResult<(&'a str, &'a str)> {
+        match part.split_once(separator) {
+            Some(fields) => Ok(fields),
+            None => Err(self.error(part, &format!("'{}'", separator)))
+        }
+    }
+
+    pub fn strip_prefix(&self, part: &'a str, prefix: &str) -> ParseResult<&'a str> {
+        match part.strip_prefix(prefix) {
+            Some(rest) => Ok(rest),
+            None => Err(self.error(part, &format!("'{}'", prefix)))
+        }
+    }
+
+    pub fn strip_suffix(&self, part: &'a str, suffix: &str) -> ParseResult<&'a str> {
+        match part.strip_suffix(suffix) {
+            Some(rest) => Ok(rest),
+            None => Err(self.error(part, &format!("'{}' at the end", suffix)))
+        }
+    }
+
+    pub fn number<T: FromStr>(&self, part: &'a str) -> ParseResult<T> {
+        match part.parse() {
+            Ok(value) => Ok(value),
+            Err(_) => Err(self.error(part, "a number"))
+        }
+    }
+
+    // Parses all fields separated by `separator` as numbers, skipping empty
+    // fields (so that numbers separated by multiple spaces are supported).
+
+    pub fn numbers<T: FromStr>(&self, part: &'a str, separator: char) -> ParseResult<Vec<T>> {
+        part.split(separator)
+            .filter(|field| !field.is_empty())
+            .map(|field| self.number(field))
+            .collect()
+    }
+
+    // Parses exactly `count` separated numbers.
+
+    pub fn fixed_numbers<T: FromStr>(&self, part: &'a str, separator: &str, count: usize) -> ParseResult<Vec<T>> {
+        let fields: Vec<&str> = part.split(separator).collect();
+
+        if fields.len() != count {
+            return Err(self.error(part, &format!("{} numbers separated by '{}'", count, separator)));
+        }
+
+        fields.into_iter().map(|field| self.number(field.trim())).collect()
+    }
+
+    // Returns the one-character slice of the line at the given (zero-based) char
+    // index, which is useful for reporting errors about a single character.
+
+    pub fn char_at(&self, index: usize) -> &'a str {
+        match self.text.char_indices().nth(index) {
+            Some((start, c)) => &self.text[start .. start + c.len_utf8()],
+            None => &self.text[self.text.len() ..]
+        }
+    }
+}
+
+pub fn lines(lines: &[String]) -> impl Iterator<Item = Line<'_>> {
+    lines.iter().enumerate().map(|(index, text)| Line::new(index, text))
+}
+
+// Returns the line at the given (zero-based) index, for inputs with a fixed
+// structure; `expected` describes the contents of the line if it is missing.
+
+pub fn line<'a>(lines: &'a [String], index: usize, expected: &str) -> ParseResult<Line<'a>> {
+    match lines.get(index) {
+        Some(text) => Ok(Line::new(index, text)),
+        None => Err(ParseError::input(lines, expected, ""))
+    }
+}
+
+// Parses a rectangular grid of characters, mapping each character to a tile,
+// and checking that all rows have the same length. Returns the tiles in row-
+// major order, and the number of rows and columns. `offset` is the index of
+// the first line of the grid in the input, for correct line numbers.
+
+pub fn grid<T, F: Fn(char) -> Option<T>>(rows: &[String], offset: usize, expected: &str, map: F) -> ParseResult<(Vec<T>, usize, usize)> {
+    let cols: usize = match rows.first() {
+        Some(row) => row.chars().count(),
+        None => return Err(ParseError::new(offset + 1, None, "a grid", ""))
+    };
+
+    let mut tiles: Vec<T> = Vec::with_capacity(rows.len() * cols);
+
+    for (index, row) in rows.iter().enumerate() {
+        let line: Line = Line::new(offset + index, row);
+
+        for (col, c) in row.chars().enumerate() {
+            match map(c) {
+                Some(tile) => tiles.push(tile),
+                None => return Err(line.error(line.char_at(col), expected))
+            }
+        }
+
+        if row.chars().count() != cols {
+            return Err(line.error(line.char_at(cols.min(row.chars().count())), &format!("a row of {} characters", cols)));
+        }
+    }
+
+    Ok((tiles, rows.len(), cols))
+}
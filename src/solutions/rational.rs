@@ -0,0 +1,248 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::solutions::number::gcd;
+
+// Exact fractions for days that would otherwise need floating point numbers,
+// which can produce slightly wrong answers once the input values get large
+// enough (e.g. intersections that land just outside of an area). A `Rational`
+// is always kept in its normalized form: the denominator is positive, and the
+// numerator and denominator have no common divisor, so that two equal values
+// also have equal fields.
+//
+// Values are stored as `i128`. The arithmetic operators panic when a result
+// does not fit, rather than silently wrapping around in release builds; the
+// `checked_*` methods return `None` instead. Comparison cross-multiplies both
+// values, and panics in the same way if the products do not fit.
+
+/* -------------------------------- Rational -------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { numerator: 0, denominator: 1 };
+    pub const ONE: Rational = Rational { numerator: 1, denominator: 1 };
+
+    pub fn new(numerator: i128, denominator: i128) -> Rational {
+        match Rational::checked_new(numerator, denominator) {
+            Some(value) => value,
+            None => panic!("Cannot create rational {numerator}/{denominator}")
+        }
+    }
+
+    // Returns `None` if the denominator is zero, or if normalizing overflows
+    // (which only happens when negating `i128::MIN`).
+
+    pub fn checked_new(numerator: i128, denominator: i128) -> Option<Rational> {
+        if denominator == 0 {
+            return None;
+        }
+
+        let divisor: i128 = gcd(numerator, denominator);
+        let (numerator, denominator): (i128, i128) = (numerator / divisor, denominator / divisor);
+
+        if denominator < 0 {
+            return Some(Rational { numerator: numerator.checked_neg()?, denominator: denominator.checked_neg()? });
+        }
+
+        Some(Rational { numerator, denominator })
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.numerator < 0
+    }
+
+    // To keep intermediate values small, additions use the LCM of both
+    // denominators, and multiplications cancel out common divisors before
+    // multiplying; `checked_new()` then normalizes the result.
+
+    pub fn checked_add(&self, other: &Rational) -> Option<Rational> {
+        let divisor: i128 = gcd(self.denominator, other.denominator);
+        let denominator: i128 = (self.denominator / divisor).checked_mul(other.denominator)?;
+        let left: i128 = self.numerator.checked_mul(denominator / self.denominator)?;
+        let right: i128 = other.numerator.checked_mul(denominator / other.denominator)?;
+        Rational::checked_new(left.checked_add(right)?, denominator)
+    }
+
+    pub fn checked_sub(&self, other: &Rational) -> Option<Rational> {
+        self.checked_add(&other.checked_neg()?)
+    }
+
+    pub fn checked_mul(&self, other: &Rational) -> Option<Rational> {
+        let divisor_a: i128 = gcd(self.numerator, other.denominator);
+        let divisor_b: i128 = gcd(other.numerator, self.denominator);
+        let numerator: i128 = (self.numerator / divisor_a).checked_mul(other.numerator / divisor_b)?;
+        let denominator: i128 = (self.denominator / divisor_b).checked_mul(other.denominator / divisor_a)?;
+        Rational::checked_new(numerator, denominator)
+    }
+
+    pub fn checked_div(&self, other: &Rational) -> Option<Rational> {
+        if other.numerator == 0 {
+            return None;
+        }
+
+        self.checked_mul(&Rational::checked_new(other.denominator, other.numerator)?)
+    }
+
+    pub fn checked_neg(&self) -> Option<Rational> {
+        Some(Rational { numerator: self.numerator.checked_neg()?, denominator: self.denominator })
+    }
+
+    pub fn checked_cmp(&self, other: &Rational) -> Option<Ordering> {
+        let left: i128 = self.numerator.checked_mul(other.denominator)?;
+        let right: i128 = other.numerator.checked_mul(self.denominator)?;
+        Some(left.cmp(&right))
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Rational {
+        Rational { numerator: value, denominator: 1 }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Rational {
+        Rational::from(value as i128)
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+/* -------------------------------- Operators ------------------------------- */
+
+fn overflow(operation: &str, a: &Rational, b: &Rational) -> ! {
+    panic!("Overflow in rational {operation} of {a} and {b}")
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational {
+        self.checked_add(&other).unwrap_or_else(|| overflow("addition", &self, &other))
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        self.checked_sub(&other).unwrap_or_else(|| overflow("subtraction", &self, &other))
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational {
+        self.checked_mul(&other).unwrap_or_else(|| overflow("multiplication", &self, &other))
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, other: Rational) -> Rational {
+        if other.numerator == 0 {
+            panic!("Division of rational {self} by zero");
+        }
+
+        self.checked_div(&other).unwrap_or_else(|| overflow("division", &self, &other))
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        self.checked_neg().unwrap_or_else(|| panic!("Overflow in rational negation of {self}"))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Rational) -> Ordering {
+        self.checked_cmp(other).unwrap_or_else(|| overflow("comparison", self, other))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/* ---------------------------------- Tests --------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_normalizes() {
+        let value: Rational = Rational::new(6, -4);
+        assert_eq!((value.numerator(), value.denominator()), (-3, 2));
+        assert_eq!(Rational::new(-3, -6), Rational::new(1, 2));
+        assert_eq!(Rational::new(0, -5), Rational::ZERO);
+        assert!(Rational::new(6, 3).is_integer());
+    }
+
+    #[test]
+    fn checked_new_rejects_invalid() {
+        assert_eq!(Rational::checked_new(1, 0), None);
+        assert_eq!(Rational::checked_new(1, i128::MIN), None);
+    }
+
+    #[test]
+    fn arithmetic_is_exact() {
+        let third: Rational = Rational::new(1, 3);
+        let sixth: Rational = Rational::new(1, 6);
+        assert_eq!(third + sixth, Rational::new(1, 2));
+        assert_eq!(third - sixth, sixth);
+        assert_eq!(third * Rational::from(3i128), Rational::ONE);
+        assert_eq!(sixth / third, Rational::new(1, 2));
+        assert_eq!(-third, Rational::new(-1, 3));
+    }
+
+    #[test]
+    fn checked_operations_report_overflow() {
+        let max: Rational = Rational::from(i128::MAX);
+        assert_eq!(max.checked_add(&Rational::ONE), None);
+        assert_eq!(max.checked_mul(&Rational::from(2i128)), None);
+        assert_eq!(Rational::ONE.checked_div(&Rational::ZERO), None);
+        assert_eq!(Rational::new(1, 2).checked_cmp(&max), None);
+        assert_eq!(Rational::new(1, 2).checked_cmp(&Rational::new(2, 3)), Some(Ordering::Less));
+    }
+
+    #[test]
+    fn ordering() {
+        let mut values: Vec<Rational> = vec![Rational::new(1, 2), Rational::new(-1, 2), Rational::new(1, 3), Rational::ZERO];
+        values.sort();
+        assert_eq!(values, vec![Rational::new(-1, 2), Rational::ZERO, Rational::new(1, 3), Rational::new(1, 2)]);
+        assert!(Rational::new(-2, 3) < Rational::new(-1, 2));
+    }
+}
@@ -0,0 +1,199 @@
+use std::any::Any;
+use std::fmt;
+
+use crate::solutions::Solution;
+use crate::solutions::parse::{ParseError, ParseResult};
+
+/* -------------------------------- Constants ------------------------------- */
+
+pub const FIRST_DAY: u8 = 1;
+pub const LAST_DAY: u8 = 25;
+
+/* ---------------------------------- Part ---------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Part {
+    A,
+    B
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::A, Part::B];
+
+    pub fn parse(c: char) -> Option<Part> {
+        match c {
+            'a' | 'A' => Some(Part::A),
+            'b' | 'B' => Some(Part::B),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::A => write!(f, "a"),
+            Part::B => write!(f, "b")
+        }
+    }
+}
+
+/* --------------------------------- Status --------------------------------- */
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Status {
+    Solved,
+    Unfinished
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Solved     => write!(f, "solved"),
+            Status::Unfinished => write!(f, "unfinished")
+        }
+    }
+}
+
+/* ---------------------------------- Task ---------------------------------- */
+
+// A task identifies a single puzzle part, and is written on the command line
+// as the two-digit day number followed by the part, e.g. "01a" or "17b".
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Task {
+    pub day: u8,
+    pub part: Part
+}
+
+impl Task {
+    pub fn parse(input: &str) -> Option<Task> {
+        let mut chars: std::str::Chars<'_> = input.chars();
+        let part: Part = chars.next_back().and_then(Part::parse)?;
+        let digits: &str = chars.as_str();
+
+        if digits.len() != 2 || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let day: u8 = digits.parse().ok()?;
+
+        if !(FIRST_DAY ..= LAST_DAY).contains(&day) {
+            return None;
+        }
+
+        Some(Task { day, part })
+    }
+}
+
+impl fmt::Display for Task {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}{}", self.day, self.part)
+    }
+}
+
+/* --------------------------------- Puzzle --------------------------------- */
+
+// Every solver is split into a parse phase, which turns the lines of the input
+// file into a day-specific input type, and a solve phase, which computes the
+// answer from a reference to this parsed input. This allows the runner to time
+// both phases separately, and to reuse the parsed input when profiling the
+// solve phase. Since the input type differs per solver, `Puzzle` passes the
+// parsed input around as `Any`; only the `Entry` that created it unpacks it.
+// Parsers report malformed input through a `ParseError`, rather than panicking.
+// Puzzles are shared with the worker threads that run them (see `watchdog`).
+
+pub type Parser<I> = fn(&Vec<String>) -> ParseResult<I>;
+pub type Solver<I> = fn(&I) -> Solution;
+
+pub trait Puzzle: Send + Sync {
+    fn day(&self) -> u8;
+    fn part(&self) -> Part;
+    fn title(&self) -> &'static str;
+    fn status(&self) -> Status;
+    fn parse(&self, lines: &Vec<String>) -> Result<Box<dyn Any>, ParseError>;
+    fn solve_input(&self, input: &dyn Any) -> Solution;
+
+    fn task(&self) -> Task {
+        Task { day: self.day(), part: self.part() }
+    }
+}
+
+/* ---------------------------------- Entry --------------------------------- */
+
+// The `Puzzle` implementation used for every day registered through the
+// `days!` macro; it wraps the `parse()` and `solve()` functions of a solver
+// module, where `I` is the type of the parsed input of that solver.
+
+pub struct Entry<I> {
+    day: u8,
+    part: Part,
+    title: &'static str,
+    status: Status,
+    parser: Parser<I>,
+    solver: Solver<I>
+}
+
+impl<I: 'static> Entry<I> {
+    pub fn boxed(day: u8, part: Part, title: &'static str, status: Status,
+        parser: Parser<I>, solver: Solver<I>) -> Box<dyn Puzzle> {
+        Box::new(Entry { day, part, title, status, parser, solver })
+    }
+}
+
+impl<I: 'static> Puzzle for Entry<I> {
+    fn day(&self) -> u8 { self.day }
+    fn part(&self) -> Part { self.part }
+    fn title(&self) -> &'static str { self.title }
+    fn status(&self) -> Status { self.status }
+
+    fn parse(&self, lines: &Vec<String>) -> Result<Box<dyn Any>, ParseError> {
+        match (self.parser)(lines) {
+            Ok(input) => Ok(Box::new(input)),
+            Err(error) => Err(error.with_day(self.day))
+        }
+    }
+
+    fn solve_input(&self, input: &dyn Any) -> Solution {
+        match input.downcast_ref::<I>() {
+            Some(input) => (self.solver)(input),
+            None => panic!("Input of task '{}' was not parsed by the same puzzle", self.task())
+        }
+    }
+}
+
+/* -------------------------------- Registry -------------------------------- */
+
+pub struct Registry {
+    puzzles: Vec<Box<dyn Puzzle>>
+}
+
+impl Registry {
+    pub fn new(mut puzzles: Vec<Box<dyn Puzzle>>) -> Registry {
+        puzzles.sort_by_key(|puzzle| puzzle.task());
+        Registry { puzzles }
+    }
+
+    pub fn all(&self) -> impl Iterator<Item = &dyn Puzzle> {
+        self.puzzles.iter().map(|puzzle| puzzle.as_ref())
+    }
+
+    pub fn get(&self, task: &Task) -> Option<&dyn Puzzle> {
+        self.all().find(|puzzle| puzzle.task() == *task)
+    }
+
+    pub fn title(&self, day: u8) -> Option<&'static str> {
+        self.all().find(|puzzle| puzzle.day() == day).map(|puzzle| puzzle.title())
+    }
+
+    pub fn unfinished(&self) -> impl Iterator<Item = &dyn Puzzle> {
+        self.all().filter(|puzzle| puzzle.status() == Status::Unfinished)
+    }
+
+    pub fn missing(&self) -> Vec<Task> {
+        (FIRST_DAY ..= LAST_DAY)
+            .flat_map(|day| Part::ALL.into_iter().map(move |part| Task { day, part }))
+            .filter(|task| self.get(task).is_none())
+            .collect()
+    }
+}
@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use crate::solutions::registry::{FIRST_DAY, LAST_DAY};
+
+// Formats the results of a full run as the Markdown table used in the README.
+// Each cell shows the average runtime in microseconds with thousands separators,
+// "DNF" for parts that were not finished, "---" for parts that do not exist,
+// "n/a" for parts whose input file could not be found, "ERR" for parts that
+// panicked while running, and "TIMEOUT" for parts that did not finish in time.
+
+/* ---------------------------------- Cell ---------------------------------- */
+
+#[derive(Clone, Copy)]
+pub enum Cell {
+    Time(Duration),
+    DidNotFinish,
+    Missing,
+    NoInput,
+    Failed,
+    TimedOut
+}
+
+impl Cell {
+    fn format(&self) -> String {
+        match self {
+            Cell::Time(duration) => group_thousands(duration.as_micros()),
+            Cell::DidNotFinish   => String::from("DNF"),
+            Cell::Missing        => String::from("---"),
+            Cell::NoInput        => String::from("n/a"),
+            Cell::Failed         => String::from("ERR"),
+            Cell::TimedOut       => String::from("TIMEOUT")
+        }
+    }
+
+    // Describes why a task has no time, for the machine-readable output formats.
+
+    pub fn status(&self) -> &'static str {
+        match self {
+            Cell::Time(_)        => "ok",
+            Cell::DidNotFinish   => "dnf",
+            Cell::Missing        => "missing",
+            Cell::NoInput        => "no-input",
+            Cell::Failed         => "failed",
+            Cell::TimedOut       => "timeout"
+        }
+    }
+}
+
+/* --------------------------------- Helpers -------------------------------- */
+
+fn group_thousands(value: u128) -> String {
+    let digits: String = value.to_string();
+    let mut result: String = String::new();
+
+    for (index, c) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            result.push(',');
+        }
+
+        result.push(c);
+    }
+
+    result
+}
+
+/* ---------------------------------- Table --------------------------------- */
+
+pub fn format_table(cells: &[(Cell, Cell)]) -> String {
+    let mut lines: Vec<String> = vec![
+        String::from("| Day  | Part A (μs) | Part B (μs) |"),
+        String::from("| :--: | ----------: | ----------: |")
+    ];
+
+    for (day, (cell_a, cell_b)) in (FIRST_DAY ..= LAST_DAY).zip(cells) {
+        lines.push(format!("|  {:02}  | {:>10}  | {:>10}  |", day, cell_a.format(), cell_b.format()));
+    }
+
+    lines.join("\n")
+}
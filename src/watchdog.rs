@@ -0,0 +1,104 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use crate::solutions::cancel;
+
+// Runs a solver on a worker thread, so that the runner can stop waiting for it
+// once the timeout has passed, report the task as timed out, and continue with
+// the next task. Rust offers no way to forcibly stop a thread, so a worker that
+// times out is abandoned rather than cancelled: it keeps running in the
+// background until it finishes or the process exits. Solvers that take far
+// longer than the timeout (like the exhaustive longest path search of day 23 on
+// a larger maze) therefore still occupy a core for the rest of the run, which
+// may slightly affect the timing of later tasks.
+//
+// A panicking solver drops the sending end of the channel without sending a
+// result, which the receiving end reports as a disconnect. Workers get a larger
+// stack than the default for spawned threads, since some solvers (e.g. days 12
+// and 23) are recursive, and would otherwise overflow where the main thread
+// does not.
+
+/* -------------------------------- Constants ------------------------------- */
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+const STACK_SIZE: usize = 64 * 1024 * 1024;
+
+/* --------------------------------- Outcome -------------------------------- */
+
+pub enum Outcome<T> {
+    Finished(T),
+    Panicked,
+    TimedOut
+}
+
+/* -------------------------------- Watchdog -------------------------------- */
+
+// Runs `work` on a worker thread, and waits for at most `timeout` for it to
+// finish, raising the worker's cancellation flag if it does not; without a
+// timeout, waits until the worker is done.
+
+pub fn run<T, F>(timeout: Option<Duration>, work: F) -> Outcome<T>
+    where T: Send + 'static, F: FnOnce() -> T + Send + 'static {
+    let (sender, receiver): (Sender<T>, Receiver<T>) = channel();
+    let flag: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+    let worker_flag: Arc<AtomicBool> = flag.clone();
+
+    let spawned: std::io::Result<thread::JoinHandle<()>> = thread::Builder::new()
+        .name(String::from("solver"))
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            cancel::install(worker_flag);
+            let _ = sender.send(work());
+        });
+
+    if spawned.is_err() {
+        panic!("Failed to start worker thread");
+    }
+
+    let received: Result<T, RecvTimeoutError> = match timeout {
+        Some(timeout) => receiver.recv_timeout(timeout),
+        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected)
+    };
+
+    match received {
+        Ok(result) => Outcome::Finished(result),
+        Err(RecvTimeoutError::Timeout) => {
+            flag.store(true, Ordering::Relaxed);
+            Outcome::TimedOut
+        },
+        Err(RecvTimeoutError::Disconnected) => Outcome::Panicked
+    }
+}
+
+/* ---------------------------------- Tests --------------------------------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancels_worker_on_timeout() {
+        let (sender, receiver): (Sender<()>, Receiver<()>) = channel();
+
+        let outcome: Outcome<()> = run(Some(Duration::from_millis(10)), move || {
+            while !cancel::requested() {
+                thread::yield_now();
+            }
+
+            let _ = sender.send(());
+        });
+
+        assert!(matches!(outcome, Outcome::TimedOut));
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+
+    #[test]
+    fn finishes_without_cancelling() {
+        let outcome: Outcome<bool> = run(Some(Duration::from_secs(5)), cancel::requested);
+        assert!(matches!(outcome, Outcome::Finished(false)));
+    }
+}